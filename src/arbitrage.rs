@@ -0,0 +1,152 @@
+/*
+
+JDA Options Pricing
+/src/arbitrage.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use chrono::NaiveDate;
+
+// Modules
+use crate::provider::ChainQuote;
+
+
+
+// One expiry of a chain with the discount factor to its expiry
+#[derive(Debug, Clone)]
+pub struct ChainSlice {
+    pub expiration: NaiveDate,
+    pub discount: f64,
+    pub calls: Vec<ChainQuote>,
+    pub puts: Vec<ChainQuote>,
+}
+
+
+
+// Static no-arbitrage relations a chain is checked against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Check {
+    // Call minus put against the stock minus the discounted strike
+    Parity,
+    // Prices move the right way with the strike and by no more than the discounted strike gap
+    Vertical,
+    // Prices are convex in the strike
+    Butterfly,
+    // Longer expiries are worth at least as much at the same strike
+    Calendar,
+}
+
+// A trade the quoted bids and asks would lock a profit into
+#[derive(Debug, Clone)]
+pub struct ArbitrageViolation {
+    pub check: Check,
+    pub expiration: NaiveDate,
+    // Longer expiry of a calendar spread
+    pub far: Option<NaiveDate>,
+    // Side of the chain, parity uses both
+    pub call: Option<bool>,
+    pub strikes: Vec<f64>,
+    // Profit per share locked in at the quoted bids and asks
+    pub size: f64,
+    pub trade: String,
+}
+
+
+
+// Scan a chain for static arbitrage at the quoted bids and asks, reporting violations worth more than the tolerance per share
+    // American exercise loosens the lower parity bound and the vertical spread bounds to the undiscounted strike
+    // Dividends aren't known, so the lower parity bound is only reliable for non-dividend payers
+pub fn check_chain(slices: &[ChainSlice], spot_bid: f64, spot_ask: f64, american: bool, tolerance: f64) -> Vec<ArbitrageViolation> {
+
+    let mut violations: Vec<ArbitrageViolation> = Vec::new();
+    let mut found = |check: Check, slice: &ChainSlice, call: Option<bool>, strikes: Vec<f64>, size: f64, trade: String| {
+        if size > tolerance {
+            violations.push(ArbitrageViolation { check, expiration: slice.expiration, far: None, call, strikes, size, trade });
+        }
+    };
+
+    for slice in slices.iter() {
+
+        // Strike gaps are discounted for European options only, American ones can be exercised early
+        let gap_discount: f64 = if american { 1.0 } else { slice.discount };
+        let calls: Vec<(f64, f64, f64)> = quoted(&slice.calls);
+        let puts: Vec<(f64, f64, f64)> = quoted(&slice.puts);
+
+        // Put-call parity, S - K <= C - P <= S - K * D for American options and C - P = S - K * D for European ones
+        for (strike, call_bid, call_ask) in calls.iter() {
+            let Some((_, put_bid, put_ask)) = puts.iter().find(|p| p.0 == *strike) else { continue; };
+            found(Check::Parity, slice, None, vec![*strike], call_bid - put_ask - (spot_ask - strike * slice.discount),
+                "Sell the call, buy the put and buy the stock".to_string());
+            let lower: f64 = if american { spot_bid - strike } else { spot_bid - strike * slice.discount };
+            found(Check::Parity, slice, None, vec![*strike], lower - (call_ask - put_bid),
+                "Buy the call, sell the put and short the stock".to_string());
+        }
+
+        // Neighbouring strikes, calls fall and puts rise with the strike by no more than the strike gap
+        for pair in calls.windows(2) {
+            let ((low, low_bid, low_ask), (high, high_bid, high_ask)) = (pair[0], pair[1]);
+            found(Check::Vertical, slice, Some(true), vec![low, high], high_bid - low_ask,
+                format!("Buy the {} call and sell the {} call", low, high));
+            found(Check::Vertical, slice, Some(true), vec![low, high], low_bid - high_ask - (high - low) * gap_discount,
+                format!("Sell the {} call and buy the {} call", low, high));
+        }
+        for pair in puts.windows(2) {
+            let ((low, low_bid, low_ask), (high, high_bid, high_ask)) = (pair[0], pair[1]);
+            found(Check::Vertical, slice, Some(false), vec![low, high], low_bid - high_ask,
+                format!("Sell the {} put and buy the {} put", low, high));
+            found(Check::Vertical, slice, Some(false), vec![low, high], high_bid - low_ask - (high - low) * gap_discount,
+                format!("Buy the {} put and sell the {} put", low, high));
+        }
+
+        // Neighbouring strike triples, the wings weighted to match the body's strike must cost at least the body
+        for (call, quotes) in [(true, &calls), (false, &puts)] {
+            for triple in quotes.windows(3) {
+                let ((low, _, low_ask), (middle, middle_bid, _), (high, _, high_ask)) = (triple[0], triple[1], triple[2]);
+                let weight: f64 = (high - middle) / (high - low);
+                found(Check::Butterfly, slice, Some(call), vec![low, middle, high], middle_bid - weight * low_ask - (1.0 - weight) * high_ask,
+                    format!("Buy {:.3} of the {} and {:.3} of the {}, sell one {}", weight, low, 1.0 - weight, high, middle));
+            }
+        }
+    }
+
+    // Consecutive expiries at the same strike, European puts can be worth less further out so are left out
+    let mut ordered: Vec<&ChainSlice> = slices.iter().collect();
+    ordered.sort_by_key(|slice| slice.expiration);
+    for pair in ordered.windows(2) {
+        let (near, far) = (pair[0], pair[1]);
+        for (call, near_quotes, far_quotes) in [(true, &near.calls, &far.calls), (false, &near.puts, &far.puts)] {
+            if !call && !american { continue; }
+            let far_quotes: Vec<(f64, f64, f64)> = quoted(far_quotes);
+            for (strike, near_bid, _) in quoted(near_quotes) {
+                let Some((_, _, far_ask)) = far_quotes.iter().find(|q| q.0 == strike) else { continue; };
+                let size: f64 = near_bid - far_ask;
+                if size > tolerance {
+                    violations.push(ArbitrageViolation { check: Check::Calendar, expiration: near.expiration, far: Some(far.expiration), call: Some(call), strikes: vec![strike], size,
+                        trade: format!("Sell the {} and buy the {}", near.expiration, far.expiration) });
+                }
+            }
+        }
+    }
+
+    return violations;
+}
+
+
+
+// (strike, bid, ask) of every contract with a two-sided quote, in strike order
+fn quoted(quotes: &[ChainQuote]) -> Vec<(f64, f64, f64)> {
+
+    let mut quoted: Vec<(f64, f64, f64)> = quotes.iter()
+        .filter_map(|q| match (q.bid, q.ask) {
+            (Some(bid), Some(ask)) if bid >= 0.0 && ask >= bid && ask > 0.0 => Some((q.strike, bid, ask)),
+            _ => None,
+        })
+        .collect();
+    quoted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    return quoted;
+}
@@ -0,0 +1,302 @@
+/*
+
+JDA Options Pricing
+/src/asian.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::black_scholes::generalized_black_scholes;
+use crate::monte_carlo::{MonteCarloEstimate, monte_carlo, monte_carlo_control_variate};
+
+
+
+// Struct to hold the averaging window of an Asian option
+    // Fixings that have already happened are known prices, the rest are times (in years from today) still to come
+#[derive(Debug, Clone)]
+pub struct Averaging {
+    pub times: Vec<f64>,
+    pub past: Vec<f64>,
+}
+
+impl Averaging {
+
+    // Build a window of equally spaced future fixings from the first fixing through to expiry
+    pub fn equally_spaced(first: f64, expiry: f64, fixings: u32, past: Vec<f64>) -> Averaging {
+
+        let times = if fixings <= 1 {
+            vec![expiry]
+        } else {
+            let spacing: f64 = (expiry - first) / ((fixings - 1) as f64);
+            (0..fixings).map(|i| first + spacing * (i as f64)).collect()
+        };
+
+        return Averaging { times, past };
+    }
+
+    // Total number of fixings in the average, past and future
+    pub fn count(&self) -> f64 {
+        return (self.times.len() + self.past.len()) as f64;
+    }
+
+    // Share of the average that is still to be fixed
+    pub fn future_weight(&self) -> f64 {
+        return (self.times.len() as f64) / self.count();
+    }
+
+    // Strike the average of the remaining fixings has to beat once the known fixings are taken out
+    pub fn future_strike(&self, strike: f64) -> f64 {
+        let past_sum: f64 = self.past.iter().sum();
+        return (self.count() * strike - past_sum) / (self.times.len() as f64);
+    }
+
+    // Arithmetic average of a set of future fixings together with the known ones
+    pub fn arithmetic_average(&self, future: &[f64]) -> f64 {
+        return (self.past.iter().sum::<f64>() + future.iter().sum::<f64>()) / self.count();
+    }
+
+    // Geometric average of a set of future fixings together with the known ones
+    pub fn geometric_average(&self, future: &[f64]) -> f64 {
+        let log_sum: f64 = self.past.iter().map(|p| p.ln()).sum::<f64>() + future.iter().map(|p| p.ln()).sum::<f64>();
+        return (log_sum / self.count()).exp();
+    }
+}
+
+
+
+// Price a continuously averaged geometric Asian option from today to expiry (Kemna-Vorst)
+pub fn geometric_asian_continuous(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    // The geometric average is lognormal with a third of the variance and a reduced drift
+    let carry: f64 = 0.5 * (rate - volatility.powi(2) / 6.0);
+    let average_volatility: f64 = volatility / 3.0_f64.sqrt();
+
+    return generalized_black_scholes(spot, strike, time, rate, carry, average_volatility, call);
+}
+
+
+
+// Price a discretely sampled geometric Asian option, allowing for fixings that have already been set
+pub fn geometric_asian(spot: f64, strike: f64, expiry: f64, rate: f64, volatility: f64, averaging: &Averaging, call: bool) -> f64 {
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+    let n: f64 = averaging.count();
+
+    // The log of the geometric average is normal, find its mean and variance
+    let mut mean: f64 = averaging.past.iter().map(|p| p.ln()).sum::<f64>() / n;
+    let mut variance: f64 = 0.0;
+
+    for ti in averaging.times.iter() {
+        mean += (spot.ln() + (carry - 0.5 * volatility.powi(2)) * ti) / n;
+        for tj in averaging.times.iter() {
+            variance += volatility.powi(2) * ti.min(*tj) / n.powi(2);
+        }
+    }
+
+    let discount: f64 = (-1.0 * rate * expiry).exp();
+    let forward: f64 = (mean + 0.5 * variance).exp();
+
+    // Everything is already fixed, so the payoff is known
+    if variance <= 0.0 {
+        return discount * if call { (forward - strike).max(0.0) } else { (strike - forward).max(0.0) };
+    }
+
+    // Black formula on the lognormal average
+    let standard_normal = Normal::standard();
+    let d1: f64 = ((forward / strike).ln() + 0.5 * variance) / variance.sqrt();
+    let d2: f64 = d1 - variance.sqrt();
+
+    if call {
+        return discount * (forward * standard_normal.cdf(d1) - strike * standard_normal.cdf(d2));
+    } else {
+        return discount * (strike * standard_normal.cdf(-1.0 * d2) - forward * standard_normal.cdf(-1.0 * d1));
+    }
+}
+
+
+
+// Price an arithmetic Asian option with the Turnbull-Wakeman approximation
+    // The remaining average is treated as continuous from the first remaining fixing to expiry and matched to a lognormal
+pub fn turnbull_wakeman(spot: f64, strike: f64, expiry: f64, rate: f64, volatility: f64, averaging: &Averaging, call: bool) -> f64 {
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+
+    let start: f64 = averaging.times.first().copied().unwrap_or(expiry).min(expiry);
+    let window: f64 = expiry - start;
+    let sigma2: f64 = volatility.powi(2);
+
+    // First two moments of the continuous average over [start, expiry], relative to spot
+    let (m1, m2): (f64, f64) = if window <= 1e-12 {
+        ((carry * expiry).exp(), ((2.0 * carry + sigma2) * expiry).exp())
+    } else if carry.abs() < 1e-12 {
+        let m1: f64 = 1.0;
+        let m2: f64 = 2.0 * (sigma2 * expiry).exp() / (sigma2.powi(2) * window.powi(2)) - 2.0 * (sigma2 * start).exp() * (1.0 + sigma2 * window) / (sigma2.powi(2) * window.powi(2));
+        (m1, m2)
+    } else {
+        let m1: f64 = ((carry * expiry).exp() - (carry * start).exp()) / (carry * window);
+        let m2: f64 = 2.0 * ((2.0 * carry + sigma2) * expiry).exp() / ((carry + sigma2) * (2.0 * carry + sigma2) * window.powi(2))
+            + 2.0 * ((2.0 * carry + sigma2) * start).exp() / (carry * window.powi(2)) * (1.0 / (2.0 * carry + sigma2) - ((carry * window).exp()) / (carry + sigma2));
+        (m1, m2)
+    };
+
+    // Equivalent carry and volatility of a lognormal with the same moments
+    let average_carry: f64 = m1.ln() / expiry;
+    let average_volatility: f64 = (m2.ln() / expiry - 2.0 * average_carry).max(0.0).sqrt();
+
+    return partial_average(strike, expiry, rate, averaging, call, m1 * spot, |future_strike| {
+        generalized_black_scholes(spot, future_strike, expiry, rate, average_carry, average_volatility, call)
+    });
+}
+
+
+
+// Price an arithmetic Asian option with Levy's approximation
+    // The discrete average, including known fixings, is matched to a lognormal with the same first two moments
+pub fn levy(spot: f64, strike: f64, expiry: f64, rate: f64, volatility: f64, averaging: &Averaging, call: bool) -> f64 {
+
+    let (m1, m2) = arithmetic_moments(spot, rate, volatility, averaging);
+    let discount: f64 = (-1.0 * rate * expiry).exp();
+    let variance: f64 = (m2 / m1.powi(2)).ln();
+
+    // Everything is already fixed, so the payoff is known
+    if variance <= 1e-14 {
+        return discount * if call { (m1 - strike).max(0.0) } else { (strike - m1).max(0.0) };
+    }
+
+    let standard_normal = Normal::standard();
+    let d1: f64 = ((m1 / strike).ln() + 0.5 * variance) / variance.sqrt();
+    let d2: f64 = d1 - variance.sqrt();
+
+    if call {
+        return discount * (m1 * standard_normal.cdf(d1) - strike * standard_normal.cdf(d2));
+    } else {
+        return discount * (strike * standard_normal.cdf(-1.0 * d2) - m1 * standard_normal.cdf(-1.0 * d1));
+    }
+}
+
+
+
+// Price an arithmetic Asian option with Curran's approximation, conditioning on the geometric average of the remaining fixings
+pub fn curran(spot: f64, strike: f64, expiry: f64, rate: f64, volatility: f64, averaging: &Averaging, call: bool) -> f64 {
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+    let times = &averaging.times;
+    let n: f64 = times.len() as f64;
+    let sigma2: f64 = volatility.powi(2);
+
+    // Expected forward of the remaining average, used to move between calls and puts
+    let forward: f64 = times.iter().map(|t| spot * (carry * t).exp()).sum::<f64>() / n;
+
+    return partial_average(strike, expiry, rate, averaging, call, forward, |future_strike| {
+
+        let standard_normal = Normal::standard();
+        let discount: f64 = (-1.0 * rate * expiry).exp();
+
+        // Log-price means and variances at each fixing, and their covariance with the log geometric average
+        let mu: Vec<f64> = times.iter().map(|t| spot.ln() + (carry - 0.5 * sigma2) * t).collect();
+        let var: Vec<f64> = times.iter().map(|t| sigma2 * t).collect();
+        let cov: Vec<f64> = times.iter().map(|ti| times.iter().map(|tj| sigma2 * ti.min(*tj)).sum::<f64>() / n).collect();
+
+        let mu_g: f64 = mu.iter().sum::<f64>() / n;
+        let var_g: f64 = cov.iter().sum::<f64>() / n;
+        let sigma_g: f64 = var_g.sqrt();
+
+        // Curran's adjusted strike for the conditional lower bound
+        let conditional_sum: f64 = (0..times.len())
+            .map(|i| (mu[i] + cov[i] * (future_strike.ln() - mu_g) / var_g + 0.5 * (var[i] - cov[i].powi(2) / var_g)).exp())
+            .sum::<f64>() / n;
+        let adjusted_strike: f64 = 2.0 * future_strike - conditional_sum;
+
+        // If the adjusted strike collapses the call is effectively certain to be exercised
+        let call_value: f64 = if adjusted_strike <= 0.0 {
+            discount * (forward - future_strike)
+        } else {
+            let x: f64 = (mu_g - adjusted_strike.ln()) / sigma_g;
+            let asset: f64 = (0..times.len())
+                .map(|i| (mu[i] + 0.5 * var[i]).exp() * standard_normal.cdf(x + cov[i] / sigma_g))
+                .sum::<f64>() / n;
+            discount * (asset - future_strike * standard_normal.cdf(x))
+        };
+
+        // Puts follow from put-call parity on the average
+        if call { call_value } else { call_value - discount * (forward - future_strike) }
+    });
+}
+
+
+
+// Price an arithmetic Asian option by Monte Carlo, using the discrete geometric Asian as a control variate
+pub fn monte_carlo_asian(spot: f64, strike: f64, expiry: f64, rate: f64, volatility: f64, averaging: &Averaging, paths: u32, seed: Option<u64>, call: bool) -> MonteCarloEstimate {
+
+    let payoff = |average: f64| if call { (average - strike).max(0.0) } else { (strike - average).max(0.0) };
+    let control_value: f64 = geometric_asian(spot, strike, expiry, rate, volatility, averaging, call);
+
+    return monte_carlo_control_variate(spot, rate, rate, volatility, &averaging.times, expiry, paths, seed,
+        |path| payoff(averaging.arithmetic_average(path)),
+        |path| payoff(averaging.geometric_average(path)),
+        control_value,
+    );
+}
+
+
+
+// Price an arithmetic Asian option by plain Monte Carlo, to show the effect of the control variate
+pub fn monte_carlo_asian_plain(spot: f64, strike: f64, expiry: f64, rate: f64, volatility: f64, averaging: &Averaging, paths: u32, seed: Option<u64>, call: bool) -> MonteCarloEstimate {
+
+    let payoff = |average: f64| if call { (average - strike).max(0.0) } else { (strike - average).max(0.0) };
+
+    return monte_carlo(spot, rate, rate, volatility, &averaging.times, expiry, paths, seed, |path| payoff(averaging.arithmetic_average(path)));
+}
+
+
+
+// First two moments of the full arithmetic average (known and future fixings)
+fn arithmetic_moments(spot: f64, rate: f64, volatility: f64, averaging: &Averaging) -> (f64, f64) {
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+    let n: f64 = averaging.count();
+    let past_sum: f64 = averaging.past.iter().sum();
+
+    let future_sum: f64 = averaging.times.iter().map(|t| spot * (carry * t).exp()).sum();
+
+    let mut future_square: f64 = 0.0;
+    for ti in averaging.times.iter() {
+        for tj in averaging.times.iter() {
+            future_square += spot.powi(2) * (carry * (ti + tj) + volatility.powi(2) * ti.min(*tj)).exp();
+        }
+    }
+
+    let m1: f64 = (past_sum + future_sum) / n;
+    let m2: f64 = (past_sum.powi(2) + 2.0 * past_sum * future_sum + future_square) / n.powi(2);
+
+    return (m1, m2);
+}
+
+
+
+// Price an option on the full average from a pricer for the average of the remaining fixings
+    // The known fixings rescale the payoff and shift the strike, and can guarantee exercise outright
+fn partial_average<F>(strike: f64, expiry: f64, rate: f64, averaging: &Averaging, call: bool, future_forward: f64, price_future: F) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let weight: f64 = averaging.future_weight();
+    let future_strike: f64 = averaging.future_strike(strike);
+
+    if future_strike <= 0.0 {
+        let discount: f64 = (-1.0 * rate * expiry).exp();
+        return if call { discount * weight * (future_forward - future_strike) } else { 0.0 };
+    }
+
+    return weight * price_future(future_strike);
+}
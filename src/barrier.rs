@@ -0,0 +1,265 @@
+/*
+
+JDA Options Pricing
+/src/barrier.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::binomial::{Exercise, binomial, backward_induction};
+use crate::black_scholes::generalized_black_scholes;
+
+
+
+// Broadie-Glasserman-Kou constant, -zeta(1/2) / sqrt(2 * pi)
+const BGK_BETA: f64 = 0.5826;
+
+// Number of terms either side of zero used in the Ikeda-Kunitomo series (converges very quickly)
+const IKEDA_KUNITOMO_TERMS: i32 = 5;
+
+// Most the barrier tree may refine the requested steps by to put a layer of nodes on the barrier
+const MAX_STEP_MULTIPLE: f64 = 10.0;
+
+
+
+// The four single barrier directions, each of which is priced as either a call or a put
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum BarrierType {
+    DownIn,
+    DownOut,
+    UpIn,
+    UpOut,
+}
+
+impl BarrierType {
+
+    // Whether the option comes alive (rather than dies) when the barrier is hit
+    pub fn knock_in(&self) -> bool {
+        return matches!(self, BarrierType::DownIn | BarrierType::UpIn);
+    }
+
+    // Whether the barrier sits below the spot
+    pub fn down(&self) -> bool {
+        return matches!(self, BarrierType::DownIn | BarrierType::DownOut);
+    }
+
+    // Whether a stock price has touched the barrier
+    pub fn breached(&self, price: f64, barrier: f64) -> bool {
+        if self.down() { price <= barrier } else { price >= barrier }
+    }
+}
+
+
+
+// Price a continuously monitored single barrier option using the Reiner-Rubinstein closed forms
+    // Knock-in rebates are paid at maturity if the barrier is never hit, knock-out rebates are paid as soon as it is hit
+pub fn barrier_option(spot: f64, strike: f64, barrier: f64, rebate: f64, time: f64, rate: f64, volatility: f64, barrier_type: BarrierType, call: bool) -> f64 {
+
+    // If the barrier has already been hit the option is either a vanilla or just the rebate
+    if barrier_type.breached(spot, barrier) {
+        if barrier_type.knock_in() {
+            return generalized_black_scholes(spot, strike, time, rate, rate, volatility, call);
+        } else {
+            return rebate;
+        }
+    }
+
+    // Standard normal distribution
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+
+    // Direction flags used throughout Haug's presentation of the formulas
+    let eta: f64 = if barrier_type.down() { 1.0 } else { -1.0 };
+    let phi: f64 = if call { 1.0 } else { -1.0 };
+
+    let vol_sqrt_t: f64 = volatility * time.sqrt();
+    let mu: f64 = (carry - 0.5 * volatility.powi(2)) / volatility.powi(2);
+    let lambda: f64 = (mu.powi(2) + 2.0 * rate / volatility.powi(2)).sqrt();
+
+    let x1: f64 = (spot / strike).ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+    let x2: f64 = (spot / barrier).ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+    let y1: f64 = (barrier.powi(2) / (spot * strike)).ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+    let y2: f64 = (barrier / spot).ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+    let z: f64 = (barrier / spot).ln() / vol_sqrt_t + lambda * vol_sqrt_t;
+
+    let carry_discount: f64 = ((carry - rate) * time).exp();
+    let discount: f64 = (-1.0 * rate * time).exp();
+    let ratio: f64 = barrier / spot;
+
+    // The building blocks the eight barrier types are assembled from
+    let a: f64 = phi * spot * carry_discount * n(phi * x1) - phi * strike * discount * n(phi * x1 - phi * vol_sqrt_t);
+    let b: f64 = phi * spot * carry_discount * n(phi * x2) - phi * strike * discount * n(phi * x2 - phi * vol_sqrt_t);
+    let c: f64 = phi * spot * carry_discount * ratio.powf(2.0 * (mu + 1.0)) * n(eta * y1) - phi * strike * discount * ratio.powf(2.0 * mu) * n(eta * y1 - eta * vol_sqrt_t);
+    let d: f64 = phi * spot * carry_discount * ratio.powf(2.0 * (mu + 1.0)) * n(eta * y2) - phi * strike * discount * ratio.powf(2.0 * mu) * n(eta * y2 - eta * vol_sqrt_t);
+    let e: f64 = rebate * discount * (n(eta * x2 - eta * vol_sqrt_t) - ratio.powf(2.0 * mu) * n(eta * y2 - eta * vol_sqrt_t));
+    let f: f64 = rebate * (ratio.powf(mu + lambda) * n(eta * z) + ratio.powf(mu - lambda) * n(eta * z - 2.0 * eta * lambda * vol_sqrt_t));
+
+    // Combine the blocks based on the barrier type and whether the strike is above the barrier
+    let above: bool = strike > barrier;
+
+    match (barrier_type, call) {
+        (BarrierType::DownIn, true) => if above { c + e } else { a - b + d + e },
+        (BarrierType::UpIn, true) => if above { a + e } else { b - c + d + e },
+        (BarrierType::DownIn, false) => if above { b - c + d + e } else { a + e },
+        (BarrierType::UpIn, false) => if above { a - b + d + e } else { c + e },
+        (BarrierType::DownOut, true) => if above { a - c + f } else { b - d + f },
+        (BarrierType::UpOut, true) => if above { f } else { a - b + c - d + f },
+        (BarrierType::DownOut, false) => if above { a - b + c - d + f } else { f },
+        (BarrierType::UpOut, false) => if above { b - d + f } else { a - c + f },
+    }
+}
+
+
+
+// Shift a continuously monitored barrier so the closed forms approximate discrete monitoring every monitoring_dt years (Broadie-Glasserman-Kou)
+    // Down barriers move further down and up barriers further up, since discrete monitoring makes a hit less likely
+pub fn discrete_barrier(barrier: f64, volatility: f64, monitoring_dt: f64, down: bool) -> f64 {
+    let shift: f64 = BGK_BETA * volatility * monitoring_dt.sqrt();
+    return if down { barrier * (-1.0 * shift).exp() } else { barrier * shift.exp() };
+}
+
+
+
+// Price a continuously monitored double barrier option with flat barriers using the Ikeda-Kunitomo series
+    // Knock-ins are priced by in-out parity against the vanilla
+pub fn double_barrier_option(spot: f64, strike: f64, lower: f64, upper: f64, time: f64, rate: f64, volatility: f64, knock_in: bool, call: bool) -> f64 {
+
+    let vanilla: f64 = generalized_black_scholes(spot, strike, time, rate, rate, volatility, call);
+
+    // If either barrier has already been hit the knock-out is dead
+    if spot <= lower || spot >= upper {
+        return if knock_in { vanilla } else { 0.0 };
+    }
+
+    // Standard normal distribution
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+
+    let vol_sqrt_t: f64 = volatility * time.sqrt();
+    let drift: f64 = (carry + 0.5 * volatility.powi(2)) * time;
+
+    // With flat barriers the curvature terms vanish and mu1 = mu3, mu2 = 0
+    let mu1: f64 = 2.0 * carry / volatility.powi(2) + 1.0;
+    let mu3: f64 = mu1;
+
+    // The payoff is only collected between the barriers, so clip the strike to the live region
+    let low: f64 = if call { strike.max(lower) } else { lower };
+    let high: f64 = if call { upper } else { strike.min(upper) };
+
+    if low >= high {
+        return if knock_in { vanilla } else { 0.0 };
+    }
+
+    let mut asset_sum: f64 = 0.0;
+    let mut cash_sum: f64 = 0.0;
+
+    for i in -IKEDA_KUNITOMO_TERMS..=IKEDA_KUNITOMO_TERMS {
+
+        let k: f64 = i as f64;
+
+        // Images of the spot reflected through both barriers
+        let direct: f64 = spot * (upper / lower).powf(2.0 * k);
+        let reflected: f64 = lower.powf(2.0 * k + 2.0) / (spot * upper.powf(2.0 * k));
+
+        let d1: f64 = ((direct / low).ln() + drift) / vol_sqrt_t;
+        let d2: f64 = ((direct / high).ln() + drift) / vol_sqrt_t;
+        let d3: f64 = ((reflected / low).ln() + drift) / vol_sqrt_t;
+        let d4: f64 = ((reflected / high).ln() + drift) / vol_sqrt_t;
+
+        let direct_weight: f64 = (upper / lower).powf(k);
+        let reflected_weight: f64 = lower.powf(k + 1.0) / (upper.powf(k) * spot);
+
+        asset_sum += direct_weight.powf(mu1) * (n(d1) - n(d2)) - reflected_weight.powf(mu3) * (n(d3) - n(d4));
+        cash_sum += direct_weight.powf(mu1 - 2.0) * (n(d1 - vol_sqrt_t) - n(d2 - vol_sqrt_t)) - reflected_weight.powf(mu3 - 2.0) * (n(d3 - vol_sqrt_t) - n(d4 - vol_sqrt_t));
+    }
+
+    let asset_leg: f64 = spot * ((carry - rate) * time).exp() * asset_sum;
+    let cash_leg: f64 = strike * (-1.0 * rate * time).exp() * cash_sum;
+
+    let knock_out: f64 = if call { asset_leg - cash_leg } else { cash_leg - asset_leg }.max(0.0);
+
+    return if knock_in { vanilla - knock_out } else { knock_out };
+}
+
+
+
+// Price a single barrier option (American, European or Bermudan) on a binomial tree whose nodes are aligned to the barrier
+    // The step count is adjusted up from the requested steps so a layer of nodes sits exactly on the barrier, which removes most of the lattice's barrier error
+pub fn binomial_barrier(spot: f64, strike: f64, barrier: f64, rebate: f64, time: f64, rate: f64, volatility: f64, steps: u32, barrier_type: BarrierType, call: bool, exercise: &Exercise) -> Result<f64, String> {
+
+    // If the barrier has already been hit the option is either a vanilla or just the rebate
+    if barrier_type.breached(spot, barrier) {
+        if barrier_type.knock_in() {
+            return Ok(binomial(spot, strike, time, rate, volatility, steps, call, exercise));
+        } else {
+            return Ok(rebate);
+        }
+    }
+
+    // Number of log-price moves between the spot and barrier, rounded up so the tree is at least as fine as requested
+    let distance: f64 = (barrier / spot).ln().abs();
+    let requested_move: f64 = volatility * (time / (steps as f64)).sqrt();
+    let moves: f64 = (distance / requested_move).ceil().max(1.0);
+
+    // Choose the number of steps whose natural move size best fits the barrier, then fix the move size to hit it exactly
+        // A barrier very close to the spot needs a tiny move, so give up rather than build a tree of unbounded size
+    let aligned: f64 = (time * (moves * volatility / distance).powi(2)).round().max(1.0);
+    if aligned > (steps as f64) * MAX_STEP_MULTIPLE {
+        return Err(format!("the barrier is too close to the spot to align a tree within {} steps, raise the steps or widen the barrier", ((steps as f64) * MAX_STEP_MULTIPLE) as u64));
+    }
+    let aligned_steps: u32 = aligned as u32;
+    let u: f64 = (distance / moves).exp();
+    let dt: f64 = time / (aligned_steps as f64);
+
+    // Nodes on the barrier layer land on it only up to rounding, so nudge the barrier outwards slightly
+    let barrier: f64 = if barrier_type.down() { barrier * (1.0 + 1e-9) } else { barrier * (1.0 - 1e-9) };
+
+    let intrinsic = |price: f64| if call { (price - strike).max(0.0) } else { (strike - price).max(0.0) };
+
+    // Knock-outs are worth the rebate on and beyond the barrier, otherwise a (possibly American) vanilla
+    if !barrier_type.knock_in() {
+        let payoff = |price: f64| if barrier_type.breached(price, barrier) { rebate } else { intrinsic(price) };
+        return backward_induction(spot, time, rate, aligned_steps, u, payoff, |step, _i, price, expected_value| {
+            if barrier_type.breached(price, barrier) {
+                rebate
+            } else if exercise.allowed(step, dt) {
+                expected_value.max(intrinsic(price))
+            } else {
+                expected_value
+            }
+        });
+    }
+
+    // Knock-ins turn into a vanilla on the barrier, so first roll back the vanilla on the same tree
+        // Paths only cross into the breached region through the node nearest the barrier, so only that node's vanilla value is kept at each step
+    let mut on_barrier: Vec<Option<f64>> = vec![None; aligned_steps as usize];
+
+    backward_induction(spot, time, rate, aligned_steps, u, intrinsic, |step, _i, price, expected_value| {
+        let value = if exercise.allowed(step, dt) { expected_value.max(intrinsic(price)) } else { expected_value };
+        // Nodes come lowest price first, so the nearest breached node is the last one seen for a down barrier and the first for an up barrier
+        if barrier_type.breached(price, barrier) && (barrier_type.down() || on_barrier[step as usize].is_none()) {
+            on_barrier[step as usize] = Some(value);
+        }
+        value
+    })?;
+
+    // Then roll back the not-yet-knocked-in claim, which pays the rebate if it never comes alive
+        // Before knock-in the holder has nothing to exercise, so there is no early exercise check
+    let payoff = |price: f64| if barrier_type.breached(price, barrier) { intrinsic(price) } else { rebate };
+    return backward_induction(spot, time, rate, aligned_steps, u, payoff, |step, _i, price, expected_value| {
+        if barrier_type.breached(price, barrier) { on_barrier[step as usize].unwrap_or(expected_value) } else { expected_value }
+    });
+}
@@ -0,0 +1,108 @@
+/*
+
+JDA Options Pricing
+/src/basket.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::monte_carlo::{MonteCarloEstimate, monte_carlo_terminal};
+
+
+
+// Struct to hold a weighted basket of correlated underlyings
+    // Carry is the risk free rate for a stock paying no dividends and zero for a futures contract
+#[derive(Debug, Clone)]
+pub struct Basket {
+    pub spots: Vec<f64>,
+    pub volatilities: Vec<f64>,
+    pub weights: Vec<f64>,
+    pub carries: Vec<f64>,
+    pub correlation: Vec<Vec<f64>>,
+}
+
+impl Basket {
+
+    // Build a basket, filling the correlation matrix from either one pairwise correlation or the upper triangle in row order
+    pub fn new(spots: Vec<f64>, volatilities: Vec<f64>, weights: Vec<f64>, carries: Vec<f64>, correlations: &[f64]) -> Result<Basket, String> {
+
+        let size: usize = spots.len();
+        if volatilities.len() != size || weights.len() != size || carries.len() != size {
+            return Err("every asset in the basket needs a spot, volatility and weight".to_string());
+        }
+
+        let pairs: usize = size * (size - 1) / 2;
+        if correlations.len() != 1 && correlations.len() != pairs {
+            return Err(format!("expected 1 or {} correlations for {} assets", pairs, size));
+        }
+
+        // Position of the pair (i, j) with i < j when the upper triangle is listed row by row
+        let pair = |i: usize, j: usize| i * size - i * (i + 1) / 2 + (j - i - 1);
+
+        let correlation: Vec<Vec<f64>> = (0..size).map(|i| (0..size).map(|j| {
+            if i == j {
+                1.0
+            } else if correlations.len() == 1 {
+                correlations[0]
+            } else {
+                correlations[pair(i.min(j), i.max(j))]
+            }
+        }).collect()).collect();
+
+        return Ok(Basket { spots, volatilities, weights, carries, correlation });
+    }
+
+    // Forward price of each underlying at a given time
+    pub fn forwards(&self, time: f64) -> Vec<f64> {
+        return self.spots.iter().zip(&self.carries).map(|(s, b)| s * (b * time).exp()).collect();
+    }
+}
+
+
+
+// Price an option on the weighted sum of the underlyings by matching the basket's first two moments to a lognormal
+pub fn basket_moment_matching(basket: &Basket, strike: f64, time: f64, rate: f64, call: bool) -> f64 {
+
+    let forwards: Vec<f64> = basket.forwards(time);
+    let size: usize = forwards.len();
+
+    // Mean and second moment of the basket at expiry
+    let m1: f64 = (0..size).map(|i| basket.weights[i] * forwards[i]).sum();
+    let mut m2: f64 = 0.0;
+    for i in 0..size {
+        for j in 0..size {
+            m2 += basket.weights[i] * basket.weights[j] * forwards[i] * forwards[j]
+                * (basket.correlation[i][j] * basket.volatilities[i] * basket.volatilities[j] * time).exp();
+        }
+    }
+
+    // Black-76 on the matched lognormal
+    let standard_normal = Normal::standard();
+    let variance: f64 = (m2 / m1.powi(2)).ln();
+    let d1: f64 = ((m1 / strike).ln() + 0.5 * variance) / variance.sqrt();
+    let d2: f64 = d1 - variance.sqrt();
+    let discount: f64 = (-1.0 * rate * time).exp();
+
+    if call {
+        return discount * (m1 * standard_normal.cdf(d1) - strike * standard_normal.cdf(d2));
+    } else {
+        return discount * (strike * standard_normal.cdf(-1.0 * d2) - m1 * standard_normal.cdf(-1.0 * d1));
+    }
+}
+
+
+
+// Price the same basket option by correlated Monte Carlo
+pub fn monte_carlo_basket(basket: &Basket, strike: f64, time: f64, rate: f64, paths: u32, seed: Option<u64>, call: bool) -> Result<MonteCarloEstimate, String> {
+
+    return monte_carlo_terminal(&basket.spots, &basket.carries, &basket.volatilities, &basket.correlation, rate, time, paths, seed, |prices| {
+        let value: f64 = prices.iter().zip(&basket.weights).map(|(p, w)| p * w).sum();
+        if call { (value - strike).max(0.0) } else { (strike - value).max(0.0) }
+    });
+}
@@ -8,27 +8,115 @@ JohnDavid Abe
 
 
 
-// Take in option data and return the price based on the binomial model for pricing American or European options
-pub fn binomial(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, steps: u32, call: bool, american: bool) -> f64 {
-    
+// When an option can be exercised before maturity
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exercise {
+
+    // Only at maturity
+    European,
+
+    // At any time
+    American,
+
+    // Only on the given dates (in years from today)
+    Bermudan(Vec<f64>),
+
+    // At any time after a lockout period (in years from today), such as a vesting cliff
+    Lockout(f64),
+}
+
+impl Exercise {
+
+    // Whether the holder may exercise at a given step of a tree with step size dt
+        // Bermudan dates are snapped to the nearest step
+    pub fn allowed(&self, step: u32, dt: f64) -> bool {
+        match self {
+            Exercise::European => false,
+            Exercise::American => true,
+            Exercise::Bermudan(dates) => dates.iter().any(|date| (date / dt).round() as u32 == step),
+            Exercise::Lockout(lockout) => (step as f64) * dt >= lockout - 1e-12,
+        }
+    }
+}
+
+
+
+// Take in option data and return the price based on the binomial model for pricing American, European or Bermudan options
+pub fn binomial(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, steps: u32, call: bool, exercise: &Exercise) -> f64 {
+    return binomial_with_carry(spot, strike, time, rate, rate, volatility, steps, call, exercise);
+}
+
+
+
+// Binomial price with a separate cost of carry (carry = rate for stocks, rate - yield for dividend payers or hard-to-borrow names)
+pub fn binomial_with_carry(spot: f64, strike: f64, time: f64, rate: f64, carry: f64, volatility: f64, steps: u32, call: bool, exercise: &Exercise) -> f64 {
+
     // Calculate step size
     let dt: f64 = time / (steps as f64);
 
-    // Calculate up/down factors
+    // Calculate up factor
     let u: f64 = (volatility * f64::sqrt(dt)).exp();
+
+    // The intrinsic value of the option based on type
+    let intrinsic = |price: f64| if call { (price - strike).max(0.0) } else { (strike - price).max(0.0) };
+
+    // Roll the tree back to today
+    let value = backward_induction_with_carry(spot, time, rate, carry, steps, u, intrinsic, |step, _i, price, expected_value| {
+
+        // Handle early exercise, which depends on the exercise schedule
+            // Option is worth the early exercise price if it is greater than the price it is expected to mature to
+
+        // Take the maximum between value it is expected to mature to and the current price (if it were to be early exercised) when exercise is allowed at this step
+        // For European options, early exercise is not applicable
+        if exercise.allowed(step, dt) { expected_value.max(intrinsic(price)) } else { expected_value }
+    });
+
+    match value {
+        // Return the option price
+        Ok(price) => return (price * 100.0).round() / 100.0,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return 1.0;
+        }
+    }
+}
+
+
+
+// Build a recombining binomial tree with up factor u (and d = 1/u) and roll the payoff back to today
+    // The node closure is handed (step, node index, stock price, discounted expected value) at every node before maturity and returns the value to keep at that node
+    // This is the lattice shared by the vanilla and barrier pricers
+pub fn backward_induction<P, N>(spot: f64, time: f64, rate: f64, steps: u32, u: f64, payoff: P, node: N) -> Result<f64, String>
+where
+    P: Fn(f64) -> f64,
+    N: FnMut(u32, u32, f64, f64) -> f64,
+{
+    return backward_induction_with_carry(spot, time, rate, rate, steps, u, payoff, node);
+}
+
+
+
+// The same lattice with the stock drifting at a cost of carry that differs from the discount rate
+pub fn backward_induction_with_carry<P, N>(spot: f64, time: f64, rate: f64, carry: f64, steps: u32, u: f64, payoff: P, mut node: N) -> Result<f64, String>
+where
+    P: Fn(f64) -> f64,
+    N: FnMut(u32, u32, f64, f64) -> f64,
+{
+
+    // Calculate step size and down factor
+    let dt: f64 = time / (steps as f64);
     let d: f64 = 1.0 / u;
 
-    // Calculate risk neutral pseudo probability of an up move
-    let p: f64 = ((rate * dt).exp() - d) / (u - d);
+    // Calculate risk neutral pseudo probability of an up move, the stock grows at the cost of carry
+    let p: f64 = ((carry * dt).exp() - d) / (u - d);
 
     // Ensure p is valid
-    if p > 1.0 || p < 0.0 { 
-        eprintln!("ERROR: Incorrect arguments, pseudo up move probability invalid: {}", p);
-        return 1.0;
+    if p > 1.0 || p < 0.0 || p.is_nan() {
+        return Err(format!("pseudo up move probability invalid: {}", p));
     }
 
-    // Vector that holds all possible option payoffs (intrinsic values)
-    let mut intrinsic_values = Vec::with_capacity((steps + 1) as usize);
+    // Vector that holds all possible option values
+    let mut values = Vec::with_capacity((steps + 1) as usize);
 
 
     // Simulate option prices at maturity
@@ -38,10 +126,7 @@ pub fn binomial(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, s
         // The price of the stock
         let price: f64 = spot * u.powi(i as i32) * d.powi((steps - i) as i32);
 
-        // Calculate the intrinsic value of the option based on type
-        let intrinsic: f64 = if call { (price-strike).max(0.0) } else { (strike-price).max(0.0) };
-
-        intrinsic_values.push(intrinsic);
+        values.push(payoff(price));
     }
 
 
@@ -58,29 +143,13 @@ pub fn binomial(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, s
 
             // Calculate the expected value of the option
                 // Uses probability of an up/down move
-            let expected_value = (-rate * dt).exp() * (p * intrinsic_values[(i + 1) as usize] + (1.0 - p) * intrinsic_values[i as usize]);
-            
-            
-            // Handle american options, which can be exercised early
-                // Option is worth the early exercise price if it is greater than the price it is expected to mature to
-
-            // The early exercise intrinsic value
-            let early_exercise = if call { (current_price - strike).max(0.0) } else { (strike - current_price).max(0.0) };
-
-            // Take the maximum between value it is expected to mature to and the current price (if it were to be early exercised) for American options
-            // For European options, early exercise is not applicable
-
-            if american {
-                intrinsic_values[i as usize] = expected_value.max(early_exercise);
-            } else {
-                intrinsic_values[i as usize] = expected_value;
-            }
+            let expected_value = (-rate * dt).exp() * (p * values[(i + 1) as usize] + (1.0 - p) * values[i as usize]);
+
+            values[i as usize] = node(step, i, current_price, expected_value);
         }
     }
 
 
-    // Return the option price
-    return (intrinsic_values[0 as usize] * 100.0).round() / 100.0;
-    
+    // Return the value at the root of the tree
+    return Ok(values[0 as usize]);
 }
-
@@ -0,0 +1,112 @@
+/*
+
+JDA Options Pricing
+/src/bivariate.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use std::f64::consts::PI;
+use statrs::distribution::{Normal, ContinuousCDF};
+
+
+
+// Gauss-Legendre weights and abscissae (half of each symmetric set) for 6, 12 and 20 point rules
+const WEIGHTS: [[f64; 10]; 3] = [
+    [0.17132449237917, 0.360761573048138, 0.46791393457269, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [0.0471753363865118, 0.106939325995318, 0.160078328543346, 0.203167426723066, 0.233492536538355, 0.249147045813403, 0.0, 0.0, 0.0, 0.0],
+    [0.0176140071391521, 0.0406014298003869, 0.0626720483341091, 0.0832767415767048, 0.10193011981724, 0.118194531961518, 0.131688638449177, 0.142096109318382, 0.149172986472604, 0.152753387130726],
+];
+
+const ABSCISSAE: [[f64; 10]; 3] = [
+    [-0.932469514203152, -0.661209386466265, -0.238619186083197, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [-0.981560634246719, -0.904117256370475, -0.769902674194305, -0.587317954286617, -0.36783149899818, -0.125233408511469, 0.0, 0.0, 0.0, 0.0],
+    [-0.993128599185095, -0.963971927277914, -0.912234428251326, -0.839116971822219, -0.746331906460151, -0.636053680726515, -0.510867001950827, -0.37370608871542, -0.227785851141645, -0.0765265211334973],
+];
+
+
+
+// Probability that two standard normals with correlation rho are below a and b respectively (Genz's algorithm)
+pub fn bivariate_normal_cdf(a: f64, b: f64, rho: f64) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+
+    // More quadrature points are needed the stronger the correlation
+    let (rule, points): (usize, usize) = if rho.abs() < 0.3 { (0, 3) } else if rho.abs() < 0.75 { (1, 6) } else { (2, 10) };
+
+    let h: f64 = -1.0 * a;
+    let mut k: f64 = -1.0 * b;
+    let mut hk: f64 = h * k;
+    let mut bvn: f64 = 0.0;
+
+    if rho.abs() < 0.925 {
+
+        // Integrate over the arcsine of the correlation
+        if rho.abs() > 0.0 {
+            let hs: f64 = (h * h + k * k) / 2.0;
+            let asr: f64 = rho.asin();
+            for i in 0..points {
+                for sign in [-1.0, 1.0] {
+                    let sn: f64 = (asr * (sign * ABSCISSAE[rule][i] + 1.0) / 2.0).sin();
+                    bvn += WEIGHTS[rule][i] * ((sn * hk - hs) / (1.0 - sn * sn)).exp();
+                }
+            }
+            bvn *= asr / (4.0 * PI);
+        }
+
+        return bvn + n(-1.0 * h) * n(-1.0 * k);
+    }
+
+    // Near perfect correlation, integrate the difference from the degenerate case instead
+    if rho < 0.0 {
+        k *= -1.0;
+        hk *= -1.0;
+    }
+
+    if rho.abs() < 1.0 {
+        let ass: f64 = (1.0 - rho) * (1.0 + rho);
+        let mut a_root: f64 = ass.sqrt();
+        let bs: f64 = (h - k).powi(2);
+        let c: f64 = (4.0 - hk) / 8.0;
+        let d: f64 = (12.0 - hk) / 16.0;
+        let asr: f64 = -1.0 * (bs / ass + hk) / 2.0;
+
+        if asr > -100.0 {
+            bvn = a_root * asr.exp() * (1.0 - c * (bs - ass) * (1.0 - d * bs / 5.0) / 3.0 + c * d * ass * ass / 5.0);
+        }
+
+        if -1.0 * hk < 100.0 {
+            let b_root: f64 = bs.sqrt();
+            bvn -= (-1.0 * hk / 2.0).exp() * (2.0 * PI).sqrt() * n(-1.0 * b_root / a_root) * b_root * (1.0 - c * bs * (1.0 - d * bs / 5.0) / 3.0);
+        }
+
+        a_root /= 2.0;
+        for i in 0..points {
+            for sign in [-1.0, 1.0] {
+                let xs: f64 = (a_root * (sign * ABSCISSAE[rule][i] + 1.0)).powi(2);
+                let rs: f64 = (1.0 - xs).sqrt();
+                let asr: f64 = -1.0 * (bs / xs + hk) / 2.0;
+                if asr > -100.0 {
+                    bvn += a_root * WEIGHTS[rule][i] * asr.exp() * ((-1.0 * hk * (1.0 - rs) / (2.0 * (1.0 + rs))).exp() / rs - (1.0 + c * xs * (1.0 + d * xs)));
+                }
+            }
+        }
+
+        bvn = -1.0 * bvn / (2.0 * PI);
+    }
+
+    if rho > 0.0 {
+        return bvn + n(-1.0 * h.max(k));
+    }
+
+    bvn *= -1.0;
+    if k > h {
+        bvn += n(k) - n(h);
+    }
+
+    return bvn;
+}
@@ -27,20 +27,26 @@ pub fn d2(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64) -> f64
 
 // Take in option data and return the price based on the black-scholes model for pricing American and European options
 pub fn black_scholes(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+    return (generalized_black_scholes(spot, strike, time, rate, rate, volatility, call) * 100.0).round() / 100.0;
+}
+
+
+
+// Unrounded black-scholes price with a separate cost of carry (carry = rate for stocks, rate - yield for dividend payers, 0 for futures)
+    // Used as the building block for the exotic pricers
+pub fn generalized_black_scholes(spot: f64, strike: f64, time: f64, rate: f64, carry: f64, volatility: f64, call: bool) -> f64 {
 
     // Standard normal distribution and the distribution at particular points to use
     let standard_normal = Normal::standard();
 
-    // Calculate d1 and d2
-    let d1: f64 = d1(spot, strike, time, rate, volatility);
-    let d2: f64 = d2(spot, strike, time, rate, volatility);
+    // Calculate d1 and d2 (the drift in d1/d2 is the cost of carry, not the discount rate)
+    let d1: f64 = d1(spot, strike, time, carry, volatility);
+    let d2: f64 = d2(spot, strike, time, carry, volatility);
 
     // Apply the formula based on the type of option
-
     if call {
-        return (((spot * standard_normal.cdf(d1)) - (strike * (-1.0 * rate * time).exp() * standard_normal.cdf(d2))) * 100.0).round() / 100.0;
+        return (spot * ((carry - rate) * time).exp() * standard_normal.cdf(d1)) - (strike * (-1.0 * rate * time).exp() * standard_normal.cdf(d2));
     } else {
-        return ((((strike * (-1.0 * rate * time).exp() * standard_normal.cdf(-1.0 * d2)) - (spot * standard_normal.cdf(-1.0 * d1))) * 100.0)).round() / 100.0;
+        return (strike * (-1.0 * rate * time).exp() * standard_normal.cdf(-1.0 * d2)) - (spot * ((carry - rate) * time).exp() * standard_normal.cdf(-1.0 * d1));
     }
 }
-
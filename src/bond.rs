@@ -0,0 +1,63 @@
+/*
+
+JDA Options Pricing
+/src/bond.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Struct to hold a fixed coupon bond
+    // A zero coupon rate gives a zero coupon bond
+#[derive(Debug, Clone, Copy)]
+pub struct Bond {
+    pub face: f64,
+    pub coupon: f64,
+    pub frequency: u32,
+    pub maturity: f64,
+}
+
+impl Bond {
+
+    // Remaining cash flows as (years from today, amount), counting back from maturity in whole coupon periods
+    pub fn cash_flows(&self) -> Vec<(f64, f64)> {
+
+        if self.coupon == 0.0 || self.frequency == 0 {
+            return vec![(self.maturity, self.face)];
+        }
+
+        let period: f64 = 1.0 / (self.frequency as f64);
+        let payment: f64 = self.face * self.coupon * period;
+
+        let mut flows: Vec<(f64, f64)> = Vec::new();
+        let mut time: f64 = self.maturity;
+        while time > 1e-9 {
+            flows.push((time, payment));
+            time -= period;
+        }
+        flows.reverse();
+
+        // The face value is repaid with the last coupon
+        if let Some(last) = flows.last_mut() {
+            last.1 += self.face;
+        }
+
+        return flows;
+    }
+
+    // Cash flows paid strictly after a given time, such as the expiry of an option on the bond
+    pub fn cash_flows_after(&self, time: f64) -> Vec<(f64, f64)> {
+        return self.cash_flows().into_iter().filter(|(t, _)| *t > time + 1e-9).collect();
+    }
+
+    // Coupon dates on or after a given time, which is when embedded calls and puts can be exercised
+    pub fn coupon_dates_from(&self, time: f64) -> Vec<f64> {
+        return self.cash_flows().iter().map(|(t, _)| *t).filter(|t| *t >= time - 1e-9).collect();
+    }
+
+    // Price the bond off a discount function
+    pub fn price<D: Fn(f64) -> f64>(&self, discount: D) -> f64 {
+        return self.cash_flows().iter().map(|(t, amount)| amount * discount(*t)).sum();
+    }
+}
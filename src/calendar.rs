@@ -0,0 +1,317 @@
+/*
+
+JDA Options Pricing
+/src/calendar.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc, Weekday};
+
+
+
+// How an option's final value is set on its expiration date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Settlement {
+    // At the closing price, so the option lives until the close
+    Pm,
+    // At the opening prices (such as the SPX special opening quotation), so the option stops trading the day before
+    Am,
+}
+
+impl Settlement {
+
+    // Standard monthly options on the big cash indices (the SPX, NDX and RUT roots) are AM settled on the third Friday, or the Thursday before when that Friday is a holiday
+        // Everything else is PM settled, including the SPXW, NDXP and RUTW weeklies and dailies that also expire on the third Friday
+        // Takes the OCC option symbol so the root decides, a bare underlying symbol is treated as the standard monthly root
+    pub fn for_option(option_symbol: &str, expiration: NaiveDate) -> Settlement {
+
+        let third_friday = NaiveDate::from_weekday_of_month_opt(expiration.year(), expiration.month(), Weekday::Fri, 3);
+        let monthly: bool = match third_friday {
+            Some(friday) if Calendar::nyse().is_business_day(friday) => expiration == friday,
+            Some(friday) => friday.pred_opt() == Some(expiration),
+            None => false,
+        };
+
+        if monthly && ["SPX", "NDX", "RUT"].contains(&occ_root(option_symbol).as_str()) {
+            return Settlement::Am;
+        }
+
+        return Settlement::Pm;
+    }
+}
+
+
+
+// Root of an OCC option symbol (SPXW in SPXW250717C05000000), or the symbol itself when it has no date, side and strike on the end
+fn occ_root(option_symbol: &str) -> String {
+
+    let symbol: &str = option_symbol.trim().trim_start_matches('^');
+    let contract: bool = symbol.len() > 15 && symbol.is_char_boundary(symbol.len() - 15) && {
+        let suffix: &[u8] = &symbol.as_bytes()[symbol.len() - 15..];
+        suffix[..6].iter().all(u8::is_ascii_digit) && matches!(suffix[6], b'C' | b'P') && suffix[7..].iter().all(u8::is_ascii_digit)
+    };
+
+    let root: &str = if contract { &symbol[..symbol.len() - 15] } else { symbol };
+    return root.trim().to_uppercase();
+}
+
+
+
+// NYSE trading calendar, which the CBOE follows for equity and index options
+    // Regular sessions run 9:30 to 16:00 New York time, with 13:00 closes before Independence Day, after Thanksgiving and on Christmas Eve
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Calendar;
+
+impl Calendar {
+
+    pub fn nyse() -> Calendar {
+        return Calendar;
+    }
+
+    // Full day exchange holidays
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+
+        let year: i32 = date.year();
+        let nth = |month: u32, weekday: Weekday, n: u8| NaiveDate::from_weekday_of_month_opt(year, month, weekday, n);
+        let fixed = |month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day).map(observed);
+
+        // Saturday New Year's Days are not made up on the Friday before, since that Friday closes a different year
+        let new_year = NaiveDate::from_ymd_opt(year, 1, 1).filter(|d| d.weekday() != Weekday::Sat).map(observed);
+
+        let holidays = [
+            new_year,
+            // Martin Luther King Jr. Day
+            nth(1, Weekday::Mon, 3),
+            // Presidents' Day
+            nth(2, Weekday::Mon, 3),
+            easter(year).and_then(|e| e.checked_sub_days(Days::new(2))),
+            // Memorial Day
+            nth(5, Weekday::Mon, 5).or(nth(5, Weekday::Mon, 4)),
+            if year >= 2022 { fixed(6, 19) } else { None },
+            fixed(7, 4),
+            // Labor Day
+            nth(9, Weekday::Mon, 1),
+            // Thanksgiving
+            nth(11, Weekday::Thu, 4),
+            fixed(12, 25),
+        ];
+
+        return holidays.contains(&Some(date));
+    }
+
+    // Whether the exchange is open at all on a date
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        return !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date);
+    }
+
+    // Local (New York) open and close of the session on a date, if there is one
+    pub fn session(&self, date: NaiveDate) -> Option<(NaiveTime, NaiveTime)> {
+
+        if !self.is_business_day(date) {
+            return None;
+        }
+
+        let open = NaiveTime::from_hms_opt(9, 30, 0)?;
+        let close = if self.is_early_close(date) { NaiveTime::from_hms_opt(13, 0, 0)? } else { NaiveTime::from_hms_opt(16, 0, 0)? };
+
+        return Some((open, close));
+    }
+
+    // Half days, which close at 13:00
+    fn is_early_close(&self, date: NaiveDate) -> bool {
+
+        let year: i32 = date.year();
+        let open = |d: Option<NaiveDate>| d.filter(|d| self.is_business_day(*d));
+
+        let before_independence = open(NaiveDate::from_ymd_opt(year, 7, 3));
+        let after_thanksgiving = open(NaiveDate::from_weekday_of_month_opt(year, 11, Weekday::Thu, 4).and_then(|d| d.succ_opt()));
+        let christmas_eve = open(NaiveDate::from_ymd_opt(year, 12, 24));
+
+        return [before_independence, after_thanksgiving, christmas_eve].contains(&Some(date));
+    }
+
+    // Number of business days after start up to and including end
+    pub fn business_days_between(&self, start: NaiveDate, end: NaiveDate) -> i64 {
+        return start.iter_days().skip(1).take_while(|d| *d <= end).filter(|d| self.is_business_day(*d)).count() as i64;
+    }
+
+    // The last business day on or before a date, where listed expirations move when they land on a holiday
+    pub fn previous_business_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut day: NaiveDate = date;
+        while !self.is_business_day(day) {
+            day = day.pred_opt().unwrap_or(day);
+        }
+        return day;
+    }
+
+    // The moment (in New York time) an option stops living: the close of its expiration date, or the open for AM settlement
+    pub fn expiry_time(&self, expiration: NaiveDate, settlement: Settlement) -> Option<NaiveDateTime> {
+        let date: NaiveDate = self.previous_business_day(expiration);
+        let (open, close) = self.session(date)?;
+        return Some(date.and_time(if settlement == Settlement::Am { open } else { close }));
+    }
+}
+
+
+
+// New York wall clock time of a UTC instant, with daylight saving from the second Sunday of March to the first Sunday of November
+pub fn new_york_time(instant: DateTime<Utc>) -> NaiveDateTime {
+
+    let standard: NaiveDateTime = instant.naive_utc() - TimeDelta::hours(5);
+    let year: i32 = standard.year();
+
+    let start = NaiveDate::from_weekday_of_month_opt(year, 3, Weekday::Sun, 2).and_then(|d| d.and_hms_opt(2, 0, 0));
+    let end = NaiveDate::from_weekday_of_month_opt(year, 11, Weekday::Sun, 1).and_then(|d| d.and_hms_opt(1, 0, 0));
+
+    let daylight: bool = match (start, end) {
+        (Some(start), Some(end)) => standard >= start && standard < end,
+        _ => false,
+    };
+
+    return if daylight { standard + TimeDelta::hours(1) } else { standard };
+}
+
+
+
+// Move a weekend holiday to the Friday before or the Monday after
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date.pred_opt().unwrap_or(date),
+        Weekday::Sun => date.succ_opt().unwrap_or(date),
+        _ => date,
+    }
+}
+
+
+
+// Easter Sunday in the Gregorian calendar (anonymous Gregorian algorithm)
+fn easter(year: i32) -> Option<NaiveDate> {
+
+    let a: i32 = year % 19;
+    let b: i32 = year / 100;
+    let c: i32 = year % 100;
+    let d: i32 = b / 4;
+    let e: i32 = b % 4;
+    let f: i32 = (b + 8) / 25;
+    let g: i32 = (b - f + 1) / 3;
+    let h: i32 = (19 * a + b - d - g + 15) % 30;
+    let i: i32 = c / 4;
+    let k: i32 = c % 4;
+    let l: i32 = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m: i32 = (a + 11 * h + 22 * l) / 451;
+
+    let month: u32 = ((h + l - 7 * m + 114) / 31) as u32;
+    let day: u32 = ((h + l - 7 * m + 114) % 31 + 1) as u32;
+
+    return NaiveDate::from_ymd_opt(year, month, day);
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        return NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+    }
+
+    #[test]
+    fn nyse_2025_holidays() {
+        let calendar = Calendar::nyse();
+
+        // The exchange's published 2025 full day closures
+        let holidays: [NaiveDate; 10] = [
+            date(2025, 1, 1), date(2025, 1, 20), date(2025, 2, 17), date(2025, 4, 18), date(2025, 5, 26),
+            date(2025, 6, 19), date(2025, 7, 4), date(2025, 9, 1), date(2025, 11, 27), date(2025, 12, 25),
+        ];
+        for holiday in holidays.iter() {
+            assert!(calendar.is_holiday(*holiday), "{} should be a holiday", holiday);
+            assert_eq!(calendar.session(*holiday), None);
+        }
+
+        let all_year: usize = date(2025, 1, 1).iter_days().take_while(|d| d.year() == 2025).filter(|d| calendar.is_holiday(*d)).count();
+        assert_eq!(all_year, holidays.len());
+
+        // Good Friday's expirations move back to Thursday
+        assert_eq!(calendar.previous_business_day(date(2025, 4, 18)), date(2025, 4, 17));
+    }
+
+    #[test]
+    fn nyse_2025_early_closes() {
+        let calendar = Calendar::nyse();
+
+        for half_day in [date(2025, 7, 3), date(2025, 11, 28), date(2025, 12, 24)] {
+            assert_eq!(calendar.session(half_day), Some((time(9, 30), time(13, 0))), "{} should close at 13:00", half_day);
+        }
+        assert_eq!(calendar.session(date(2025, 7, 2)), Some((time(9, 30), time(16, 0))));
+        assert_eq!(calendar.session(date(2025, 12, 26)), Some((time(9, 30), time(16, 0))));
+    }
+
+    #[test]
+    fn saturday_new_year_is_not_observed() {
+        let calendar = Calendar::nyse();
+
+        // 2022 opened on a Saturday, and the last Friday of 2021 still traded
+        assert!(calendar.is_business_day(date(2021, 12, 31)));
+        assert!(!calendar.is_holiday(date(2022, 1, 3)));
+
+        // A Sunday New Year's Day moves to the Monday
+        assert!(calendar.is_holiday(date(2023, 1, 2)));
+
+        // Other Saturday holidays move to the Friday, Independence Day 2026 is the 3rd
+        assert!(calendar.is_holiday(date(2026, 7, 3)));
+    }
+
+    #[test]
+    fn daylight_saving_transitions() {
+        let at = |text: &str| new_york_time(DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc));
+        let local = |day: NaiveDate, hour: u32, minute: u32| day.and_time(time(hour, minute));
+
+        // Clocks spring forward at 2:00 on March 9, 2025
+        assert_eq!(at("2025-03-09T06:59:00Z"), local(date(2025, 3, 9), 1, 59));
+        assert_eq!(at("2025-03-09T07:00:00Z"), local(date(2025, 3, 9), 3, 0));
+
+        // And fall back at 2:00 on November 2, 2025, so 1:00 to 2:00 comes twice
+        assert_eq!(at("2025-11-02T05:59:00Z"), local(date(2025, 11, 2), 1, 59));
+        assert_eq!(at("2025-11-02T06:00:00Z"), local(date(2025, 11, 2), 1, 0));
+
+        // The close is 20:00 UTC in summer and 21:00 UTC in winter
+        assert_eq!(at("2025-06-20T20:00:00Z"), local(date(2025, 6, 20), 16, 0));
+        assert_eq!(at("2025-12-19T21:00:00Z"), local(date(2025, 12, 19), 16, 0));
+    }
+
+    #[test]
+    fn settlement_follows_the_root() {
+        let calendar = Calendar::nyse();
+
+        // Third Friday of June 2025, where the SPX monthly and the SPXW weekly both expire
+        let june: NaiveDate = date(2025, 6, 20);
+        assert_eq!(Settlement::for_option("SPX250620C06000000", june), Settlement::Am);
+        assert_eq!(Settlement::for_option("SPXW250620C06000000", june), Settlement::Pm);
+        assert_eq!(Settlement::for_option("NDXP250620P20000000", june), Settlement::Pm);
+        assert_eq!(Settlement::for_option("RUT250620P02000000", june), Settlement::Am);
+        assert_eq!(Settlement::for_option("AAPL250620C00200000", june), Settlement::Pm);
+        assert_eq!(Settlement::for_option("^SPX", june), Settlement::Am);
+
+        assert_eq!(calendar.expiry_time(june, Settlement::Am), Some(june.and_time(time(9, 30))));
+        assert_eq!(calendar.expiry_time(june, Settlement::Pm), Some(june.and_time(time(16, 0))));
+
+        // A Thursday daily the day before a trading third Friday runs to the close
+        assert_eq!(Settlement::for_option("SPXW250717C06000000", date(2025, 7, 17)), Settlement::Pm);
+        assert_eq!(Settlement::for_option("SPX", date(2025, 7, 17)), Settlement::Pm);
+
+        // The April 2025 monthly moved to Thursday for Good Friday
+        assert_eq!(Settlement::for_option("SPX250417C06000000", date(2025, 4, 17)), Settlement::Am);
+        assert_eq!(Settlement::for_option("SPXW250417C06000000", date(2025, 4, 17)), Settlement::Pm);
+    }
+}
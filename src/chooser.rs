@@ -0,0 +1,85 @@
+/*
+
+JDA Options Pricing
+/src/chooser.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::bivariate::bivariate_normal_cdf;
+use crate::black_scholes::{d1, d2, generalized_black_scholes};
+use crate::monte_carlo::{MonteCarloEstimate, monte_carlo};
+use crate::solver::positive_root;
+
+
+
+// Price a simple chooser, where at choose_time the holder picks a call or a put with the same strike and expiry (Rubinstein)
+pub fn simple_chooser(spot: f64, strike: f64, time: f64, choose_time: f64, rate: f64, volatility: f64) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+
+    let d: f64 = d1(spot, strike, time, carry, volatility);
+    let y: f64 = ((spot / strike).ln() + carry * time + 0.5 * volatility.powi(2) * choose_time) / (volatility * choose_time.sqrt());
+
+    let asset: f64 = spot * ((carry - rate) * time).exp();
+    let cash: f64 = strike * (-1.0 * rate * time).exp();
+
+    return asset * n(d) - cash * n(d - volatility * time.sqrt()) - asset * n(-1.0 * y) + cash * n(-1.0 * y + volatility * choose_time.sqrt());
+}
+
+
+
+// Price a complex chooser, where the call and put on offer can have different strikes and expiries (Rubinstein)
+pub fn complex_chooser(spot: f64, call_strike: f64, call_time: f64, put_strike: f64, put_time: f64, choose_time: f64, rate: f64, volatility: f64) -> Result<f64, String> {
+
+    if choose_time <= 0.0 || choose_time >= call_time.min(put_time) {
+        return Err("the choice must be made before either option expires".to_string());
+    }
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+
+    // Stock price at the choice date where the holder is indifferent between the call and the put
+    let critical: f64 = positive_root(
+        |s| generalized_black_scholes(s, call_strike, call_time - choose_time, rate, carry, volatility, true)
+            - generalized_black_scholes(s, put_strike, put_time - choose_time, rate, carry, volatility, false),
+        0.5 * (call_strike + put_strike),
+        1e-8,
+    ).ok_or("could not find the critical stock price")?;
+
+    let dd1: f64 = d1(spot, critical, choose_time, carry, volatility);
+    let dd2: f64 = d2(spot, critical, choose_time, carry, volatility);
+    let y1: f64 = d1(spot, call_strike, call_time, carry, volatility);
+    let y2: f64 = d1(spot, put_strike, put_time, carry, volatility);
+    let rho1: f64 = (choose_time / call_time).sqrt();
+    let rho2: f64 = (choose_time / put_time).sqrt();
+
+    let price: f64 = spot * ((carry - rate) * call_time).exp() * bivariate_normal_cdf(dd1, y1, rho1)
+        - call_strike * (-1.0 * rate * call_time).exp() * bivariate_normal_cdf(dd2, y1 - volatility * call_time.sqrt(), rho1)
+        - spot * ((carry - rate) * put_time).exp() * bivariate_normal_cdf(-1.0 * dd1, -1.0 * y2, rho2)
+        + put_strike * (-1.0 * rate * put_time).exp() * bivariate_normal_cdf(-1.0 * dd2, -1.0 * y2 + volatility * put_time.sqrt(), rho2);
+
+    return Ok(price);
+}
+
+
+
+// Price a (simple or complex) chooser by simulating to the choice date and taking the more valuable option there
+pub fn monte_carlo_chooser(spot: f64, call_strike: f64, call_time: f64, put_strike: f64, put_time: f64, choose_time: f64, rate: f64, volatility: f64, paths: u32, seed: Option<u64>) -> MonteCarloEstimate {
+
+    return monte_carlo(spot, rate, rate, volatility, &[choose_time], choose_time, paths, seed, |path| {
+        let call: f64 = generalized_black_scholes(path[0], call_strike, call_time - choose_time, rate, rate, volatility, true);
+        let put: f64 = generalized_black_scholes(path[0], put_strike, put_time - choose_time, rate, rate, volatility, false);
+        call.max(put)
+    });
+}
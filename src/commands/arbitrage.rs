@@ -0,0 +1,112 @@
+/*
+
+JDA Options Pricing
+/src/commands/arbitrage.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::arbitrage::{ArbitrageViolation, ChainSlice, Check, check_chain};
+use crate::day_count::DayCount;
+use crate::expiration::{ExpirationArgs, select_expirations};
+use crate::fetch::{fetch_treasury_curve, settlement_symbol, time_to_expiry};
+use crate::provider::{MarketDataProvider, Provider, ProviderArgs};
+use crate::yield_curve::Interpolation;
+
+
+// Entry point for the command
+pub async fn run(symbol: String, expiration: ExpirationArgs, european: bool, tolerance: f64, provider: ProviderArgs) {
+
+    // Check arguments
+    if tolerance < 0.0 {
+        eprintln!("ERROR: Incorrect arguments, the tolerance can't be negative.");
+        return;
+    }
+
+
+    // Connect to the market data source
+    let provider: Provider = match Provider::load(&provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Failed to load the market data provider: {}", e);
+            return;
+        }
+    };
+
+
+    // Get the expiration dates to check, from the arguments or the drop down
+    let expirations = match select_expirations(&provider, &symbol, true, &expiration).await {
+        Ok(expirations) => expirations,
+        Err(e) => {
+            eprintln!("Failed to choose an expiration date: {}", e);
+            return;
+        }
+    };
+
+
+    // Pull both sides of every chain, the spot and the discount factor to each expiry
+    let market = async {
+        let quote = provider.quote(&symbol).await?;
+        let curve = fetch_treasury_curve(&provider, Interpolation::MonotoneCubic).await?;
+        let mut slices: Vec<ChainSlice> = Vec::new();
+        for expiration in expirations.iter() {
+            let calls = provider.chain(&symbol, *expiration, true).await?;
+            let puts = provider.chain(&symbol, *expiration, false).await?;
+            let (_, calendar_years) = time_to_expiry(&provider, settlement_symbol(&calls, &symbol), *expiration, DayCount::Act365Fixed)?;
+            slices.push(ChainSlice { expiration: *expiration, discount: curve.discount(calendar_years), calls, puts });
+        }
+        Ok::<_, Box<dyn std::error::Error>>((quote, slices))
+    };
+
+    let (quote, slices) = match market.await {
+        Ok(market) => market,
+        Err(e) => {
+            eprintln!("Failed to fetch the option chain: {}", e);
+            return;
+        }
+    };
+
+    let (spot_bid, spot_ask) = (quote.bid.unwrap_or(quote.last), quote.ask.unwrap_or(quote.last));
+    let violations: Vec<ArbitrageViolation> = check_chain(&slices, spot_bid, spot_ask, !european, tolerance);
+
+
+    // Output
+    println!("\u{1F4B0} Static Arbitrage Tool");
+    println!("--------------------------------\n");
+
+    println!("Symbol:             {}", symbol);
+    println!("Spot Bid / Ask:     {} / {}", spot_bid, spot_ask);
+    println!("Exercise:           {}", if european { "European" } else { "American" });
+    println!("Expirations:        {}", expirations.iter().map(|d| d.to_string()).collect::<Vec<String>>().join(", "));
+    println!("Tolerance:          {} per share", tolerance);
+
+    for check in [Check::Parity, Check::Vertical, Check::Butterfly, Check::Calendar] {
+
+        let found: Vec<&ArbitrageViolation> = violations.iter().filter(|v| v.check == check).collect();
+
+        println!("\n--------------------------------\n");
+        println!("{} ({} found)\n", match check {
+            Check::Parity => "Put-Call Parity",
+            Check::Vertical => "Vertical Spreads",
+            Check::Butterfly => "Butterfly Spreads",
+            Check::Calendar => "Calendar Spreads",
+        }, found.len());
+
+        if found.is_empty() { continue; }
+
+        println!("{:>12} {:>12} {:>5} {:>22} {:>8}  Trade", "Expiration", "Far", "Side", "Strikes", "Size");
+        for violation in found {
+            println!("{:>12} {:>12} {:>5} {:>22} {:>8.4}  {}",
+                violation.expiration.to_string(),
+                violation.far.map(|d| d.to_string()).unwrap_or("-".to_string()),
+                match violation.call { Some(true) => "Call", Some(false) => "Put", None => "Both" },
+                violation.strikes.iter().map(|k| k.to_string()).collect::<Vec<String>>().join(" / "),
+                violation.size,
+                violation.trade);
+        }
+    }
+
+    println!("\n--------------------------------\n");
+    println!("{} violations worth more than {} per share at the quoted bids and asks", violations.len(), tolerance);
+}
@@ -0,0 +1,87 @@
+/*
+
+JDA Options Pricing
+/src/commands/asian.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::asian::{Averaging, curran, geometric_asian, geometric_asian_continuous, levy, monte_carlo_asian, monte_carlo_asian_plain, turnbull_wakeman};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, strike: f64, time: f64, rates: RateArgs, volatility: f64, fixings: Option<u32>, first: Option<f64>, past: Vec<f64>, paths: u32, seed: Option<u64>, call: bool, put: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    // Default to daily fixings over the remaining life, equally spaced through to expiry
+    let remaining: u32 = fixings.unwrap_or(((time * 252.0).round() as u32).max(1));
+    if remaining == 0 {
+        eprintln!("ERROR: Incorrect arguments, at least one fixing must remain before expiry.");
+        return;
+    }
+
+    let first_fixing: f64 = first.unwrap_or(time / (remaining as f64));
+    if first_fixing <= 0.0 || first_fixing > time {
+        eprintln!("ERROR: Incorrect arguments, the first remaining fixing must fall between now and expiry.");
+        return;
+    }
+
+    let averaging = Averaging::equally_spaced(first_fixing, time, remaining, past);
+
+
+    // Output
+    println!("\u{1F4B0} Asian Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {}", if call_opt { "Call" } else { "Put" } );
+    println!("Spot Price:         {}", spot);
+    println!("Strike Price:       {}", strike);
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+    println!("Fixings Remaining:  {} (first in {} years)", remaining, (first_fixing * 10000.0).round() / 10000.0);
+    println!("Fixings Set:        {}", averaging.past.len());
+    println!("Paths:              {}", paths);
+
+    println!("\n--------------------------------\n");
+
+    // Geometric averages have exact prices
+    if averaging.past.is_empty() {
+        println!("Geometric Average Price (Continuous):         {}", (geometric_asian_continuous(spot, strike, time, rate, volatility, call_opt) * 100.0).round() / 100.0);
+    }
+    println!("Geometric Average Price (Discrete):           {}", (geometric_asian(spot, strike, time, rate, volatility, &averaging, call_opt) * 100.0).round() / 100.0);
+
+    println!();
+
+    // Arithmetic averages only have approximations
+    println!("Option Price using Turnbull-Wakeman:          {}", (turnbull_wakeman(spot, strike, time, rate, volatility, &averaging, call_opt) * 100.0).round() / 100.0);
+    println!("Option Price using Levy:                      {}", (levy(spot, strike, time, rate, volatility, &averaging, call_opt) * 100.0).round() / 100.0);
+    println!("Option Price using Curran:                    {}", (curran(spot, strike, time, rate, volatility, &averaging, call_opt) * 100.0).round() / 100.0);
+
+    let plain = monte_carlo_asian_plain(spot, strike, time, rate, volatility, &averaging, paths, seed, call_opt);
+    let controlled = monte_carlo_asian(spot, strike, time, rate, volatility, &averaging, paths, seed, call_opt);
+
+    println!("Option Price using Monte Carlo:               {} (std. error {})", (plain.price * 100.0).round() / 100.0, (plain.std_error * 10000.0).round() / 10000.0);
+    println!("Option Price using Monte Carlo (Control):     {} (std. error {})", (controlled.price * 100.0).round() / 100.0, (controlled.std_error * 10000.0).round() / 10000.0);
+}
@@ -8,14 +8,22 @@ JohnDavid Abe
 
 
 // Fetch data module
-use dialoguer::Select;
-use crate::fetch::{fetch_expiration_dates, fetch_american_option_data};
-use crate::binomial::binomial;
-use crate::greeks::calculate_greeks;
+use crate::fetch::{OptionData, fetch_american_option_data, fetch_option_data, fetch_treasury_curve};
+use crate::expiration::{ExpirationArgs, select_expirations};
+use crate::provider::{MarketDataProvider, Provider, ProviderArgs};
+use crate::binomial::{Exercise, binomial_with_carry};
+use crate::black_scholes::generalized_black_scholes;
+use crate::greeks::{Greeks, calculate_greeks, numerical_greeks};
+use crate::day_count::DayCount;
+use clap::ValueEnum;
+use chrono::Datelike;
+use crate::smile::{SmileAxis, SmileMethod};
+use crate::yield_curve::Interpolation;
+use crate::historical::{VolatilityEstimate, VolatilityInput, read_bars};
 
 
 // Entry point for the command
-pub async fn run(symbol: String, strike: f64, steps: u32, call: bool, put: bool, greeks: bool) {
+pub async fn run(symbol: String, strike: f64, steps: u32, call: bool, put: bool, greeks: bool, day_count: DayCount, smile: Option<SmileMethod>, smile_axis: SmileAxis, implied_carry: bool, volatility: Option<VolatilityInput>, bars: Option<String>, expiration: ExpirationArgs, provider: ProviderArgs) {
 
 
     // Ensure option is either only call OR put
@@ -30,68 +38,253 @@ pub async fn run(symbol: String, strike: f64, steps: u32, call: bool, put: bool,
 
 
 
-    // Get valid expiration dates for the symbol from the option chain
-    match fetch_expiration_dates(&symbol, call).await {
-
-        Ok(dates) => {
-
-            // Get the user to select the expiration date from drop down
-            let items = dates;
-            let selection = Select::new()
-                .with_prompt("Choose an options expiration date")
-                .items(&items)
-                .default(0)
-                .interact()
-                .unwrap();
-
-
-            // Pull the option data
-            match fetch_american_option_data(&symbol, strike, items[selection], call).await {
-                Ok(data) => {
-                    
-                        // Output
-                        println!("\u{1F4B0} Auto Options Pricing Tool");
-                        println!("--------------------------------\n");
-                        
-                        println!("Symbol:             {}", symbol);
-                        println!("Option Type:        {}", if call_opt { "Call" } else { "Put" } );
-                        println!("Spot Price:         {}", data.spot);
-                        println!("Strike Price:       {}", data.strike);
-                        println!("Years to Maturity:  {}", data.expiration);
-                        println!("Risk-Free Rate:     {}", data.rate);
-                        println!("Volatility:         {}", data.volatility);
-                        println!("Steps:              {}", steps);
-
-                        println!("\n--------------------------------\n");
-
-                        // Price using American Binomial Model
-                        println!("Option Price using Binomial Model:       {}", binomial(data.spot, data.strike, data.expiration, data.rate, data.volatility, steps, call_opt, true));
-
-
-                        // Attempt to get the Greeks if the flag is specified
-                        if greeks {
-                            match calculate_greeks(data.spot, data.strike, data.expiration, data.rate, data.volatility, call_opt) {
-                                Ok(greeks) => {
-                                    
-                                        // Output
-                                        println!("\n--------------------------------\n");
-                                        
-                                        println!("Delta:              {}", (greeks.delta * 10000.0).round() / 10000.0);
-                                        println!("Gamma:              {}", (greeks.gamma * 10000.0).round() / 10000.0);
-                                        println!("Vega:               {}", (greeks.vega * 10000.0).round() / 10000.0);
-                                        println!("Theta:              {}", (greeks.theta * 10000.0).round() / 10000.0);
-                                        println!("Rho:                {}", (greeks.rho * 10000.0).round() / 10000.0);
+    // Connect to the market data source
+    let provider: Provider = match Provider::load(&provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Failed to load the market data provider: {}", e);
+            return;
+        }
+    };
+
+
+    // Get the expiration dates to price, from the arguments or the drop down
+    let expirations = match select_expirations(&provider, &symbol, call_opt, &expiration).await {
+        Ok(expirations) => expirations,
+        Err(e) => {
+            eprintln!("Failed to choose an expiration date: {}", e);
+            return;
+        }
+    };
+
+
+    // Price at a given, historical or forecast volatility instead of the market's implied vol
+    let volatility: Option<(VolatilityEstimate, String)> = match volatility {
+        None => None,
+        Some(VolatilityInput::Fixed(value)) => Some((VolatilityEstimate::Flat(value), "given".to_string())),
+        Some(input) => {
+            let estimate = async {
+                let history = match &bars {
+                    Some(path) => read_bars(path)?,
+                    None => provider.candles(&symbol, input.bars()).await?,
+                };
+                Ok::<_, Box<dyn std::error::Error>>(input.estimate(&history)?)
+            };
+            match estimate.await {
+                Ok(estimate) => Some((estimate, format!("{} of {}", input, bars.as_deref().unwrap_or(&symbol)))),
+                Err(e) => {
+                    eprintln!("Failed to compute the volatility: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+
+
+    // Price the closest strike on every expiration in one table
+    if expirations.len() > 1 {
+
+        // The spot and the curve are shared by every expiry
+        let market = async {
+            let underlying = provider.quote(&symbol).await?;
+            let curve = fetch_treasury_curve(&provider, Interpolation::MonotoneCubic).await?;
+            Ok::<_, Box<dyn std::error::Error>>((underlying, curve))
+        };
+
+        let (underlying, curve) = match market.await {
+            Ok(market) => market,
+            Err(e) => {
+                eprintln!("Failed to fetch market data: {}", e);
+                return;
+            }
+        };
+
+        println!("\u{1F4B0} Auto Options Pricing Tool");
+        println!("--------------------------------\n");
+
+        println!("Symbol:             {}", symbol);
+        println!("Option Type:        {}", if call_opt { "Call" } else { "Put" } );
+        println!("Target Strike:      {}", strike);
+        println!("Steps:              {}", steps);
+
+        println!("\n--------------------------------\n");
+
+        println!("{:>12} {:>9} {:>10} {:>10} {:>10} {:>8} {:>10} {:>8} {:>8}", "Expiration", "Strike", "Years", "Rate", "Div/Borrow", "IV", "Price", "Mid", "Edge");
+        for date in expirations {
+            match fetch_option_data(&provider, &symbol, strike, date, call_opt, day_count, smile.map(|method| (method, smile_axis)), implied_carry, &underlying, &curve).await {
+                Ok(mut data) => {
+                    if let Some((estimate, _)) = &volatility { data.volatility = estimate.volatility(data.expiration); }
+                    let (rate, carry) = rates(&data);
+                    let model: f64 = binomial_with_carry(data.spot, data.strike, data.expiration, rate, carry, data.volatility, steps, call_opt, &Exercise::American);
+                    println!("{:>12} {:>9} {:>10.6} {:>9.4}% {:>9.4}% {:>7.2}% {:>10} {:>8} {:>8}",
+                        date.to_string(), data.strike, data.expiration, rate * 100.0, (rate - carry) * 100.0, data.volatility * 100.0,
+                        model, show(data.mid), show(data.mid.map(|mid| mid - model)));
+                }
+                Err(e) => eprintln!("{:>12} Failed to fetch option data: {}", date.to_string(), e),
+            }
+        }
+        return;
+    }
+
+
+    // Pull the option data
+    match fetch_american_option_data(&provider, &symbol, strike, expirations[0], call_opt, day_count, smile.map(|method| (method, smile_axis)), implied_carry).await {
+        Ok(mut data) => {
+
+                // Replace the market's implied vol
+                if let Some((estimate, _)) = &volatility { data.volatility = estimate.volatility(data.expiration); }
+
+                // Price at the implied discount rate and carry when they were backed out of the chain
+                let (rate, carry) = rates(&data);
+
+                // Output
+                println!("\u{1F4B0} Auto Options Pricing Tool");
+                println!("--------------------------------\n");
+
+                println!("Symbol:             {}", data.symbol);
+                println!("Option Type:        {}", if call_opt { "Call" } else { "Put" } );
+                println!("Spot Price:         {}", data.spot);
+                println!("Strike Price:       {}", data.strike);
+                println!("Years to Maturity:  {} ({:?})", (data.expiration * 1000000.0).round() / 1000000.0, day_count);
+                println!("Risk-Free Rate:     {}", data.rate);
+                println!("Volatility:         {}", data.volatility);
+                if let Some((_, source)) = &volatility {
+                    println!("Volatility Source:  {}", source);
+                }
+                println!("Steps:              {}", steps);
+
+                // Put-call parity on the expiry
+                if let Some(implied) = data.carry {
+
+                    println!("\n--------------------------------\n");
+
+                    println!("Implied Forward:    {}", (implied.forward * 10000.0).round() / 10000.0);
+                    if implied.from_curve {
+                        println!("Implied Rate:       {} (curve, the parity rate's standard error of {:.2}% is too wide)", (implied.rate * 1000000.0).round() / 1000000.0, implied.rate_error * 100.0);
+                    } else {
+                        println!("Implied Rate:       {} (\u{00B1}{:.2}%)", (implied.rate * 1000000.0).round() / 1000000.0, implied.rate_error * 100.0);
+                    }
+                    println!("Dividend/Borrow:    {}", (implied.dividend * 1000000.0).round() / 1000000.0);
+                    println!("Parity Strikes:     {} (RMSE {:.4})", implied.strikes, implied.fit_error);
+                }
 
+                println!("\n--------------------------------\n");
 
+                // Price using American Binomial Model
+                let model: f64 = binomial_with_carry(data.spot, data.strike, data.expiration, rate, carry, data.volatility, steps, call_opt, &Exercise::American);
+                println!("Option Price using Binomial Model:       {}", model);
+
+                // European reference price at the same carry
+                if data.carry.is_some() {
+                    let european: f64 = (generalized_black_scholes(data.spot, data.strike, data.expiration, rate, carry, data.volatility, call_opt) * 100.0).round() / 100.0;
+                    println!("Option Price using Black-Scholes Model:  {}", european);
+                }
+
+
+                // The listed quotes an interpolated vol came from
+                if let Some(fitted) = &data.smile {
+
+                    println!("\n--------------------------------\n");
+
+                    let name = |value: Option<clap::builder::PossibleValue>| value.map(|v| v.get_name().to_string()).unwrap_or_default();
+                    println!("Smile:              {} in {}", name(smile.unwrap_or(SmileMethod::Linear).to_possible_value()), name(smile_axis.to_possible_value()));
+                    if let Some(error) = fitted.fit_error {
+                        println!("Fit Error (RMSE):   {}%", (error * 1000000.0).round() / 10000.0);
+                    }
+                    println!("Quotes Used:        {}", fitted.quotes.iter().map(|(k, v)| format!("{} @ {:.2}%", k, v * 100.0)).collect::<Vec<String>>().join(", "));
+                }
+
+
+                // Market quote next to the model
+                println!("\n--------------------------------\n");
+
+                println!("Contract:           {}", if data.option_symbol.is_empty() { "not listed, vol from the smile" } else { &data.option_symbol });
+                println!("Bid / Ask:          {} / {}", show(data.bid), show(data.ask));
+                println!("Mid:                {}", show(data.mid));
+                println!("Last:               {}", show(data.last));
+                println!("Volume:             {}", data.volume);
+                println!("Open Interest:      {}", data.open_interest);
+                println!("Quoted At:          {}", data.quote_time.map(|t| t.to_string()).unwrap_or("-".to_string()));
+                println!("Underlying Bid/Ask: {} / {}", show(data.underlying_bid), show(data.underlying_ask));
+                println!("Underlying At:      {}", data.underlying_time.map(|t| t.to_string()).unwrap_or("-".to_string()));
+
+                // Positive edge means the market is richer than the model
+                if let Some(mid) = data.mid {
+                    println!("\nMarket Edge (Mid - Model):               {} ({}%)", ((mid - model) * 100.0).round() / 100.0, (((mid - model) / model) * 10000.0).round() / 100.0);
+                }
+
+
+                // Attempt to get the Greeks if the flag is specified
+                if greeks {
+
+                    // At the same rate and carry as the price, by finite differences on Black-Scholes when the carry came from the chain
+                    let dividend: f64 = rate - carry;
+                    let calculated: Result<Greeks, Box<dyn std::error::Error>> = match data.carry {
+                        Some(_) => Ok(numerical_greeks(data.spot, data.expiration, rate, data.volatility, |s, t, r, v| generalized_black_scholes(s, data.strike, t, r, r - dividend, v, call_opt))),
+                        None => calculate_greeks(data.spot, data.strike, data.expiration, rate, data.volatility, call_opt),
+                    };
+
+                    match calculated {
+                        Ok(greeks) => {
+
+                                // Output
+                                println!("\n--------------------------------\n");
+
+                                println!("Delta:              {}", (greeks.delta * 10000.0).round() / 10000.0);
+                                println!("Gamma:              {}", (greeks.gamma * 10000.0).round() / 10000.0);
+                                println!("Vega:               {}", (greeks.vega * 10000.0).round() / 10000.0);
+                                println!("Theta:              {}", (greeks.theta * 10000.0).round() / 10000.0);
+                                println!("Rho:                {}", (greeks.rho * 10000.0).round() / 10000.0);
+
+                                // Compare with the vendor's Greeks in the vendor's units, vega per vol point and theta per calendar day
+                                    // Theta is per year of the day count, which is 360 calendar days under ACT/360 and 30/360
+                                let vendor = data.vendor_greeks;
+                                let days_per_year: f64 = day_count.days_per_year(expirations[0].year());
+                                if [vendor.delta, vendor.gamma, vendor.vega, vendor.theta].iter().any(|g| g.is_some()) {
+
+                                    println!("\n--------------------------------\n");
+
+                                    println!("{:<8} {:>12} {:>12} {:>12}", "Greek", "RustQuant", "Vendor", "Difference");
+                                    for (name, ours, theirs) in [
+                                        ("Delta", greeks.delta, vendor.delta),
+                                        ("Gamma", greeks.gamma, vendor.gamma),
+                                        ("Vega", greeks.vega / 100.0, vendor.vega),
+                                        ("Theta", greeks.theta / days_per_year, vendor.theta),
+                                    ] {
+                                        println!("{:<8} {:>12.4} {:>12} {:>12}", name, ours, show4(theirs), show4(theirs.map(|t| ours - t)));
+                                    }
                                 }
-                                Err(e) => eprintln!("Failed to calculate the greeks: {}", e),
-                            }
+
+
                         }
+                        Err(e) => eprintln!("Failed to calculate the greeks: {}", e),
+                    }
                 }
-                Err(e) => eprintln!("Failed to fetch option data: {}", e),
-            }
         }
-        Err(e) => eprintln!("Failed to fetch expiration dates: {}", e),
+        Err(e) => eprintln!("Failed to fetch option data: {}", e),
     }
 }
 
+
+// Discount rate and cost of carry to price with, the implied ones if present or the curve rate with no dividend
+fn rates(data: &OptionData) -> (f64, f64) {
+    match data.carry {
+        Some(implied) => (implied.rate, implied.carry),
+        None => (data.rate, data.rate),
+    }
+}
+
+
+fn show(value: Option<f64>) -> String {
+    match value {
+        Some(value) => format!("{:.2}", value),
+        None => "-".to_string(),
+    }
+}
+
+fn show4(value: Option<f64>) -> String {
+    match value {
+        Some(value) => format!("{:.4}", value),
+        None => "-".to_string(),
+    }
+}
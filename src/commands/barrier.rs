@@ -0,0 +1,123 @@
+/*
+
+JDA Options Pricing
+/src/commands/barrier.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::binomial::Exercise;
+use crate::barrier::{BarrierType, barrier_option, binomial_barrier, discrete_barrier, double_barrier_option};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, strike: f64, time: f64, rates: RateArgs, volatility: f64, barrier: f64, upper: Option<f64>, rebate: f64, kind: BarrierType, monitoring: Option<f64>, steps: u32, call: bool, put: bool, american: bool, european: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    // Ensure option is either only American OR European
+    let mut american_opt: bool = american;
+
+    // If neither flag has been specified, default to American
+    if !american && !european { american_opt = true; } else if american && european {
+        // Check for double American/European flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option region.");
+        return;
+    }
+
+    // Double barriers only have a closed form, so they must be European
+    if upper.is_some() && american_opt {
+        eprintln!("ERROR: Double barrier options can only be priced as European, specify --european.");
+        return;
+    }
+
+    if upper.is_some_and(|u| u <= barrier) {
+        eprintln!("ERROR: Incorrect arguments, the upper barrier must be above the lower barrier.");
+        return;
+    }
+
+    // The Ikeda-Kunitomo closed form has no rebate
+    if upper.is_some() && rebate != 0.0 {
+        eprintln!("ERROR: Incorrect arguments, double barrier options are priced without a rebate, leave out --rebate.");
+        return;
+    }
+
+
+    // Shift the barriers for discrete monitoring if a monitoring interval was given
+    let lower_barrier: f64 = match monitoring {
+        Some(dt) => discrete_barrier(barrier, volatility, dt, upper.is_some() || kind.down()),
+        None => barrier,
+    };
+    let upper_barrier: Option<f64> = upper.map(|u| match monitoring {
+        Some(dt) => discrete_barrier(u, volatility, dt, false),
+        None => u,
+    });
+
+
+    // Output
+    println!("\u{1F4B0} Barrier Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {}", if call_opt { "Call" } else { "Put" } );
+    match upper {
+        Some(u) => {
+            println!("Barrier Type:       {}", if kind.knock_in() { "Double Knock-In" } else { "Double Knock-Out" });
+            println!("Lower Barrier:      {}", barrier);
+            println!("Upper Barrier:      {}", u);
+        }
+        None => {
+            println!("Barrier Type:       {:?}", kind);
+            println!("Barrier:            {}", barrier);
+            println!("Rebate:             {}", rebate);
+        }
+    }
+    println!("Spot Price:         {}", spot);
+    println!("Strike Price:       {}", strike);
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+    println!("Steps:              {}", steps);
+    match monitoring {
+        Some(dt) => println!("Monitoring:         Every {} years (adjusted barrier {})", dt, (lower_barrier * 10000.0).round() / 10000.0),
+        None => println!("Monitoring:         Continuous"),
+    }
+
+    println!("\n--------------------------------\n");
+
+    match upper_barrier {
+        Some(upper_barrier) => {
+            let price = double_barrier_option(spot, strike, lower_barrier, upper_barrier, time, rate, volatility, kind.knock_in(), call_opt);
+            println!("Option Price using Ikeda-Kunitomo Model:      {}", (price * 100.0).round() / 100.0);
+        }
+        None => {
+            match binomial_barrier(spot, strike, lower_barrier, rebate, time, rate, volatility, steps, kind, call_opt, if american_opt { &Exercise::American } else { &Exercise::European }) {
+                Ok(price) => println!("Option Price using Binomial Model:            {}", (price * 100.0).round() / 100.0),
+                Err(e) => eprintln!("ERROR: Incorrect arguments, {}", e),
+            }
+            if !american_opt {
+                let price = barrier_option(spot, strike, lower_barrier, rebate, time, rate, volatility, kind, call_opt);
+                println!("Option Price using Reiner-Rubinstein Model:   {}", (price * 100.0).round() / 100.0);
+            }
+        }
+    }
+}
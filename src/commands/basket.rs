@@ -0,0 +1,81 @@
+/*
+
+JDA Options Pricing
+/src/commands/basket.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::basket::{Basket, basket_moment_matching, monte_carlo_basket};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spots: Vec<f64>, volatilities: Vec<f64>, weights: Vec<f64>, correlations: Vec<f64>, strike: f64, time: f64, rates: RateArgs, futures: bool, paths: u32, seed: Option<u64>, call: bool, put: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    if spots.len() < 2 {
+        eprintln!("ERROR: Missing arguments, a basket needs at least two assets.");
+        return;
+    }
+
+    // Default to an equally weighted basket
+    let weights: Vec<f64> = if weights.is_empty() { vec![1.0 / (spots.len() as f64); spots.len()] } else { weights };
+
+    // Futures cost nothing to carry, stocks earn the risk free rate
+    let carries: Vec<f64> = vec![if futures { 0.0 } else { rate }; spots.len()];
+
+    let basket = match Basket::new(spots, volatilities, weights, carries, &correlations) {
+        Ok(basket) => basket,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Basket Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {}", if call_opt { "Call" } else { "Put" } );
+    println!("Spot Prices:        {:?}", basket.spots);
+    println!("Volatilities:       {:?}", basket.volatilities);
+    println!("Weights:            {:?}", basket.weights);
+    println!("Strike Price:       {}", strike);
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Paths:              {}", paths);
+
+    println!("\n--------------------------------\n");
+
+    // A lognormal can only stand in for a basket that is long every asset
+    if basket.weights.iter().all(|w| *w > 0.0) {
+        println!("Option Price using Moment Matching:           {}", (basket_moment_matching(&basket, strike, time, rate, call_opt) * 100.0).round() / 100.0);
+    }
+
+    match monte_carlo_basket(&basket, strike, time, rate, paths, seed, call_opt) {
+        Ok(simulated) => println!("Option Price using Monte Carlo:               {} (std. error {})", (simulated.price * 100.0).round() / 100.0, (simulated.std_error * 10000.0).round() / 10000.0),
+        Err(e) => eprintln!("ERROR: Incorrect arguments, {}", e),
+    }
+}
@@ -0,0 +1,157 @@
+/*
+
+JDA Options Pricing
+/src/commands/bond.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::bond::Bond;
+use crate::yield_curve::{Interpolation, YieldCurve};
+use crate::short_rate::{Cir, HullWhite, ShortRateModel, ShortRateType, Vasicek, coupon_bond_option};
+use crate::trinomial::{HullWhiteTree, tree_bond, tree_bond_option};
+
+
+// Entry point for the command
+pub fn run(model: ShortRateType, rate: f64, curve_file: Option<String>, interpolation: Interpolation, speed: f64, mean: Option<f64>, volatility: f64, bond: Bond, strike: Option<f64>, expiry: Option<f64>, call_price: Option<f64>, call_from: Option<f64>, put_price: Option<f64>, put_from: Option<f64>, steps: u32, call: bool, put: bool) {
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    if speed <= 0.0 || volatility <= 0.0 || bond.maturity <= 0.0 {
+        eprintln!("ERROR: Incorrect arguments, the speed, volatility and maturity must be positive.");
+        return;
+    }
+
+    // The bond option needs both a strike and an expiry before the bond matures
+    let option: Option<(f64, f64)> = match (strike, expiry) {
+        (Some(strike), Some(expiry)) if expiry > 0.0 && expiry < bond.maturity => Some((strike, expiry)),
+        (None, None) => None,
+        _ => {
+            eprintln!("ERROR: Incorrect arguments, a bond option needs a strike and an expiry before maturity.");
+            return;
+        }
+    };
+
+    // Embedded calls and puts are exercisable from the first coupon date unless a later date is given
+    let embedded_call: Option<(f64, f64)> = call_price.map(|price| (price, call_from.unwrap_or(0.0)));
+    let embedded_put: Option<(f64, f64)> = put_price.map(|price| (price, put_from.unwrap_or(0.0)));
+
+    // Vasicek and CIR revert to the current rate unless a long run mean is given
+    let mean: f64 = mean.unwrap_or(rate);
+
+    // Hull-White is fitted to today's curve, bootstrapped from a file or taken as flat at the current rate
+    let yield_curve: YieldCurve = match &curve_file {
+        Some(path) => match YieldCurve::from_csv(path, interpolation) {
+            Ok(curve) => curve,
+            Err(e) => {
+                eprintln!("ERROR: Incorrect arguments, {}", e);
+                return;
+            }
+        },
+        None => YieldCurve::flat(rate),
+    };
+    let curve = |t: f64| yield_curve.discount(t);
+
+
+    // Output
+    println!("\u{1F4B0} Bond Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Model:              {}", match model { ShortRateType::Vasicek => "Vasicek", ShortRateType::Cir => "Cox-Ingersoll-Ross", ShortRateType::HullWhite => "Hull-White" });
+    match (&curve_file, model) {
+        (Some(path), ShortRateType::HullWhite) => println!("Yield Curve:        {}", path),
+        _ => println!("Short Rate:         {}", rate),
+    }
+    println!("Reversion Speed:    {}", speed);
+    if model != ShortRateType::HullWhite { println!("Long Run Mean:      {}", mean); }
+    println!("Volatility:         {}", volatility);
+    println!("Face Value:         {}", bond.face);
+    println!("Coupon Rate:        {} ({} per year)", bond.coupon, bond.frequency);
+    println!("Years to Maturity:  {}", bond.maturity);
+    if let Some((strike, expiry)) = option {
+        println!("Option Type:        {}", if call_opt { "Call" } else { "Put" });
+        println!("Strike Price:       {}", strike);
+        println!("Years to Expiry:    {}", expiry);
+    }
+    if let Some((price, first)) = embedded_call { println!("Callable:           at {} from {} years", price, first); }
+    if let Some((price, first)) = embedded_put { println!("Putable:            at {} from {} years", price, first); }
+    println!("Steps:              {}", steps);
+
+    println!("\n--------------------------------\n");
+
+    match model {
+        ShortRateType::Vasicek => {
+            let vasicek = Vasicek { rate, speed, mean, volatility };
+            report(&vasicek, |t| vasicek.discount(t), speed, volatility, &bond, option, embedded_call, embedded_put, steps, call_opt);
+        }
+        ShortRateType::HullWhite => {
+            let hull_white = HullWhite { speed, volatility, curve };
+            report(&hull_white, curve, speed, volatility, &bond, option, embedded_call, embedded_put, steps, call_opt);
+        }
+        ShortRateType::Cir => {
+            let cir = Cir { rate, speed, mean, volatility };
+
+            println!("Zero Coupon Yield:                            {}%", (-1.0 * cir.discount(bond.maturity).ln() / bond.maturity * 10000.0).round() / 100.0);
+            println!("Bond Price:                                   {}", (bond.price(|t| cir.discount(t)) * 100.0).round() / 100.0);
+
+            if let Some((strike, expiry)) = option {
+                println!();
+                match coupon_bond_option(&cir, &bond, strike, expiry, call_opt) {
+                    Ok(price) => println!("Option Price using Jamshidian:                {}", (price * 10000.0).round() / 10000.0),
+                    Err(e) => eprintln!("ERROR: Incorrect arguments, {}", e),
+                }
+            }
+
+            // The trinomial tree is built for gaussian rates
+            if embedded_call.is_some() || embedded_put.is_some() {
+                eprintln!("ERROR: Callable and putable bonds are priced on the Hull-White tree, use the vasicek or hull-white model.");
+            }
+        }
+    }
+}
+
+
+// Print the closed form prices alongside the trinomial tree for a gaussian model
+    // The tree is fitted to the model's own discount curve, so for Vasicek it is the Vasicek tree
+fn report<M: ShortRateModel, D: Fn(f64) -> f64>(model: &M, curve: D, speed: f64, volatility: f64, bond: &Bond, option: Option<(f64, f64)>, embedded_call: Option<(f64, f64)>, embedded_put: Option<(f64, f64)>, steps: u32, call: bool) {
+
+    let tree = match HullWhiteTree::new(speed, volatility, &curve, bond.maturity, steps) {
+        Ok(tree) => tree,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    println!("Zero Coupon Yield:                            {}%", (-1.0 * model.discount(bond.maturity).ln() / bond.maturity * 10000.0).round() / 100.0);
+    println!("Bond Price:                                   {}", (bond.price(|t| model.discount(t)) * 100.0).round() / 100.0);
+    println!("Bond Price using Trinomial Tree:              {}", (tree_bond(&tree, bond, None, None) * 100.0).round() / 100.0);
+
+    if let Some((strike, expiry)) = option {
+        println!();
+        match coupon_bond_option(model, bond, strike, expiry, call) {
+            Ok(price) => println!("Option Price using Jamshidian:                {}", (price * 10000.0).round() / 10000.0),
+            Err(e) => eprintln!("ERROR: Incorrect arguments, {}", e),
+        }
+        println!("Option Price using Trinomial Tree:            {}", (tree_bond_option(&tree, bond, strike, expiry, call) * 10000.0).round() / 10000.0);
+    }
+
+    if embedded_call.is_some() || embedded_put.is_some() {
+        println!();
+        let label: &str = match (embedded_call, embedded_put) {
+            (Some(_), Some(_)) => "Callable/Putable Bond Price:",
+            (Some(_), None) => "Callable Bond Price:",
+            _ => "Putable Bond Price:",
+        };
+        println!("{:<46}{}", label, (tree_bond(&tree, bond, embedded_call, embedded_put) * 100.0).round() / 100.0);
+    }
+}
@@ -0,0 +1,79 @@
+/*
+
+JDA Options Pricing
+/src/commands/cap.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::yield_curve::{Interpolation, YieldCurve};
+use crate::rate_options::{Caplet, RateModel, Volatility, cap_floor};
+
+
+// Entry point for the command
+pub fn run(rate: Option<f64>, curve_file: Option<String>, interpolation: Interpolation, strike: f64, maturity: f64, frequency: u32, notional: f64, model: RateModel, volatility: Option<f64>, cube: Option<String>, floor: bool) {
+
+    let volatility: Volatility = match Volatility::load(volatility, cube) {
+        Ok(volatility) => volatility,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    if frequency == 0 {
+        eprintln!("ERROR: Incorrect arguments, the frequency must be at least one payment a year.");
+        return;
+    }
+
+    // Discount curve, flat at the given rate or bootstrapped from a file
+    let curve: YieldCurve = match YieldCurve::load(rate, curve_file.clone(), interpolation) {
+        Ok(curve) => curve,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    let caplets: Vec<Caplet> = match cap_floor(|t| curve.discount(t), strike, maturity, frequency, notional, &volatility, model, !floor) {
+        Ok(caplets) => caplets,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Cap & Floor Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {}", if floor { "Floor" } else { "Cap" });
+    println!("Model:              {}", if model == RateModel::Black { "Black-76" } else { "Bachelier" });
+    println!("Strike Rate:        {}", strike);
+    println!("Years to Maturity:  {}", maturity);
+    println!("Payments per Year:  {}", frequency);
+    println!("Notional:           {}", notional);
+    match (&curve_file, rate) {
+        (Some(path), _) => println!("Yield Curve:        {}", path),
+        (None, Some(rate)) => println!("Risk-Free Rate:     {}", rate),
+        _ => {}
+    }
+    match &volatility {
+        Volatility::Flat(vol) => println!("Volatility:         {}", vol),
+        Volatility::Cube(cube) => println!("Volatility:         cube ({} expiries, {} tenors, {} strikes)", cube.expiries.len(), cube.tenors.len(), cube.strikes.len()),
+    }
+
+    println!("\n--------------------------------\n");
+
+    println!("{:>8} {:>8} {:>10} {:>10} {:>12} {:>12}", "Fixing", "Payment", "Forward", "Vol", "Price", "Vega");
+    for caplet in &caplets {
+        println!("{:>8.2} {:>8.2} {:>9.4}% {:>10.4} {:>12.4} {:>12.4}", caplet.fixing, caplet.payment, caplet.forward * 100.0, caplet.volatility, caplet.price, caplet.vega);
+    }
+
+    println!("\n--------------------------------\n");
+
+    println!("{:<46}{}", if floor { "Floor Price:" } else { "Cap Price:" }, (caplets.iter().map(|c| c.price).sum::<f64>() * 10000.0).round() / 10000.0);
+    println!("Vega:                                         {}", (caplets.iter().map(|c| c.vega).sum::<f64>() * 10000.0).round() / 10000.0);
+}
@@ -0,0 +1,87 @@
+/*
+
+JDA Options Pricing
+/src/commands/carry.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::day_count::DayCount;
+use crate::expiration::{ExpirationArgs, select_expirations};
+use crate::fetch::{fetch_treasury_curve, settlement_symbol, time_to_expiry};
+use crate::implied_carry::implied_carry;
+use crate::provider::{MarketDataProvider, Provider, ProviderArgs};
+use crate::yield_curve::Interpolation;
+
+
+// Entry point for the command
+pub async fn run(symbol: String, expiration: ExpirationArgs, day_count: DayCount, provider: ProviderArgs) {
+
+    // Connect to the market data source
+    let provider: Provider = match Provider::load(&provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Failed to load the market data provider: {}", e);
+            return;
+        }
+    };
+
+
+    // Get the expiration dates to use, from the arguments or the drop down
+    let expirations = match select_expirations(&provider, &symbol, true, &expiration).await {
+        Ok(expirations) => expirations,
+        Err(e) => {
+            eprintln!("Failed to choose an expiration date: {}", e);
+            return;
+        }
+    };
+
+
+    // The spot and the curve are shared by every expiry
+    let market = async {
+        let spot = provider.quote(&symbol).await?.last;
+        let curve = fetch_treasury_curve(&provider, Interpolation::MonotoneCubic).await?;
+        Ok::<_, Box<dyn std::error::Error>>((spot, curve))
+    };
+
+    let (spot, curve) = match market.await {
+        Ok(market) => market,
+        Err(e) => {
+            eprintln!("Failed to fetch market data: {}", e);
+            return;
+        }
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Implied Carry Tool");
+    println!("--------------------------------\n");
+
+    println!("Symbol:             {}", symbol);
+    println!("Spot Price:         {}", spot);
+
+    println!("\n--------------------------------\n");
+
+    println!("{:>12} {:>10} {:>10} {:>9} {:>10} {:>9} {:>10} {:>11} {:>8} {:>8}", "Expiration", "Years", "Forward", "Discount", "Implied", "\u{00B1}", "Curve", "Div/Borrow", "Strikes", "RMSE");
+    for date in expirations {
+
+        // Regress call minus put mids on the strike
+        let implied = async {
+            let calls = provider.chain(&symbol, date, true).await?;
+            let puts = provider.chain(&symbol, date, false).await?;
+            let (time, calendar_years) = time_to_expiry(&provider, settlement_symbol(&calls, &symbol), date, day_count)?;
+            let implied = implied_carry(&calls, &puts, spot, time, curve.discount(calendar_years))?;
+            Ok::<_, Box<dyn std::error::Error>>((time, curve.zero_rate(calendar_years), implied))
+        };
+
+        match implied.await {
+            Ok((time, curve_rate, implied)) => println!("{:>12} {:>10.6} {:>10.4} {:>9.6} {:>10} {:>8.4}% {:>9.4}% {:>10.4}% {:>8} {:>8.4}",
+                date.to_string(), time, implied.forward, implied.discount, if implied.from_curve { "curve".to_string() } else { format!("{:.4}%", implied.rate * 100.0) },
+                implied.rate_error.min(9.9999) * 100.0, curve_rate * 100.0, implied.dividend * 100.0, implied.strikes, implied.fit_error),
+            Err(e) => eprintln!("{:>12} Failed to back out the carry: {}", date.to_string(), e),
+        }
+    }
+
+    println!("\nImplied rates with a standard error over 0.5% are replaced by the curve's and only the forward is backed out");
+}
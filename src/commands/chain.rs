@@ -0,0 +1,158 @@
+/*
+
+JDA Options Pricing
+/src/commands/chain.rs
+JohnDavid Abe
+
+*/
+
+
+use chrono::NaiveDate;
+use crate::binomial::{Exercise, binomial};
+use crate::day_count::DayCount;
+use crate::expiration::{ExpirationArgs, select_expirations};
+use crate::fetch::{fetch_treasury_curve, settlement_symbol, time_to_expiry};
+use crate::greeks::calculate_greeks;
+use crate::provider::{ChainQuote, MarketDataProvider, Provider, ProviderArgs};
+use crate::yield_curve::{Interpolation, YieldCurve};
+
+
+// Entry point for the command
+pub async fn run(symbol: String, expiration: ExpirationArgs, steps: u32, threshold: f64, day_count: DayCount, provider: ProviderArgs) {
+
+    // Check arguments
+    if threshold < 0.0 {
+        eprintln!("ERROR: Incorrect arguments, the threshold can't be negative.");
+        return;
+    }
+
+
+    // Connect to the market data source
+    let provider: Provider = match Provider::load(&provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Failed to load the market data provider: {}", e);
+            return;
+        }
+    };
+
+
+    // Get the expiration dates to show, from the arguments or the drop down
+    let expirations = match select_expirations(&provider, &symbol, true, &expiration).await {
+        Ok(expirations) => expirations,
+        Err(e) => {
+            eprintln!("Failed to choose an expiration date: {}", e);
+            return;
+        }
+    };
+
+
+    // The spot and the curve are shared by every expiry
+    let market = async {
+        let spot = provider.quote(&symbol).await?.last;
+        let curve = fetch_treasury_curve(&provider, Interpolation::MonotoneCubic).await?;
+        Ok::<_, Box<dyn std::error::Error>>((spot, curve))
+    };
+
+    let (spot, curve) = match market.await {
+        Ok(market) => market,
+        Err(e) => {
+            eprintln!("Failed to fetch market data: {}", e);
+            return;
+        }
+    };
+
+    for expiration in expirations {
+        show_chain(&provider, &symbol, expiration, spot, &curve, steps, threshold, day_count).await;
+    }
+}
+
+
+// Print one expiration's chain
+async fn show_chain(provider: &Provider, symbol: &str, expiration: NaiveDate, spot: f64, curve: &YieldCurve, steps: u32, threshold: f64, day_count: DayCount) {
+
+    // Pull both sides of the chain and the time and rate to expiry
+    let market = async {
+        let calls = provider.chain(symbol, expiration, true).await?;
+        let puts = provider.chain(symbol, expiration, false).await?;
+        let (time, calendar_years) = time_to_expiry(provider, settlement_symbol(&calls, symbol), expiration, day_count)?;
+        Ok::<_, Box<dyn std::error::Error>>((calls, puts, time, curve.zero_rate(calendar_years)))
+    };
+
+    let (calls, puts, time, rate) = match market.await {
+        Ok(market) => market,
+        Err(e) => {
+            eprintln!("Failed to fetch the option chain: {}", e);
+            return;
+        }
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Option Chain Tool");
+    println!("--------------------------------\n");
+
+    println!("Symbol:             {}", symbol);
+    println!("Expiration:         {}", expiration);
+    println!("Spot Price:         {}", spot);
+    println!("Years to Maturity:  {} ({:?})", (time * 1000000.0).round() / 1000000.0, day_count);
+    println!("Risk-Free Rate:     {}", (rate * 1000000.0).round() / 1000000.0);
+    println!("Steps:              {}", steps);
+    println!("Edge Threshold:     {}%", threshold * 100.0);
+
+    let mut flagged: usize = 0;
+    for (call, quotes) in [(true, &calls), (false, &puts)] {
+
+        println!("\n--------------------------------\n");
+        println!("{}\n", if call { "Calls" } else { "Puts" });
+
+        println!("{:>9} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>7} {:>8} {:>8} {:>8} {:>7} {:>7} {:>8} {:>9}",
+            "Strike", "Bid", "Ask", "Mid", "Last", "Volume", "OI", "IV", "Model", "Edge", "Delta", "Gamma", "Vega", "Theta", "");
+
+        for quote in quotes.iter() {
+            let row = price_quote(quote, spot, time, rate, steps, call);
+            let flag: bool = matches!(row.edge, Some(edge) if row.model > 0.0 && (edge / row.model).abs() > threshold);
+            if flag { flagged += 1; }
+
+            println!("{:>9} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>6.2}% {:>8.2} {:>8} {:>8.4} {:>7.4} {:>7.2} {:>8.2} {:>9}",
+                quote.strike, show(quote.bid), show(quote.ask), show(quote.mid), show(quote.last), quote.volume, quote.open_interest,
+                quote.volatility * 100.0, row.model, show(row.edge), row.delta, row.gamma, row.vega, row.theta, if flag { "\u{2190} edge" } else { "" });
+        }
+    }
+
+    println!("\n--------------------------------\n");
+    println!("{} contracts with a mid more than {}% away from the model price", flagged, threshold * 100.0);
+}
+
+
+// Model price and Greeks for one contract, priced on the American binomial tree at the market's implied vol
+struct Row {
+    model: f64,
+    edge: Option<f64>,
+    delta: f64,
+    gamma: f64,
+    vega: f64,
+    theta: f64,
+}
+
+fn price_quote(quote: &ChainQuote, spot: f64, time: f64, rate: f64, steps: u32, call: bool) -> Row {
+
+    let model: f64 = binomial(spot, quote.strike, time, rate, quote.volatility, steps, call, &Exercise::American);
+
+    let mid: Option<f64> = quote.mid_price();
+
+    let (delta, gamma, vega, theta) = match calculate_greeks(spot, quote.strike, time, rate, quote.volatility, call) {
+        Ok(greeks) => (greeks.delta, greeks.gamma, greeks.vega, greeks.theta),
+        Err(_) => (f64::NAN, f64::NAN, f64::NAN, f64::NAN),
+    };
+
+    return Row { model, edge: mid.map(|mid| ((mid - model) * 100.0).round() / 100.0), delta, gamma, vega, theta };
+}
+
+
+fn show(value: Option<f64>) -> String {
+    match value {
+        Some(value) => format!("{:.2}", value),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,74 @@
+/*
+
+JDA Options Pricing
+/src/commands/chooser.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::chooser::{complex_chooser, monte_carlo_chooser, simple_chooser};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, strike: f64, time: f64, choose_time: f64, put_strike: Option<f64>, put_time: Option<f64>, rates: RateArgs, volatility: f64, paths: u32, seed: Option<u64>) {
+
+    // Risk free rate, flat or the curve's zero rate to the option's last expiry
+    let rate: f64 = match rates.zero_rate(time.max(put_time.unwrap_or(time))) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // A different put strike or expiry makes it a complex chooser
+    let complex: bool = put_strike.is_some() || put_time.is_some();
+    let put_strike: f64 = put_strike.unwrap_or(strike);
+    let put_time: f64 = put_time.unwrap_or(time);
+
+    if choose_time <= 0.0 || choose_time >= time.min(put_time) {
+        eprintln!("ERROR: Incorrect arguments, the choice must be made before either option expires.");
+        return;
+    }
+
+
+    // Output
+    println!("\u{1F4B0} Chooser Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {} Chooser", if complex { "Complex" } else { "Simple" });
+    println!("Spot Price:         {}", spot);
+    println!("Years to Choice:    {}", choose_time);
+    if complex {
+        println!("Call Strike:        {}", strike);
+        println!("Call Maturity:      {}", time);
+        println!("Put Strike:         {}", put_strike);
+        println!("Put Maturity:       {}", put_time);
+    } else {
+        println!("Strike Price:       {}", strike);
+        println!("Years to Maturity:  {}", time);
+    }
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+    println!("Paths:              {}", paths);
+
+    println!("\n--------------------------------\n");
+
+    if complex {
+        match complex_chooser(spot, strike, time, put_strike, put_time, choose_time, rate, volatility) {
+            Ok(price) => println!("Option Price using Rubinstein Model:          {}", (price * 100.0).round() / 100.0),
+            Err(e) => {
+                eprintln!("ERROR: Incorrect arguments, {}", e);
+                return;
+            }
+        }
+    } else {
+        println!("Option Price using Rubinstein Model:          {}", (simple_chooser(spot, strike, time, choose_time, rate, volatility) * 100.0).round() / 100.0);
+    }
+
+    let simulated = monte_carlo_chooser(spot, strike, time, put_strike, put_time, choose_time, rate, volatility, paths, seed);
+    println!("Option Price using Monte Carlo:               {} (std. error {})", (simulated.price * 100.0).round() / 100.0, (simulated.std_error * 10000.0).round() / 10000.0);
+}
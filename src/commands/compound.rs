@@ -0,0 +1,66 @@
+/*
+
+JDA Options Pricing
+/src/commands/compound.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::compound::{compound_option, monte_carlo_compound};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, strike: f64, time: f64, compound_strike: f64, compound_time: f64, rates: RateArgs, volatility: f64, underlying_put: bool, paths: u32, seed: Option<u64>, call: bool, put: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to the underlying option's expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    let underlying_call: bool = !underlying_put;
+
+
+    // Output
+    println!("\u{1F4B0} Compound Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {} on {}", if call_opt { "Call" } else { "Put" }, if underlying_call { "Call" } else { "Put" });
+    println!("Spot Price:         {}", spot);
+    println!("Compound Strike:    {}", compound_strike);
+    println!("Compound Maturity:  {}", compound_time);
+    println!("Underlying Strike:  {}", strike);
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+    println!("Paths:              {}", paths);
+
+    println!("\n--------------------------------\n");
+
+    match compound_option(spot, strike, time, compound_strike, compound_time, rate, volatility, call_opt, underlying_call) {
+        Ok(price) => println!("Option Price using Geske Model:               {}", (price * 100.0).round() / 100.0),
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    }
+
+    let simulated = monte_carlo_compound(spot, strike, time, compound_strike, compound_time, rate, volatility, paths, seed, call_opt, underlying_call);
+    println!("Option Price using Monte Carlo:               {} (std. error {})", (simulated.price * 100.0).round() / 100.0, (simulated.std_error * 10000.0).round() / 10000.0);
+}
@@ -0,0 +1,70 @@
+/*
+
+JDA Options Pricing
+/src/commands/curve.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::fetch::fetch_treasury_curve;
+use crate::provider::{Provider, ProviderArgs};
+use crate::yield_curve::{Interpolation, YieldCurve};
+
+
+// Entry point for the command
+pub async fn run(file: Option<String>, interpolation: Interpolation, at: Vec<f64>, provider: ProviderArgs) {
+
+    // Bootstrap from a local file of par yields if one is given, otherwise from the latest Treasury yields
+    let (curve, source): (YieldCurve, String) = match &file {
+        Some(path) => match YieldCurve::from_csv(path, interpolation) {
+            Ok(curve) => (curve, path.clone()),
+            Err(e) => {
+                eprintln!("ERROR: Incorrect arguments, {}", e);
+                return;
+            }
+        },
+        None => match Provider::load(&provider) {
+            Ok(provider) => match fetch_treasury_curve(&provider, interpolation).await {
+                Ok(curve) => (curve, provider.source()),
+                Err(e) => {
+                    eprintln!("Failed to fetch the treasury curve: {}", e);
+                    return;
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to load the market data provider: {}", e);
+                return;
+            }
+        },
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Yield Curve Tool");
+    println!("--------------------------------\n");
+
+    println!("Source:             {}", if file.is_some() { source } else { format!("US Treasury (FRED, {})", source) });
+    println!("Interpolation:      {}", match interpolation {
+        Interpolation::LinearZero => "Linear on zero rates",
+        Interpolation::LogLinearDiscount => "Log-linear on discount factors",
+        Interpolation::MonotoneCubic => "Monotone cubic",
+        Interpolation::NelsonSiegelSvensson => "Nelson-Siegel-Svensson",
+    });
+
+    println!("\n--------------------------------\n");
+
+    // Pillars, then any extra maturities asked for
+    let mut times: Vec<f64> = curve.times.clone();
+    times.extend(at);
+    times.sort_by(|a, b| a.total_cmp(b));
+    times.dedup();
+
+    println!("{:>8} {:>12} {:>12} {:>14}", "Years", "Zero Rate", "Discount", "Forward");
+    let mut previous: f64 = 0.0;
+    for time in times {
+        let forward: f64 = if previous > 0.0 { curve.forward_rate(previous, time) } else { curve.zero_rate(time) };
+        println!("{:>8.4} {:>11.4}% {:>12.6} {:>13.4}%", time, curve.zero_rate(time) * 100.0, curve.discount(time), forward * 100.0);
+        previous = time;
+    }
+}
@@ -0,0 +1,144 @@
+/*
+
+JDA Options Pricing
+/src/commands/density.rs
+JohnDavid Abe
+
+*/
+
+
+use std::fs;
+use chrono::NaiveDate;
+use crate::day_count::DayCount;
+use crate::density::RiskNeutralDensity;
+use crate::expiration::{ExpirationArgs, select_expirations};
+use crate::fetch::{fetch_treasury_curve, out_of_the_money_quotes, settlement_symbol, time_to_expiry};
+use crate::provider::{MarketDataProvider, Provider, ProviderArgs};
+use crate::svi::Svi;
+use crate::yield_curve::{Interpolation, YieldCurve};
+
+
+// Entry point for the command
+pub async fn run(symbol: String, expiration: ExpirationArgs, levels: Vec<f64>, points: usize, csv: Option<String>, day_count: DayCount, provider: ProviderArgs) {
+
+    // Check arguments
+    if points < 3 || levels.iter().any(|level| *level <= 0.0) {
+        eprintln!("ERROR: Incorrect arguments, use at least three points and positive levels.");
+        return;
+    }
+
+
+    // Connect to the market data source
+    let provider: Provider = match Provider::load(&provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Failed to load the market data provider: {}", e);
+            return;
+        }
+    };
+
+
+    // Get the expiration dates to use, from the arguments or the drop down
+    let expirations = match select_expirations(&provider, &symbol, true, &expiration).await {
+        Ok(expirations) => expirations,
+        Err(e) => {
+            eprintln!("Failed to choose an expiration date: {}", e);
+            return;
+        }
+    };
+
+
+    // The spot and the curve are shared by every expiry
+    let market = async {
+        let spot = provider.quote(&symbol).await?.last;
+        let curve = fetch_treasury_curve(&provider, Interpolation::MonotoneCubic).await?;
+        Ok::<_, Box<dyn std::error::Error>>((spot, curve))
+    };
+
+    let (spot, curve) = match market.await {
+        Ok(market) => market,
+        Err(e) => {
+            eprintln!("Failed to fetch market data: {}", e);
+            return;
+        }
+    };
+
+    // Levels default to 5% and 10% either side of the spot
+    let levels: Vec<f64> = if levels.is_empty() { [0.9, 0.95, 1.05, 1.1].iter().map(|m| (spot * m * 100.0).round() / 100.0).collect() } else { levels };
+
+    let mut rows: String = "expiration,strike,log_moneyness,density,probability_below\n".to_string();
+    for date in expirations {
+        match fit_density(&provider, &symbol, date, spot, &curve, points, day_count).await {
+            Ok((density, fit_error, quotes)) => {
+                show_density(&symbol, date, spot, &density, fit_error, quotes, &levels);
+                for (strike, value) in density.points.iter() {
+                    rows += &format!("{},{},{},{},{}\n", date, strike, (strike / density.forward).ln(), value, density.probability_below(*strike));
+                }
+            }
+            Err(e) => eprintln!("{} Failed to extract the density: {}", date, e),
+        }
+    }
+
+    if let Some(path) = csv {
+        match fs::write(&path, rows) {
+            Ok(_) => println!("\nWrote {}", path),
+            Err(e) => eprintln!("Failed to write {}: {}", path, e),
+        }
+    }
+}
+
+
+// Fit SVI to the out-of-the-money quotes of one expiry and read the density off it, with the fit error in vol and the quotes used
+async fn fit_density(provider: &Provider, symbol: &str, expiration: NaiveDate, spot: f64, curve: &YieldCurve, points: usize, day_count: DayCount) -> Result<(RiskNeutralDensity, f64, usize), Box<dyn std::error::Error>> {
+
+    let calls = provider.chain(symbol, expiration, true).await?;
+    let puts = provider.chain(symbol, expiration, false).await?;
+
+    let (time, calendar_years) = time_to_expiry(provider, settlement_symbol(&calls, symbol), expiration, day_count)?;
+    let (forward, quotes) = out_of_the_money_quotes(&calls, &puts, spot, time, calendar_years, curve);
+
+    let total_variance: Vec<(f64, f64)> = quotes.iter().map(|(k, v)| (*k, v * v * time)).collect();
+    let (smile, _) = Svi::fit(&total_variance)?;
+    let fit_error: f64 = (quotes.iter().map(|(k, v)| (smile.volatility(*k, time) - v).powi(2)).sum::<f64>() / (quotes.len() as f64)).sqrt();
+
+    return Ok((RiskNeutralDensity::from_smile(smile, forward, time, points)?, fit_error, quotes.len()));
+}
+
+
+// Print one expiry's density summary
+fn show_density(symbol: &str, expiration: NaiveDate, spot: f64, density: &RiskNeutralDensity, fit_error: f64, quotes: usize, levels: &[f64]) {
+
+    let moments = density.moments();
+    let expected_move: f64 = density.expected_move();
+
+    // Output
+    println!("\u{1F4B0} Risk-Neutral Density Tool");
+    println!("--------------------------------\n");
+
+    println!("Symbol:             {}", symbol);
+    println!("Expiration:         {}", expiration);
+    println!("Spot Price:         {}", spot);
+    println!("Forward:            {}", (density.forward * 10000.0).round() / 10000.0);
+    println!("Years to Maturity:  {}", (density.time * 1000000.0).round() / 1000000.0);
+    println!("Smile:              SVI on {} quotes (RMSE {:.3}%)", quotes, fit_error * 100.0);
+
+    println!("\n--------------------------------\n");
+
+    println!("Probability Mass:   {:.4}", density.mass());
+    println!("Mean:               {:.4}", moments.mean);
+    println!("Volatility:         {:.2}%", moments.volatility * 100.0);
+    println!("Skewness:           {:.4}", moments.skewness);
+    println!("Excess Kurtosis:    {:.4}", moments.kurtosis);
+    println!("Expected Move:      \u{00B1}{:.2} ({:.2}% of spot)", expected_move, expected_move / spot * 100.0);
+    if density.negative() {
+        println!("\nWARNING: the density goes negative, the fitted smile has butterfly arbitrage");
+    }
+
+    println!("\n--------------------------------\n");
+
+    println!("{:>10} {:>10} {:>10}", "Level", "P(Below)", "P(Above)");
+    for level in levels {
+        println!("{:>10} {:>9.2}% {:>9.2}%", level, density.probability_below(*level) * 100.0, density.probability_above(*level) * 100.0);
+    }
+    println!();
+}
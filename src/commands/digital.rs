@@ -0,0 +1,114 @@
+/*
+
+JDA Options Pricing
+/src/commands/digital.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::digital::{DigitalType, asset_or_nothing, call_spread_replication, cash_or_nothing, gap, supershare};
+use crate::black_scholes::generalized_black_scholes;
+use crate::greeks::numerical_greeks;
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, strike: f64, time: f64, rates: RateArgs, volatility: f64, kind: DigitalType, payout: f64, trigger: Option<f64>, upper: Option<f64>, width: Option<f64>, call: bool, put: bool, greeks: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    // Gap options need a trigger and supershares an upper strike
+    if kind == DigitalType::Gap && trigger.is_none() {
+        eprintln!("ERROR: Missing arguments, gap options need a --trigger.");
+        return;
+    }
+    if kind == DigitalType::Supershare && upper.is_none_or(|u| u <= strike) {
+        eprintln!("ERROR: Missing arguments, supershares need an --upper strike above the strike.");
+        return;
+    }
+
+    // Pricer in terms of the inputs the greeks are bumped on
+    let price = |s: f64, t: f64, r: f64, v: f64| match kind {
+        DigitalType::Cash => cash_or_nothing(s, strike, payout, t, r, v, call_opt),
+        DigitalType::Asset => asset_or_nothing(s, strike, t, r, v, call_opt),
+        DigitalType::Gap => gap(s, strike, trigger.unwrap_or(strike), t, r, v, call_opt),
+        DigitalType::Supershare => supershare(s, strike, upper.unwrap_or(strike), t, r, v),
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Digital Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    match kind {
+        DigitalType::Supershare => println!("Option Type:        Supershare"),
+        _ => println!("Option Type:        {:?} {}", kind, if call_opt { "Call" } else { "Put" }),
+    }
+    println!("Spot Price:         {}", spot);
+    println!("Strike Price:       {}", strike);
+    match kind {
+        DigitalType::Cash => println!("Payout:             {}", payout),
+        DigitalType::Gap => println!("Trigger Price:      {}", trigger.unwrap_or(strike)),
+        DigitalType::Supershare => println!("Upper Strike:       {}", upper.unwrap_or(strike)),
+        DigitalType::Asset => {}
+    }
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+
+    println!("\n--------------------------------\n");
+
+    println!("Option Price using Black-Scholes Model:       {}", (price(spot, time, rate, volatility) * 100.0).round() / 100.0);
+
+    // Check digitals against a tight vanilla spread around the strike
+    let spread_width: f64 = width.unwrap_or(strike * 0.001);
+    match kind {
+        DigitalType::Cash => {
+            let replicated = call_spread_replication(spot, strike, payout, spread_width, time, rate, volatility, call_opt);
+            println!("Option Price using Call Spread Replication:   {} (width {})", (replicated * 100.0).round() / 100.0, spread_width);
+        }
+        DigitalType::Asset => {
+            // An asset-or-nothing call is a vanilla call plus a cash digital paying the strike (a put is the digital less the vanilla put)
+            let vanilla = generalized_black_scholes(spot, strike, time, rate, rate, volatility, call_opt);
+            let digital = call_spread_replication(spot, strike, strike, spread_width, time, rate, volatility, call_opt);
+            let replicated = if call_opt { vanilla + digital } else { digital - vanilla };
+            println!("Option Price using Call Spread Replication:   {} (width {})", (replicated * 100.0).round() / 100.0, spread_width);
+        }
+        _ => {}
+    }
+
+    // Output Greeks if specified in flag
+    if greeks {
+
+        let greeks = numerical_greeks(spot, time, rate, volatility, price);
+
+        // Output
+        println!("\n--------------------------------\n");
+
+        println!("Delta:              {}", (greeks.delta * 10000.0).round() / 10000.0);
+        println!("Gamma:              {}", (greeks.gamma * 10000.0).round() / 10000.0);
+        println!("Vega:               {}", (greeks.vega * 10000.0).round() / 10000.0);
+        println!("Theta:              {}", (greeks.theta * 10000.0).round() / 10000.0);
+        println!("Rho:                {}", (greeks.rho * 10000.0).round() / 10000.0);
+
+    }
+}
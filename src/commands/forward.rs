@@ -0,0 +1,63 @@
+/*
+
+JDA Options Pricing
+/src/commands/forward.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::forward_start::{cliquet, monte_carlo_cliquet};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, moneyness: f64, start_time: f64, time: f64, resets: u32, rates: RateArgs, volatility: f64, paths: u32, seed: Option<u64>, call: bool, put: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    if start_time < 0.0 || start_time >= time || resets == 0 {
+        eprintln!("ERROR: Incorrect arguments, the option must start before it expires and have at least one period.");
+        return;
+    }
+
+
+    // Output
+    println!("\u{1F4B0} Forward Start Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {} {}", if resets > 1 { "Cliquet" } else { "Forward Start" }, if call_opt { "Call" } else { "Put" });
+    println!("Spot Price:         {}", spot);
+    println!("Strike Moneyness:   {}", moneyness);
+    println!("Years to Start:     {}", start_time);
+    println!("Years to Maturity:  {}", time);
+    println!("Reset Periods:      {}", resets);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+    println!("Paths:              {}", paths);
+
+    println!("\n--------------------------------\n");
+
+    println!("Option Price using Rubinstein Model:          {}", (cliquet(spot, moneyness, start_time, time, resets, rate, volatility, call_opt) * 100.0).round() / 100.0);
+
+    let simulated = monte_carlo_cliquet(spot, moneyness, start_time, time, resets, rate, volatility, paths, seed, call_opt);
+    println!("Option Price using Monte Carlo:               {} (std. error {})", (simulated.price * 100.0).round() / 100.0, (simulated.std_error * 10000.0).round() / 10000.0);
+}
@@ -0,0 +1,139 @@
+/*
+
+JDA Options Pricing
+/src/commands/garch.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::garch::{Garch, GarchModel};
+use crate::historical::{TRADING_DAYS, log_returns, read_bars};
+use crate::provider::{Bar, MarketDataProvider, Provider, ProviderArgs};
+
+
+// A labelled line of the parameter table
+type Row = (&'static str, fn(&Garch) -> String);
+
+
+// Entry point for the command
+pub async fn run(symbol: Option<String>, file: Option<String>, model: Option<GarchModel>, history: usize, horizons: Vec<usize>, provider: ProviderArgs) {
+
+    // Default horizons of a week, a month, a quarter, six months and a year in trading days
+    let horizons: Vec<usize> = if horizons.is_empty() { vec![5, 21, 63, 126, 252] } else { horizons };
+    let models: Vec<GarchModel> = match model {
+        Some(model) => vec![model],
+        None => vec![GarchModel::Garch, GarchModel::Gjr, GarchModel::Egarch],
+    };
+
+    // Check arguments
+    if horizons.contains(&0) {
+        eprintln!("ERROR: Incorrect arguments, every horizon must be at least one day.");
+        return;
+    }
+
+
+    // Read the bars from the file or the data provider
+    let (source, bars): (String, Vec<Bar>) = match (&symbol, &file) {
+        (_, Some(file)) => match read_bars(file) {
+            Ok(bars) => (file.clone(), bars),
+            Err(e) => {
+                eprintln!("Failed to read the bars: {}", e);
+                return;
+            }
+        },
+        (Some(symbol), None) => {
+            let bars = async {
+                let provider: Provider = Provider::load(&provider)?;
+                provider.candles(symbol, history + 1).await
+            };
+            match bars.await {
+                Ok(bars) => (symbol.clone(), bars),
+                Err(e) => {
+                    eprintln!("Failed to fetch the bars: {}", e);
+                    return;
+                }
+            }
+        }
+        (None, None) => {
+            eprintln!("ERROR: Incorrect arguments, give a symbol or a file of bars.");
+            return;
+        }
+    };
+
+    let start: usize = bars.len().saturating_sub(history + 1);
+    let returns: Vec<f64> = log_returns(&bars[start..]);
+
+
+    // Fit every model
+    let mut fits: Vec<Garch> = Vec::new();
+    for model in models {
+        match Garch::fit(&returns, model) {
+            Ok(garch) => fits.push(garch),
+            Err(e) => eprintln!("Failed to fit {}: {}", model, e),
+        }
+    }
+
+    if fits.is_empty() {
+        return;
+    }
+
+
+    // Output
+    println!("\u{1F4B0} GARCH Volatility Tool");
+    println!("--------------------------------\n");
+
+    println!("Source:             {}", source);
+    println!("Returns:            {}", returns.len());
+    if let (Some(first), Some(last)) = (bars[start..].first(), bars.last()) {
+        println!("From:               {} to {}", first.date, last.date);
+    }
+
+    println!("\n--------------------------------\n");
+
+    print!("{:<16}", "");
+    for garch in fits.iter() {
+        print!(" {:>15}", garch.model.to_string());
+    }
+    println!();
+
+    // Parameters, with omega in daily variance (log variance for EGARCH)
+    let rows: [Row; 10] = [
+        ("Omega", |g| format!("{:.3e}", g.omega)),
+        ("Alpha", |g| format!("{:.4}", g.alpha)),
+        ("Gamma", |g| if g.model == GarchModel::Garch { "-".to_string() } else { format!("{:.4}", g.gamma) }),
+        ("Beta", |g| format!("{:.4}", g.beta)),
+        ("Persistence", |g| format!("{:.4}", g.persistence())),
+        ("Half-Life", |g| format!("{:.1}d", g.half_life())),
+        ("Next-Day Vol", |g| format!("{:.2}%", (g.next_variance * TRADING_DAYS).sqrt() * 100.0)),
+        ("Long-Run Vol", |g| format!("{:.2}%", (g.long_run_variance() * TRADING_DAYS).sqrt() * 100.0)),
+        ("Log-Likelihood", |g| format!("{:.2}", g.log_likelihood)),
+        ("AIC", |g| format!("{:.2}", 2.0 * (if g.model == GarchModel::Garch { 3.0 } else { 4.0 }) - 2.0 * g.log_likelihood)),
+    ];
+    for (name, value) in rows {
+        print!("{:<16}", name);
+        for garch in fits.iter() {
+            print!(" {:>15}", value(garch));
+        }
+        println!();
+    }
+
+    println!("\n--------------------------------\n");
+
+    // Average volatility from today to each horizon
+    print!("{:<16}", "Horizon");
+    for garch in fits.iter() {
+        print!(" {:>15}", garch.model.to_string());
+    }
+    println!();
+
+    for days in horizons {
+        print!("{:<16}", format!("{}d", days));
+        for garch in fits.iter() {
+            print!(" {:>14.2}%", garch.term_volatility(days as f64) * 100.0);
+        }
+        println!();
+    }
+
+    println!("\nForecasts are the annualised average variance to each horizon in trading days");
+}
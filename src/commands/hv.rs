@@ -0,0 +1,92 @@
+/*
+
+JDA Options Pricing
+/src/commands/hv.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::historical::{Estimator, TRADING_DAYS, read_bars, realized_volatility};
+use crate::provider::{Bar, MarketDataProvider, Provider, ProviderArgs};
+
+
+// Entry point for the command
+pub async fn run(symbol: Option<String>, file: Option<String>, windows: Vec<usize>, estimator: Option<Estimator>, provider: ProviderArgs) {
+
+    // Default windows of two weeks, a month, six weeks and three months
+    let windows: Vec<usize> = if windows.is_empty() { vec![10, 20, 30, 60] } else { windows };
+    let estimators: Vec<Estimator> = match estimator {
+        Some(estimator) => vec![estimator],
+        None => vec![Estimator::CloseToClose, Estimator::Parkinson, Estimator::GarmanKlass, Estimator::RogersSatchell, Estimator::YangZhang],
+    };
+
+    // Check arguments
+    if windows.iter().any(|w| *w < 2) {
+        eprintln!("ERROR: Incorrect arguments, every window must be at least two days.");
+        return;
+    }
+
+
+    // Read the bars from the file or the data provider, with one extra for the estimators that need the previous close
+    let count: usize = windows.iter().max().copied().unwrap_or(0) + 1;
+    let (source, bars): (String, Vec<Bar>) = match (&symbol, &file) {
+        (_, Some(file)) => match read_bars(file) {
+            Ok(bars) => (file.clone(), bars),
+            Err(e) => {
+                eprintln!("Failed to read the bars: {}", e);
+                return;
+            }
+        },
+        (Some(symbol), None) => {
+            let bars = async {
+                let provider: Provider = Provider::load(&provider)?;
+                provider.candles(symbol, count).await
+            };
+            match bars.await {
+                Ok(bars) => (symbol.clone(), bars),
+                Err(e) => {
+                    eprintln!("Failed to fetch the bars: {}", e);
+                    return;
+                }
+            }
+        }
+        (None, None) => {
+            eprintln!("ERROR: Incorrect arguments, give a symbol or a file of bars.");
+            return;
+        }
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Historical Volatility Tool");
+    println!("--------------------------------\n");
+
+    println!("Source:             {}", source);
+    println!("Bars:               {}", bars.len());
+    if let (Some(first), Some(last)) = (bars.first(), bars.last()) {
+        println!("From:               {} to {}", first.date, last.date);
+    }
+    println!("Last Close:         {}", bars.last().map(|bar| bar.close).unwrap_or(0.0));
+
+    println!("\n--------------------------------\n");
+
+    print!("{:<16}", "Estimator");
+    for window in windows.iter() {
+        print!(" {:>9}", format!("{}d", window));
+    }
+    println!();
+
+    for estimator in estimators {
+        print!("{:<16}", estimator.to_string());
+        for window in windows.iter() {
+            match realized_volatility(&bars, *window, estimator) {
+                Ok(volatility) => print!(" {:>8.2}%", volatility * 100.0),
+                Err(_) => print!(" {:>9}", "-"),
+            }
+        }
+        println!();
+    }
+
+    println!("\nVolatilities are annualised over {} trading days, - marks windows longer than the bars available", TRADING_DAYS);
+}
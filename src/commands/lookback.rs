@@ -0,0 +1,87 @@
+/*
+
+JDA Options Pricing
+/src/commands/lookback.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::lookback::{fixed_lookback, floating_lookback};
+use crate::greeks::numerical_greeks;
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, strike: Option<f64>, time: f64, rates: RateArgs, volatility: f64, extreme: Option<f64>, call: bool, put: bool, greeks: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    // A new option has only seen today's price
+    let running_extreme: f64 = extreme.unwrap_or(spot);
+
+    // The running extreme has to be consistent with the spot
+        // Floating calls and fixed puts track the minimum, floating puts and fixed calls the maximum
+    let tracks_minimum: bool = call_opt == strike.is_none();
+    if (tracks_minimum && running_extreme > spot) || (!tracks_minimum && running_extreme < spot) {
+        eprintln!("ERROR: Incorrect arguments, the running {} cannot be {} the spot price.", if tracks_minimum { "minimum" } else { "maximum" }, if tracks_minimum { "above" } else { "below" });
+        return;
+    }
+
+    // Pricer in terms of the inputs the greeks are bumped on
+    let price = |s: f64, t: f64, r: f64, v: f64| match strike {
+        Some(k) => fixed_lookback(s, k, running_extreme, t, r, v, call_opt),
+        None => floating_lookback(s, running_extreme, t, r, v, call_opt),
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Lookback Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {} ({} Strike)", if call_opt { "Call" } else { "Put" }, if strike.is_some() { "Fixed" } else { "Floating" });
+    println!("Spot Price:         {}", spot);
+    if let Some(k) = strike { println!("Strike Price:       {}", k); }
+    println!("Running {}:    {}", if tracks_minimum { "Minimum" } else { "Maximum" }, running_extreme);
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+
+    println!("\n--------------------------------\n");
+
+    println!("Option Price using {} Model:   {}", if strike.is_some() { "Conze-Viswanathan" } else { "Goldman-Sosin-Gatto" }, (price(spot, time, rate, volatility) * 100.0).round() / 100.0);
+
+    // Output Greeks if specified in flag
+    if greeks {
+
+        let greeks = numerical_greeks(spot, time, rate, volatility, price);
+
+        // Output
+        println!("\n--------------------------------\n");
+
+        println!("Delta:              {}", (greeks.delta * 10000.0).round() / 10000.0);
+        println!("Gamma:              {}", (greeks.gamma * 10000.0).round() / 10000.0);
+        println!("Vega:               {}", (greeks.vega * 10000.0).round() / 10000.0);
+        println!("Theta:              {}", (greeks.theta * 10000.0).round() / 10000.0);
+        println!("Rho:                {}", (greeks.rho * 10000.0).round() / 10000.0);
+
+    }
+}
@@ -7,13 +7,24 @@ JohnDavid Abe
 */
 
 
-use crate::binomial::binomial;
+use crate::binomial::{Exercise, binomial};
 use crate::black_scholes::black_scholes;
 use crate::greeks::{calculate_greeks};
+use crate::historical::{VolatilityInput, read_bars};
+use crate::yield_curve::RateArgs;
 
 
 // Entry point for the command
-pub fn run(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, steps: u32, call: bool, put: bool, american: bool, european: bool, greeks: bool) {
+pub fn run(spot: f64, strike: f64, time: f64, rates: RateArgs, volatility: VolatilityInput, bars: Option<String>, steps: u32, call: bool, put: bool, american: bool, european: bool, bermudan: Vec<f64>, lockout: Option<f64>, greeks: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
 
     // Ensure option is either only call OR put
     let mut call_opt: bool = call;
@@ -25,33 +36,71 @@ pub fn run(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, steps:
         return;
     }
 
-    // Ensure option is either only American OR European
-    let mut american_opt: bool = american;
+    // Ensure option has only one exercise style (American, European, Bermudan or American with a lockout)
+    let styles: usize = [american, european, !bermudan.is_empty(), lockout.is_some()].iter().filter(|s| **s).count();
 
-    // If neither flag has been specified, default to call
-    if !american && !european { american_opt = true; } else if american && european { 
-        // Check for double call/put flags in arguments
+    if styles > 1 {
+        // Check for conflicting exercise flags in arguments
         eprintln!("ERROR: Ambigious arguments, only specify a single option region.");
         return;
     }
 
+    // If no exercise style has been specified, default to American
+    let exercise: Exercise = if european {
+        Exercise::European
+    } else if !bermudan.is_empty() {
+        Exercise::Bermudan(bermudan)
+    } else if let Some(lockout) = lockout {
+        Exercise::Lockout(lockout)
+    } else {
+        Exercise::American
+    };
+
+    // Compute a historical or forecast volatility from the file of bars
+    let (volatility, source): (f64, Option<String>) = match volatility {
+        VolatilityInput::Fixed(volatility) => (volatility, None),
+        input => {
+            let Some(path) = bars else {
+                eprintln!("ERROR: Incorrect arguments, a historical or forecast volatility needs a file of bars (--bars).");
+                return;
+            };
+            match read_bars(&path).and_then(|bars| input.estimate(&bars)) {
+                Ok(estimate) => (estimate.volatility(time), Some(format!("{} of {}", input, path))),
+                Err(e) => {
+                    eprintln!("Failed to compute the volatility: {}", e);
+                    return;
+                }
+            }
+        }
+    };
+
 
     // Output
     println!("\u{1F4B0} Manual Options Pricing Tool");
     println!("--------------------------------\n");
 
     println!("Option Type:        {}", if call_opt { "Call" } else { "Put" } );
+    match &exercise {
+        Exercise::European => println!("Exercise:           European"),
+        Exercise::American => println!("Exercise:           American"),
+        Exercise::Bermudan(dates) => println!("Exercise:           Bermudan {:?}", dates),
+        Exercise::Lockout(lockout) => println!("Exercise:           American after {} years", lockout),
+    }
     println!("Spot Price:         {}", spot);
     println!("Strike Price:       {}", strike);
     println!("Years to Maturity:  {}", time);
     println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
     println!("Volatility:         {}", volatility);
+    if let Some(source) = source {
+        println!("Volatility Source:  {}", source);
+    }
     println!("Steps:              {}", steps);
 
     println!("\n--------------------------------\n");
 
-    println!("Option Price using Binomial Model:            {}", binomial(spot, strike, time, rate, volatility, steps, call_opt, american_opt));
-    if !american_opt { println!("Option Price using Black-Scholes Model:       {}", black_scholes(spot, strike, time, rate, volatility, call_opt)); }
+    println!("Option Price using Binomial Model:            {}", binomial(spot, strike, time, rate, volatility, steps, call_opt, &exercise));
+    if exercise == Exercise::European { println!("Option Price using Black-Scholes Model:       {}", black_scholes(spot, strike, time, rate, volatility, call_opt)); }
 
     // Output Greeks if specified in flag
     if greeks {
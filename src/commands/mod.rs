@@ -10,4 +10,27 @@ JohnDavid Abe
 // Commands submodules
 pub mod auto;
 pub mod manual;
+pub mod barrier;
+pub mod asian;
+pub mod lookback;
+pub mod digital;
+pub mod compound;
+pub mod chooser;
+pub mod forward;
+pub mod two_asset;
+pub mod basket;
+pub mod quanto;
+pub mod price;
+pub mod bond;
+pub mod cap;
+pub mod swaption;
+pub mod curve;
+pub mod chain;
 
+pub mod surface;
+pub mod arbitrage;
+pub mod carry;
+pub mod hv;
+pub mod garch;
+pub mod density;
+pub mod vix;
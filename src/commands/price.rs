@@ -0,0 +1,114 @@
+/*
+
+JDA Options Pricing
+/src/commands/price.rs
+JohnDavid Abe
+
+*/
+
+
+use std::fs;
+
+use crate::asian::Averaging;
+use crate::binomial::Exercise;
+use crate::payoff::{Payoff, lattice_price, monte_carlo_price};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(payoff_file: String, spot: f64, time: f64, rates: RateArgs, volatility: f64, defines: Vec<String>, steps: u32, fixings: Option<u32>, paths: u32, seed: Option<u64>) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Values set on the command line, as NAME=VALUE
+    let mut values: Vec<(String, f64)> = Vec::new();
+    for define in &defines {
+        match define.split_once('=').map(|(name, value)| (name.trim(), value.trim().parse::<f64>())) {
+            Some((name, Ok(value))) if !name.is_empty() => values.push((name.to_string(), value)),
+            _ => {
+                eprintln!("ERROR: Incorrect arguments, expected NAME=VALUE but got '{}'.", define);
+                return;
+            }
+        }
+    }
+
+    let source: String = match fs::read_to_string(&payoff_file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("ERROR: Unable to read payoff file {}, {}", payoff_file, e);
+            return;
+        }
+    };
+
+    let payoff: Payoff = match Payoff::parse(&source, &values) {
+        Ok(payoff) => payoff,
+        Err(e) => {
+            eprintln!("ERROR: Invalid payoff in {}, {}", payoff_file, e);
+            return;
+        }
+    };
+
+    // Path dependent payoffs are observed daily by default, the last fixing being at expiry
+    let remaining: u32 = fixings.unwrap_or(((time * 252.0).round() as u32).max(1));
+    if remaining == 0 {
+        eprintln!("ERROR: Incorrect arguments, at least one fixing must remain before expiry.");
+        return;
+    }
+    let averaging = Averaging::equally_spaced(time / (remaining as f64), time, remaining, Vec::new());
+
+
+    // Output
+    println!("\u{1F4B0} Payoff Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Payoff:             {}", payoff.text);
+    match &payoff.exercise {
+        Exercise::European => println!("Exercise:           European"),
+        Exercise::American => println!("Exercise:           American"),
+        Exercise::Bermudan(dates) => println!("Exercise:           Bermudan {:?}", dates),
+        Exercise::Lockout(lockout) => println!("Exercise:           American after {} years", lockout),
+    }
+    for (name, value) in &values {
+        println!("{:<20}{}", format!("{}:", name), value);
+    }
+    println!("Spot Price:         {}", spot);
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Volatility:         {}", volatility);
+    if payoff.path_dependent() {
+        println!("Fixings:            {}", remaining);
+    } else {
+        println!("Steps:              {}", steps);
+    }
+    println!("Paths:              {}", paths);
+
+    println!("\n--------------------------------\n");
+
+    // Payoffs on the current price go through the lattice, which also handles early exercise
+    if !payoff.path_dependent() {
+        match lattice_price(&payoff, spot, time, rate, volatility, steps) {
+            Ok(price) => println!("Option Price using Binomial Model:            {}", (price * 100.0).round() / 100.0),
+            Err(e) => {
+                eprintln!("ERROR: Incorrect arguments, {}", e);
+                return;
+            }
+        }
+    }
+
+    // Monte Carlo cannot exercise early, but checks every European payoff
+    if payoff.exercise == Exercise::European {
+        let times: Vec<f64> = if payoff.path_dependent() { averaging.times } else { vec![time] };
+        match monte_carlo_price(&payoff, spot, time, rate, volatility, &times, paths, seed) {
+            Ok(estimate) => println!("Option Price using Monte Carlo:               {} (std. error {})", (estimate.price * 100.0).round() / 100.0, (estimate.std_error * 10000.0).round() / 10000.0),
+            Err(e) => eprintln!("ERROR: Incorrect arguments, {}", e),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+/*
+
+JDA Options Pricing
+/src/commands/quanto.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::quanto::{FxMarket, composite_option, quanto_option};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(spot: f64, strike: f64, time: f64, rates: RateArgs, volatility: f64, fx: FxMarket, fixed_rate: Option<f64>, composite: bool, call: bool, put: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    if fx.correlation.abs() > 1.0 {
+        eprintln!("ERROR: Incorrect arguments, the correlation must be between -1 and 1.");
+        return;
+    }
+
+    // Quantos convert at today's exchange rate unless a fixed rate is agreed
+    let conversion: f64 = fixed_rate.unwrap_or(fx.rate);
+
+
+    // Output
+    println!("\u{1F4B0} Quanto Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {} {}", if composite { "Composite" } else { "Quanto" }, if call_opt { "Call" } else { "Put" });
+    println!("Spot Price:         {} (foreign)", spot);
+    println!("Strike Price:       {} ({})", strike, if composite { "domestic" } else { "foreign" });
+    println!("Years to Maturity:  {}", time);
+    println!("Domestic Rate:      {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("Foreign Rate:       {}", fx.foreign_rate);
+    println!("Volatility:         {}", volatility);
+    println!("Exchange Rate:      {}", fx.rate);
+    if !composite { println!("Fixed Rate:         {}", conversion); }
+    println!("FX Volatility:      {}", fx.volatility);
+    println!("Correlation:        {}", fx.correlation);
+
+    println!("\n--------------------------------\n");
+
+    if composite {
+        println!("Option Price (domestic):                      {}", (composite_option(spot, strike, time, rate, volatility, &fx, call_opt) * 100.0).round() / 100.0);
+    } else {
+        println!("Option Price (domestic):                      {}", (quanto_option(spot, strike, time, rate, volatility, &fx, conversion, call_opt) * 100.0).round() / 100.0);
+    }
+}
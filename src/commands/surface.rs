@@ -0,0 +1,242 @@
+/*
+
+JDA Options Pricing
+/src/commands/surface.rs
+JohnDavid Abe
+
+*/
+
+
+use std::fs;
+use chrono::NaiveDate;
+use serde_json::json;
+use crate::calendar::new_york_time;
+use crate::day_count::DayCount;
+use crate::fetch::{fetch_treasury_curve, out_of_the_money_quotes, settlement_symbol, time_to_expiry};
+use crate::provider::{MarketDataProvider, Provider, ProviderArgs};
+use crate::vol_surface::{SliceQuotes, SurfaceModel, Violation, VolSurface};
+use crate::yield_curve::Interpolation;
+
+
+// Entry point for the command
+pub async fn run(symbol: String, model: SurfaceModel, max_dte: Option<u32>, range: f64, points: usize, csv: Option<String>, json_file: Option<String>, heatmap: bool, svg: Option<String>, day_count: DayCount, provider: ProviderArgs) {
+
+    // Check arguments
+    if range <= 0.0 || points < 2 {
+        eprintln!("ERROR: Incorrect arguments, the moneyness range must be positive with at least two points.");
+        return;
+    }
+
+
+    // Connect to the market data source
+    let provider: Provider = match Provider::load(&provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Failed to load the market data provider: {}", e);
+            return;
+        }
+    };
+
+
+    // Pull out-of-the-money quotes for every expiration
+    let (spot, slices) = match fetch_slices(&provider, &symbol, max_dte, day_count).await {
+        Ok(market) => market,
+        Err(e) => {
+            eprintln!("Failed to fetch the option chains: {}", e);
+            return;
+        }
+    };
+
+    let surface: VolSurface = match VolSurface::fit(slices, model) {
+        Ok(surface) => surface,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}.", e);
+            return;
+        }
+    };
+
+
+    // Grid of log-moneyness for the exports and the arbitrage scan (which uses a finer grid)
+    let grid: Vec<f64> = (0..points).map(|i| -1.0 * range + 2.0 * range * (i as f64) / ((points - 1) as f64)).collect();
+    let scan: Vec<f64> = (0..=400).map(|i| -1.0 * range + 2.0 * range * (i as f64) / 400.0).collect();
+    let violations: Vec<Violation> = surface.arbitrage(&scan);
+
+
+    // Output
+    println!("\u{1F4B0} Volatility Surface Tool");
+    println!("--------------------------------\n");
+
+    println!("Symbol:             {}", symbol);
+    println!("Spot Price:         {}", spot);
+    println!("Model:              {}", if surface.model == SurfaceModel::Svi { "SVI per expiration" } else { "Surface SVI (power law)" });
+    if let Some((rho, eta, gamma)) = surface.ssvi {
+        println!("SSVI Parameters:    rho {:.4}, eta {:.4}, gamma {:.4}", rho, eta, gamma);
+    }
+    if !surface.skipped.is_empty() {
+        println!("Skipped:            {} (fewer than five quotes)", surface.skipped.iter().map(|d| d.to_string()).collect::<Vec<String>>().join(", "));
+    }
+
+    println!("\n--------------------------------\n");
+
+    println!("{:>12} {:>9} {:>10} {:>8} {:>7} {:>8} {:>9} {:>9} {:>8} {:>8} {:>8}", "Expiration", "Years", "Forward", "ATM IV", "Quotes", "RMSE", "a", "b", "rho", "m", "sigma");
+    for slice in surface.slices.iter() {
+        println!("{:>12} {:>9.4} {:>10.2} {:>7.2}% {:>7} {:>7.3}% {:>9.5} {:>9.5} {:>8.4} {:>8.4} {:>8.4}",
+            slice.expiration.to_string(), slice.time, slice.forward, slice.volatility(0.0) * 100.0, slice.quotes.len(), slice.fit_error * 100.0,
+            slice.svi.a, slice.svi.b, slice.svi.rho, slice.svi.m, slice.svi.sigma);
+    }
+
+    println!("\n--------------------------------\n");
+
+    if violations.is_empty() {
+        println!("No butterfly or calendar arbitrage for log-moneyness within \u{00B1}{}", range);
+    }
+    for violation in violations.iter() {
+        match violation {
+            Violation::Butterfly { expiration, from, to, worst } =>
+                println!("Butterfly arbitrage on {} for k in [{:.3}, {:.3}], g(k) down to {:.5}", expiration, from, to, worst),
+            Violation::Calendar { first, second, from, to, worst } =>
+                println!("Calendar arbitrage from {} to {} for k in [{:.3}, {:.3}], total variance falls by up to {:.6}", first, second, from, to, -1.0 * worst),
+        }
+    }
+
+    if heatmap {
+        println!("\n--------------------------------\n");
+        print_heatmap(&surface, &grid);
+    }
+
+
+    // Exports
+    if let Some(path) = csv {
+        let mut text: String = "expiration,years,log_moneyness,strike,volatility,total_variance\n".to_string();
+        for slice in surface.slices.iter() {
+            for k in grid.iter() {
+                text += &format!("{},{},{},{},{},{}\n", slice.expiration, slice.time, k, slice.forward * k.exp(), slice.volatility(*k), slice.svi.total_variance(*k));
+            }
+        }
+        save(&path, &text);
+    }
+
+    if let Some(path) = json_file {
+        let document = json!({
+            "symbol": symbol,
+            "spot": spot,
+            "model": if surface.model == SurfaceModel::Svi { "svi" } else { "ssvi" },
+            "ssvi": surface.ssvi.map(|(rho, eta, gamma)| json!({ "rho": rho, "eta": eta, "gamma": gamma })),
+            "slices": surface.slices.iter().map(|slice| json!({
+                "expiration": slice.expiration.to_string(),
+                "years": slice.time,
+                "forward": slice.forward,
+                "fit_error": slice.fit_error,
+                "svi": { "a": slice.svi.a, "b": slice.svi.b, "rho": slice.svi.rho, "m": slice.svi.m, "sigma": slice.svi.sigma },
+                "grid": grid.iter().map(|k| json!({ "log_moneyness": k, "strike": slice.forward * k.exp(), "volatility": slice.volatility(*k) })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "violations": violations.iter().map(|violation| match violation {
+                Violation::Butterfly { expiration, from, to, worst } => json!({ "kind": "butterfly", "expiration": expiration.to_string(), "from": from, "to": to, "worst": worst }),
+                Violation::Calendar { first, second, from, to, worst } => json!({ "kind": "calendar", "first": first.to_string(), "second": second.to_string(), "from": from, "to": to, "worst": worst }),
+            }).collect::<Vec<_>>(),
+        });
+        match serde_json::to_string_pretty(&document) {
+            Ok(text) => save(&path, &text),
+            Err(e) => eprintln!("Failed to write {}: {}", path, e),
+        }
+    }
+
+    if let Some(path) = svg {
+        save(&path, &svg_heatmap(&surface, &grid, &symbol));
+    }
+}
+
+
+// Spot and the (log-moneyness, vol) quotes of every expiration, using puts below the forward and calls above it
+async fn fetch_slices(provider: &Provider, symbol: &str, max_dte: Option<u32>, day_count: DayCount) -> Result<(f64, Vec<SliceQuotes>), Box<dyn std::error::Error>> {
+
+    let spot: f64 = provider.quote(symbol).await?.last;
+    let curve = fetch_treasury_curve(provider, Interpolation::MonotoneCubic).await?;
+    let today: NaiveDate = new_york_time(provider.now()).date();
+
+    let mut slices: Vec<SliceQuotes> = Vec::new();
+    for expiration in provider.expirations(symbol, true).await? {
+
+        if max_dte.is_some_and(|dte| (expiration - today).num_days() > dte as i64) {
+            continue;
+        }
+
+        let calls = provider.chain(symbol, expiration, true).await?;
+        let puts = provider.chain(symbol, expiration, false).await?;
+
+        // Expired or expiring contracts have no time left to fit
+        let Ok((time, calendar_years)) = time_to_expiry(provider, settlement_symbol(&calls, symbol), expiration, day_count) else { continue; };
+        let (forward, quotes) = out_of_the_money_quotes(&calls, &puts, spot, time, calendar_years, &curve);
+
+        slices.push(SliceQuotes { expiration, time, forward, quotes });
+    }
+
+    return Ok((spot, slices));
+}
+
+
+// Shade each (expiration, log-moneyness) cell by its vol, light to dark from the lowest to the highest
+fn print_heatmap(surface: &VolSurface, grid: &[f64]) {
+
+    let shades: Vec<char> = " .:-=+*#%@".chars().collect();
+    let vols: Vec<Vec<f64>> = surface.slices.iter().map(|slice| grid.iter().map(|k| slice.volatility(*k)).collect()).collect();
+    let (low, high) = bounds(&vols);
+
+    println!("{:>12}  k = {:.2} .. {:.2}", "Expiration", grid[0], grid[grid.len() - 1]);
+    for (slice, row) in surface.slices.iter().zip(vols.iter()) {
+        let cells: String = row.iter().map(|vol| {
+            let level: usize = (((vol - low) / (high - low).max(1e-12)) * ((shades.len() - 1) as f64)).round() as usize;
+            shades[level.min(shades.len() - 1)]
+        }).collect();
+        println!("{:>12} |{}|", slice.expiration.to_string(), cells);
+    }
+    println!("\nScale: '{}' = {:.2}% up to '{}' = {:.2}%", shades[0], low * 100.0, shades[shades.len() - 1], high * 100.0);
+}
+
+
+// The same heatmap as an SVG image, blue for low vols through to red for high ones
+fn svg_heatmap(surface: &VolSurface, grid: &[f64], symbol: &str) -> String {
+
+    let (cell_width, cell_height, left, top) = (24.0, 22.0, 100.0, 40.0);
+    let vols: Vec<Vec<f64>> = surface.slices.iter().map(|slice| grid.iter().map(|k| slice.volatility(*k)).collect()).collect();
+    let (low, high) = bounds(&vols);
+
+    let width: f64 = left + cell_width * (grid.len() as f64) + 20.0;
+    let height: f64 = top + cell_height * (vols.len() as f64) + 50.0;
+
+    let mut svg: String = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"11\">\n", width, height);
+    svg += &format!("<text x=\"{}\" y=\"20\" font-size=\"14\">{} implied volatility ({:.1}% to {:.1}%)</text>\n", left, symbol, low * 100.0, high * 100.0);
+
+    for (i, (slice, row)) in surface.slices.iter().zip(vols.iter()).enumerate() {
+        let y: f64 = top + cell_height * (i as f64);
+        svg += &format!("<text x=\"4\" y=\"{}\">{}</text>\n", y + 15.0, slice.expiration);
+        for (j, vol) in row.iter().enumerate() {
+            let level: f64 = (vol - low) / (high - low).max(1e-12);
+            let (red, blue) = ((255.0 * level).round(), (255.0 * (1.0 - level)).round());
+            svg += &format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"rgb({},80,{})\"><title>k {:.3}: {:.2}%</title></rect>\n",
+                left + cell_width * (j as f64), y, cell_width, cell_height, red, blue, grid[j], vol * 100.0);
+        }
+    }
+
+    let bottom: f64 = top + cell_height * (vols.len() as f64) + 16.0;
+    svg += &format!("<text x=\"{}\" y=\"{}\">k = {:.2}</text>\n", left, bottom, grid[0]);
+    svg += &format!("<text x=\"{}\" y=\"{}\" text-anchor=\"end\">k = {:.2}</text>\n", left + cell_width * (grid.len() as f64), bottom, grid[grid.len() - 1]);
+    svg += "</svg>\n";
+
+    return svg;
+}
+
+
+fn bounds(vols: &[Vec<f64>]) -> (f64, f64) {
+    let low: f64 = vols.iter().flatten().copied().fold(f64::INFINITY, f64::min);
+    let high: f64 = vols.iter().flatten().copied().fold(f64::NEG_INFINITY, f64::max);
+    return (low, high);
+}
+
+
+fn save(path: &str, text: &str) {
+    match fs::write(path, text) {
+        Ok(_) => println!("\nWrote {}", path),
+        Err(e) => eprintln!("Failed to write {}: {}", path, e),
+    }
+}
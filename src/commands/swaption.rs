@@ -0,0 +1,72 @@
+/*
+
+JDA Options Pricing
+/src/commands/swaption.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::yield_curve::{Interpolation, YieldCurve};
+use crate::rate_options::{RateModel, Volatility, swaption};
+
+
+// Entry point for the command
+pub fn run(rate: Option<f64>, curve_file: Option<String>, interpolation: Interpolation, strike: f64, expiry: f64, tenor: f64, frequency: u32, notional: f64, model: RateModel, volatility: Option<f64>, cube: Option<String>, receiver: bool) {
+
+    let volatility: Volatility = match Volatility::load(volatility, cube) {
+        Ok(volatility) => volatility,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    if frequency == 0 {
+        eprintln!("ERROR: Incorrect arguments, the frequency must be at least one payment a year.");
+        return;
+    }
+
+    // Discount curve, flat at the given rate or bootstrapped from a file
+    let curve: YieldCurve = match YieldCurve::load(rate, curve_file.clone(), interpolation) {
+        Ok(curve) => curve,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    let value = match swaption(|t| curve.discount(t), strike, expiry, tenor, frequency, notional, &volatility, model, !receiver) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+
+    // Output
+    println!("\u{1F4B0} Swaption Pricing Tool");
+    println!("--------------------------------\n");
+
+    println!("Option Type:        {}", if receiver { "Receiver" } else { "Payer" });
+    println!("Model:              {}", if model == RateModel::Black { "Black-76" } else { "Bachelier" });
+    println!("Strike Rate:        {}", strike);
+    println!("Years to Expiry:    {}", expiry);
+    println!("Swap Tenor:         {}", tenor);
+    println!("Payments per Year:  {}", frequency);
+    println!("Notional:           {}", notional);
+    match (&curve_file, rate) {
+        (Some(path), _) => println!("Yield Curve:        {}", path),
+        (None, Some(rate)) => println!("Risk-Free Rate:     {}", rate),
+        _ => {}
+    }
+    println!("Volatility:         {}", value.volatility);
+
+    println!("\n--------------------------------\n");
+
+    println!("Forward Swap Rate:                            {}%", (value.swap_rate * 1000000.0).round() / 10000.0);
+    println!("Annuity:                                      {}", (value.annuity * 10000.0).round() / 10000.0);
+    println!("Swaption Price:                               {}", (value.price * 10000.0).round() / 10000.0);
+    println!("Vega:                                         {}", (value.vega * 10000.0).round() / 10000.0);
+}
@@ -0,0 +1,85 @@
+/*
+
+JDA Options Pricing
+/src/commands/two_asset.rs
+JohnDavid Abe
+
+*/
+
+
+use crate::two_asset::{AssetPair, Rainbow, bjerksund_stensland_spread, exchange_option, kirk_spread, monte_carlo_two_asset, rainbow_option};
+use crate::yield_curve::RateArgs;
+
+
+// Entry point for the command
+pub fn run(pair: AssetPair, strike: f64, time: f64, rates: RateArgs, futures: bool, rainbow: Option<Rainbow>, paths: u32, seed: Option<u64>, call: bool, put: bool) {
+
+    // Risk free rate, flat or the curve's zero rate to expiry
+    let rate: f64 = match rates.zero_rate(time) {
+        Ok(rate) => rate,
+        Err(e) => {
+            eprintln!("ERROR: Incorrect arguments, {}", e);
+            return;
+        }
+    };
+
+    // Both underlyings grow at the rate unless they are futures
+    let carry: f64 = if futures { 0.0 } else { rate };
+    let pair = AssetPair { carry1: carry, carry2: carry, ..pair };
+
+    // Ensure option is either only call OR put
+    let mut call_opt: bool = call;
+
+    // If neither flag has been specified, default to call
+    if !call && !put { call_opt = true; } else if call && put {
+        // Check for double call/put flags in arguments
+        eprintln!("ERROR: Ambigious arguments, only specify a single option type.");
+        return;
+    }
+
+    if pair.correlation.abs() >= 1.0 {
+        eprintln!("ERROR: Incorrect arguments, the correlation must be strictly between -1 and 1.");
+        return;
+    }
+
+
+    // Output
+    println!("\u{1F4B0} Two Asset Options Pricing Tool");
+    println!("--------------------------------\n");
+
+    match rainbow {
+        Some(r) => println!("Option Type:        {:?} {}", r, if call_opt { "Call" } else { "Put" }),
+        None => println!("Option Type:        Spread {}", if call_opt { "Call" } else { "Put" }),
+    }
+    println!("First Spot Price:   {}", pair.spot1);
+    println!("Second Spot Price:  {}", pair.spot2);
+    println!("Strike Price:       {}", strike);
+    println!("Years to Maturity:  {}", time);
+    println!("Risk-Free Rate:     {}", rate);
+    if let Some(path) = &rates.curve { println!("Yield Curve:        {} (zero rate to expiry)", path); }
+    println!("First Volatility:   {}", pair.volatility1);
+    println!("Second Volatility:  {}", pair.volatility2);
+    println!("Correlation:        {}", pair.correlation);
+    println!("Paths:              {}", paths);
+
+    println!("\n--------------------------------\n");
+
+    match rainbow {
+        Some(r) => {
+            println!("Option Price using Stulz Model:               {}", (rainbow_option(&pair, strike, time, rate, r, call_opt) * 100.0).round() / 100.0);
+        }
+        None => {
+            // A zero strike spread call is the option to exchange the second asset for the first
+            if strike == 0.0 && call_opt {
+                println!("Option Price using Margrabe Model:            {}", (exchange_option(&pair, time, rate) * 100.0).round() / 100.0);
+            }
+            println!("Option Price using Kirk Model:                {}", (kirk_spread(&pair, strike, time, rate, call_opt) * 100.0).round() / 100.0);
+            println!("Option Price using Bjerksund-Stensland Model: {}", (bjerksund_stensland_spread(&pair, strike, time, rate, call_opt) * 100.0).round() / 100.0);
+        }
+    }
+
+    match monte_carlo_two_asset(&pair, strike, time, rate, rainbow, paths, seed, call_opt) {
+        Ok(simulated) => println!("Option Price using Monte Carlo:               {} (std. error {})", (simulated.price * 100.0).round() / 100.0, (simulated.std_error * 10000.0).round() / 10000.0),
+        Err(e) => eprintln!("ERROR: Incorrect arguments, {}", e),
+    }
+}
@@ -0,0 +1,124 @@
+/*
+
+JDA Options Pricing
+/src/commands/vix.rs
+JohnDavid Abe
+
+*/
+
+
+use chrono::NaiveDate;
+use crate::day_count::DayCount;
+use crate::fetch::{fetch_treasury_curve, settlement_symbol, time_to_expiry};
+use crate::provider::{MarketDataProvider, Provider, ProviderArgs};
+use crate::variance::{ExpiryVariance, interpolate_variance, model_free_variance};
+use crate::yield_curve::Interpolation;
+
+
+// Entry point for the command
+pub async fn run(symbol: String, days: Vec<u32>, min_days: u32, provider: ProviderArgs) {
+
+    // The index is quoted at 30 days by default
+    let days: Vec<u32> = if days.is_empty() { vec![30] } else { days };
+
+    // Check arguments
+    if days.contains(&0) {
+        eprintln!("ERROR: Incorrect arguments, every horizon must be at least one day.");
+        return;
+    }
+
+
+    // Connect to the market data source
+    let provider: Provider = match Provider::load(&provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            eprintln!("Failed to load the market data provider: {}", e);
+            return;
+        }
+    };
+
+
+    // The spot, the curve and the listed expirations
+    let market = async {
+        let spot = provider.quote(&symbol).await?.last;
+        let curve = fetch_treasury_curve(&provider, Interpolation::MonotoneCubic).await?;
+        let expirations = provider.expirations(&symbol, true).await?;
+        Ok::<_, Box<dyn std::error::Error>>((spot, curve, expirations))
+    };
+
+    let (spot, curve, mut expirations) = match market.await {
+        Ok(market) => market,
+        Err(e) => {
+            eprintln!("Failed to fetch market data: {}", e);
+            return;
+        }
+    };
+    expirations.sort();
+
+
+    // Calendar years to each expiry, dropping those too close to expiry to be stable
+        // This first pass is before the chains are fetched, so it goes by the underlying's monthly settlement
+    let minimum: f64 = (min_days as f64) / 365.0;
+    let dated: Vec<(NaiveDate, f64)> = expirations.iter()
+        .filter_map(|date| time_to_expiry(&provider, &symbol, *date, DayCount::Act365Fixed).ok().map(|(_, years)| (*date, years)))
+        .filter(|(_, years)| *years >= minimum)
+        .collect();
+
+    // Only the expiries up to the longest horizon and the first one past it are needed
+    let longest: f64 = (*days.iter().max().unwrap_or(&30) as f64) / 365.0;
+    let needed: usize = dated.iter().position(|(_, years)| *years >= longest).map(|i| i + 1).unwrap_or(dated.len());
+
+
+    // Model-free variance of every expiry
+    let mut terms: Vec<(NaiveDate, ExpiryVariance)> = Vec::new();
+    for (date, _) in dated.iter().take(needed) {
+
+        let variance = async {
+            let calls = provider.chain(&symbol, *date, true).await?;
+            let puts = provider.chain(&symbol, *date, false).await?;
+            let (_, years) = time_to_expiry(&provider, settlement_symbol(&calls, &symbol), *date, DayCount::Act365Fixed)?;
+            Ok::<_, Box<dyn std::error::Error>>(model_free_variance(&calls, &puts, years, curve.zero_rate(years))?)
+        };
+
+        match variance.await {
+            Ok(variance) => terms.push((*date, variance)),
+            Err(e) => eprintln!("{} Failed to compute the variance: {}", date, e),
+        }
+    }
+
+    if terms.is_empty() {
+        eprintln!("Failed to compute the variance: no expiration at least {} days out could be used", min_days);
+        return;
+    }
+
+
+    // Output
+    println!("\u{1F4B0} Variance Index Tool");
+    println!("--------------------------------\n");
+
+    println!("Symbol:             {}", symbol);
+    println!("Spot Price:         {}", spot);
+
+    println!("\n--------------------------------\n");
+
+    // Variance swap fair strikes to each listed expiry
+    println!("{:>12} {:>8} {:>9} {:>10} {:>9} {:>8} {:>10} {:>10}", "Expiration", "Days", "Rate", "Forward", "K0", "Strikes", "Variance", "Vol Strike");
+    for (date, variance) in terms.iter() {
+        println!("{:>12} {:>8.2} {:>8.4}% {:>10.4} {:>9} {:>8} {:>10.6} {:>10.2}",
+            date.to_string(), variance.time * 365.0, variance.rate * 100.0, variance.forward, variance.atm_strike, variance.strikes.len(), variance.variance, variance.volatility() * 100.0);
+    }
+
+    println!("\n--------------------------------\n");
+
+    // Constant maturity index at each horizon
+    let points: Vec<(f64, f64)> = terms.iter().map(|(_, variance)| (variance.time, variance.variance)).collect();
+    println!("{:>8} {:>12} {:>12} {:>12} {:>10} {:>8}", "Horizon", "Near", "Far", "Near Weight", "Variance", "Index");
+    for horizon in days {
+        if let Some((variance, near, far, weight)) = interpolate_variance(&points, (horizon as f64) / 365.0) {
+            println!("{:>8} {:>12} {:>12} {:>12.4} {:>10.6} {:>8.2}",
+                format!("{}d", horizon), terms[near].0.to_string(), terms[far].0.to_string(), weight, variance, variance.max(0.0).sqrt() * 100.0);
+        }
+    }
+
+    println!("\nThe index is 100 times the square root of the annualised variance, held flat past the first and last expiries");
+}
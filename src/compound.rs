@@ -0,0 +1,76 @@
+/*
+
+JDA Options Pricing
+/src/compound.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::bivariate::bivariate_normal_cdf;
+use crate::black_scholes::{d1, d2, generalized_black_scholes};
+use crate::monte_carlo::{MonteCarloEstimate, monte_carlo};
+use crate::solver::positive_root;
+
+
+
+// Price an option on an option (Geske)
+    // The compound option expires at compound_time with compound_strike, the underlying option at time with strike
+    // call/underlying_call pick between call on call, put on call, call on put and put on put
+pub fn compound_option(spot: f64, strike: f64, time: f64, compound_strike: f64, compound_time: f64, rate: f64, volatility: f64, call: bool, underlying_call: bool) -> Result<f64, String> {
+
+    if compound_time <= 0.0 || compound_time >= time {
+        return Err("the compound option must expire before the underlying option".to_string());
+    }
+
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+
+    // Stock price at the compound expiry where the underlying option is worth exactly the compound strike
+    let remaining: f64 = time - compound_time;
+    let critical: f64 = positive_root(
+        |s| generalized_black_scholes(s, strike, remaining, rate, carry, volatility, underlying_call) - compound_strike,
+        strike,
+        1e-8,
+    ).ok_or("could not find the critical stock price, check the compound strike")?;
+
+    let y1: f64 = d1(spot, critical, compound_time, carry, volatility);
+    let y2: f64 = d2(spot, critical, compound_time, carry, volatility);
+    let z1: f64 = d1(spot, strike, time, carry, volatility);
+    let z2: f64 = d2(spot, strike, time, carry, volatility);
+    let rho: f64 = (compound_time / time).sqrt();
+
+    let asset: f64 = spot * ((carry - rate) * time).exp();
+    let cash: f64 = strike * (-1.0 * rate * time).exp();
+    let compound_cash: f64 = compound_strike * (-1.0 * rate * compound_time).exp();
+
+    let price: f64 = match (call, underlying_call) {
+        (true, true) => asset * bivariate_normal_cdf(z1, y1, rho) - cash * bivariate_normal_cdf(z2, y2, rho) - compound_cash * n(y2),
+        (false, true) => cash * bivariate_normal_cdf(z2, -1.0 * y2, -1.0 * rho) - asset * bivariate_normal_cdf(z1, -1.0 * y1, -1.0 * rho) + compound_cash * n(-1.0 * y2),
+        (true, false) => cash * bivariate_normal_cdf(-1.0 * z2, -1.0 * y2, rho) - asset * bivariate_normal_cdf(-1.0 * z1, -1.0 * y1, rho) - compound_cash * n(-1.0 * y2),
+        (false, false) => asset * bivariate_normal_cdf(-1.0 * z1, y1, -1.0 * rho) - cash * bivariate_normal_cdf(-1.0 * z2, y2, -1.0 * rho) + compound_cash * n(y2),
+    };
+
+    return Ok(price);
+}
+
+
+
+// Price the same compound option by simulating the stock to the compound expiry and valuing the underlying option there with black-scholes
+pub fn monte_carlo_compound(spot: f64, strike: f64, time: f64, compound_strike: f64, compound_time: f64, rate: f64, volatility: f64, paths: u32, seed: Option<u64>, call: bool, underlying_call: bool) -> MonteCarloEstimate {
+
+    let remaining: f64 = time - compound_time;
+
+    return monte_carlo(spot, rate, rate, volatility, &[compound_time], compound_time, paths, seed, |path| {
+        let underlying: f64 = generalized_black_scholes(path[0], strike, remaining, rate, rate, volatility, underlying_call);
+        if call { (underlying - compound_strike).max(0.0) } else { (compound_strike - underlying).max(0.0) }
+    });
+}
@@ -0,0 +1,175 @@
+/*
+
+JDA Options Pricing
+/src/config.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+// Modules
+use crate::provider::ProviderType;
+
+
+
+// Settings from ~/.config/rustquant/config.toml, every section and key is optional
+    // [provider]
+    // default = "http"             # or "fixture"
+    // fixtures = "/path/to/fixtures"
+    //
+    // [marketdata]
+    // token = "..."
+    //
+    // [fred]
+    // api_key = "..."
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub provider: ProviderConfig,
+    #[serde(default)]
+    pub marketdata: MarketDataConfig,
+    #[serde(default)]
+    pub fred: FredConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderConfig {
+    pub default: Option<String>,
+    pub fixtures: Option<PathBuf>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarketDataConfig {
+    pub token: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FredConfig {
+    pub api_key: Option<String>,
+}
+
+
+
+// API credentials for the live data providers
+#[derive(Clone, Default)]
+pub struct Credentials {
+    marketdata_token: Option<String>,
+    fred_api_key: Option<String>,
+}
+
+impl Credentials {
+
+    // Environment variables win over the config file
+    pub fn load(config: &Config) -> Credentials {
+        Credentials {
+            marketdata_token: from_env("MARKETDATA_TOKEN").or(config.marketdata.token.clone()),
+            fred_api_key: from_env("FRED_API_KEY").or(config.fred.api_key.clone()),
+        }
+    }
+
+    pub fn marketdata_token(&self) -> Result<&str, String> {
+        self.marketdata_token.as_deref().ok_or_else(|| missing("marketdata.app token", "MARKETDATA_TOKEN", "[marketdata] token"))
+    }
+
+    pub fn fred_api_key(&self) -> Result<&str, String> {
+        self.fred_api_key.as_deref().ok_or_else(|| missing("FRED API key", "FRED_API_KEY", "[fred] api_key"))
+    }
+}
+
+// Never print the secrets themselves
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let shown = |secret: &Option<String>| if secret.is_some() { "<redacted>" } else { "<missing>" };
+        f.debug_struct("Credentials")
+            .field("marketdata_token", &shown(&self.marketdata_token))
+            .field("fred_api_key", &shown(&self.fred_api_key))
+            .finish()
+    }
+}
+
+
+
+impl Config {
+
+    // Where the config file lives, RUSTQUANT_CONFIG overrides the usual $XDG_CONFIG_HOME (or ~/.config) location
+    pub fn path() -> Option<PathBuf> {
+
+        if let Some(path) = from_env("RUSTQUANT_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+
+        let base: PathBuf = match from_env("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(from_env("HOME")?).join(".config"),
+        };
+
+        return Some(base.join("rustquant").join("config.toml"));
+    }
+
+    // Read the config file, a missing file is the same as an empty one
+    pub fn load() -> Result<Config, Box<dyn Error>> {
+
+        let path: PathBuf = match Config::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        };
+
+        let text: String = fs::read_to_string(&path).map_err(|e| format!("unable to read {}, {}", path.display(), e))?;
+        let config: Config = toml::from_str(&text).map_err(|e| format!("invalid config file {}, {}", path.display(), e))?;
+
+        Ok(config)
+    }
+
+    // Provider named in the config file, if any
+    pub fn provider(&self) -> Result<Option<ProviderType>, Box<dyn Error>> {
+        match &self.provider.default {
+            Some(name) => Ok(Some(<ProviderType as clap::ValueEnum>::from_str(name, true).map_err(|_| format!("unknown provider \"{}\" in the config file, use http or fixture", name))?)),
+            None => Ok(None),
+        }
+    }
+}
+
+
+
+// Hide credential values in a URL's query string before it is shown anywhere
+pub fn redact(url: &str) -> String {
+
+    let (base, query) = match url.split_once('?') {
+        Some(parts) => parts,
+        None => return url.to_string(),
+    };
+
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if ["api_key", "apikey", "token", "access_token"].contains(&key.to_lowercase().as_str()) => format!("{}=REDACTED", key),
+            _ => pair.to_string(),
+        })
+        .collect();
+
+    return format!("{}?{}", base, query.join("&"));
+}
+
+
+
+// Non-empty environment variable
+fn from_env(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+fn missing(what: &str, variable: &str, key: &str) -> String {
+    let path: String = Config::path().map(|p| p.display().to_string()).unwrap_or("~/.config/rustquant/config.toml".to_string());
+    format!("no {} configured, set the {} environment variable or add {} to {}", what, variable, key, path)
+}
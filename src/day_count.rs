@@ -0,0 +1,181 @@
+/*
+
+JDA Options Pricing
+/src/day_count.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+// Modules
+use crate::calendar::Calendar;
+
+
+
+// Conventions for turning the time between two dates into years
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DayCount {
+    // Actual days over 365
+    #[value(name = "act365f")]
+    Act365Fixed,
+    // Actual days over 360
+    #[value(name = "act360")]
+    Act360,
+    // US 30/360 bond basis, every month has 30 days
+    #[value(name = "30-360")]
+    Thirty360,
+    // ISDA actual/actual, days in each calendar year over that year's length
+    #[value(name = "actact")]
+    ActAct,
+    // Trading time, exchange sessions over 252 a year
+    #[value(name = "trading252")]
+    Trading252,
+}
+
+impl DayCount {
+
+    // Years between two dates
+    pub fn year_fraction(&self, start: NaiveDate, end: NaiveDate, calendar: &Calendar) -> f64 {
+
+        if end < start {
+            return -1.0 * self.year_fraction(end, start, calendar);
+        }
+
+        let days: f64 = (end - start).num_days() as f64;
+
+        match self {
+            DayCount::Act365Fixed => days / 365.0,
+            DayCount::Act360 => days / 360.0,
+            DayCount::Thirty360 => {
+                let d1: i32 = (start.day() as i32).min(30);
+                let d2: i32 = if d1 == 30 { (end.day() as i32).min(30) } else { end.day() as i32 };
+                let months: i32 = 12 * (end.year() - start.year()) + (end.month() as i32 - start.month() as i32);
+                (30.0 * (months as f64) + ((d2 - d1) as f64)) / 360.0
+            }
+            DayCount::ActAct => {
+                let mut years: f64 = 0.0;
+                let mut from: NaiveDate = start;
+                while from < end {
+                    let next_year = NaiveDate::from_ymd_opt(from.year() + 1, 1, 1).unwrap_or(end);
+                    let to: NaiveDate = next_year.min(end);
+                    years += ((to - from).num_days() as f64) / (days_in_year(from.year()) as f64);
+                    from = to;
+                }
+                years
+            }
+            DayCount::Trading252 => (calendar.business_days_between(start, end) as f64) / 252.0,
+        }
+    }
+
+    // Years between two New York wall clock times, counting part days
+        // Calendar conventions add the time of day to the date fraction, trading time only counts the hours the exchange is open
+    pub fn year_fraction_between(&self, start: NaiveDateTime, end: NaiveDateTime, calendar: &Calendar) -> f64 {
+
+        if end < start {
+            return -1.0 * self.year_fraction_between(end, start, calendar);
+        }
+
+        let seconds = |time: NaiveDateTime| time.time().signed_duration_since(chrono::NaiveTime::MIN).num_seconds() as f64;
+        let part_day: f64 = (seconds(end) - seconds(start)) / 86400.0;
+
+        match self {
+            DayCount::Act365Fixed => self.year_fraction(start.date(), end.date(), calendar) + part_day / 365.0,
+            DayCount::Act360 | DayCount::Thirty360 => self.year_fraction(start.date(), end.date(), calendar) + part_day / 360.0,
+            DayCount::ActAct => self.year_fraction(start.date(), end.date(), calendar) + part_day / (days_in_year(end.year()) as f64),
+            DayCount::Trading252 => {
+
+                // Fraction of each session between start and end
+                let mut sessions: f64 = 0.0;
+                for date in start.date().iter_days().take_while(|d| *d <= end.date()) {
+                    if let Some((open, close)) = calendar.session(date) {
+                        let open: NaiveDateTime = date.and_time(open);
+                        let close: NaiveDateTime = date.and_time(close);
+                        let length: f64 = (close - open).num_seconds() as f64;
+                        let overlap: f64 = (close.min(end) - open.max(start)).num_seconds() as f64;
+                        sessions += overlap.max(0.0) / length;
+                    }
+                }
+                sessions / 252.0
+            }
+        }
+    }
+
+    // Calendar days in one year of the count, for quoting a yearly rate such as theta per calendar day
+        // ACT/ACT years are as long as the calendar year they fall in, and trading time's 252 sessions span a whole calendar year
+    pub fn days_per_year(&self, year: i32) -> f64 {
+        match self {
+            DayCount::Act365Fixed | DayCount::Trading252 => 365.0,
+            DayCount::Act360 | DayCount::Thirty360 => 360.0,
+            DayCount::ActAct => days_in_year(year) as f64,
+        }
+    }
+}
+
+
+
+fn days_in_year(year: i32) -> i64 {
+    let leap: bool = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    return if leap { 366 } else { 365 };
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    }
+
+    fn close_enough(actual: f64, expected: f64) -> bool {
+        return (actual - expected).abs() < 1e-12;
+    }
+
+    #[test]
+    fn day_counts() {
+        let calendar = Calendar::nyse();
+
+        // The last day of 2024 to the end of the first quarter, across a year end
+        let (start, end) = (date(2024, 12, 31), date(2025, 3, 31));
+        assert!(close_enough(DayCount::Act365Fixed.year_fraction(start, end, &calendar), 90.0 / 365.0));
+        assert!(close_enough(DayCount::Act360.year_fraction(start, end, &calendar), 90.0 / 360.0));
+        assert!(close_enough(DayCount::Thirty360.year_fraction(start, end, &calendar), 0.25));
+        assert!(close_enough(DayCount::ActAct.year_fraction(start, end, &calendar), 1.0 / 366.0 + 89.0 / 365.0));
+        // 21 sessions in January, 19 in February and 21 in March
+        assert!(close_enough(DayCount::Trading252.year_fraction(start, end, &calendar), 61.0 / 252.0));
+
+        // Reversed dates give negative years
+        assert!(close_enough(DayCount::Act365Fixed.year_fraction(end, start, &calendar), -90.0 / 365.0));
+
+        // 30/360 only clips the end date to the 30th when the start was too
+        assert!(close_enough(DayCount::Thirty360.year_fraction(date(2025, 1, 15), date(2025, 3, 31), &calendar), 76.0 / 360.0));
+
+        // A year of each count in calendar days
+        assert_eq!(DayCount::Act360.days_per_year(2025), 360.0);
+        assert_eq!(DayCount::ActAct.days_per_year(2024), 366.0);
+        assert_eq!(DayCount::Trading252.days_per_year(2025), 365.0);
+    }
+
+    #[test]
+    fn part_days() {
+        let calendar = Calendar::nyse();
+        let at = |day: NaiveDate, hour: u32, minute: u32| day.and_hms_opt(hour, minute, 0).unwrap();
+
+        // Friday 14:00 to Monday 16:00 is three days and two hours of calendar time but one and a third sessions
+        let (start, end) = (at(date(2025, 6, 13), 14, 0), at(date(2025, 6, 16), 16, 0));
+        assert!(close_enough(DayCount::Act365Fixed.year_fraction_between(start, end, &calendar), (3.0 + 2.0 / 24.0) / 365.0));
+        assert!(close_enough(DayCount::Act360.year_fraction_between(start, end, &calendar), (3.0 + 2.0 / 24.0) / 360.0));
+        assert!(close_enough(DayCount::Trading252.year_fraction_between(start, end, &calendar), (1.0 + 2.0 / 6.5) / 252.0));
+
+        // Half of the Independence Day eve half session
+        let half_day: NaiveDate = date(2025, 7, 3);
+        assert!(close_enough(DayCount::Trading252.year_fraction_between(at(half_day, 9, 30), at(half_day, 11, 15), &calendar), 0.5 / 252.0));
+    }
+}
@@ -0,0 +1,122 @@
+/*
+
+JDA Options Pricing
+/src/density.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Modules
+use crate::black_scholes::generalized_black_scholes;
+use crate::svi::Svi;
+
+
+
+// Moments of the log return ln(S_T / F) under the density
+#[derive(Debug, Clone, Copy)]
+pub struct Moments {
+    // Mean of the terminal price, which should come back to the forward
+    pub mean: f64,
+    pub volatility: f64,
+    pub skewness: f64,
+    // Excess kurtosis, zero for a lognormal
+    pub kurtosis: f64,
+}
+
+
+
+// Risk-neutral density of the price at expiry (Breeden-Litzenberger), read off a smoothed smile
+    // The undiscounted call C(K) = F N(d1) - K N(d2) at the smile's vol gives P(S_T < K) = 1 + dC/dK and the density d2C/dK2
+#[derive(Debug, Clone)]
+pub struct RiskNeutralDensity {
+    pub forward: f64,
+    pub time: f64,
+    pub smile: Svi,
+    // (strike, density) on an evenly spaced grid
+    pub points: Vec<(f64, f64)>,
+}
+
+impl RiskNeutralDensity {
+
+    // Density on a grid of strikes six at-the-money standard deviations either side of the forward
+    pub fn from_smile(smile: Svi, forward: f64, time: f64, points: usize) -> Result<RiskNeutralDensity, String> {
+
+        if forward <= 0.0 || time <= 0.0 || points < 3 {
+            return Err("the forward and time must be positive with at least three points".to_string());
+        }
+
+        let deviation: f64 = smile.total_variance(0.0).max(1e-8).sqrt();
+        let (low, high) = (forward * (-6.0 * deviation).exp(), forward * (6.0 * deviation).exp());
+        let step: f64 = (high - low) / ((points - 1) as f64);
+
+        let mut density = RiskNeutralDensity { forward, time, smile, points: Vec::with_capacity(points) };
+        for i in 0..points {
+            let strike: f64 = low + step * (i as f64);
+            let h: f64 = (0.5 * step).min(0.5 * strike);
+            let value: f64 = (density.call(strike + h) - 2.0 * density.call(strike) + density.call(strike - h)) / (h * h);
+            density.points.push((strike, value));
+        }
+
+        return Ok(density);
+    }
+
+    // Undiscounted call at the smile's vol
+    fn call(&self, strike: f64) -> f64 {
+        let volatility: f64 = self.smile.volatility((strike / self.forward).ln(), self.time);
+        return generalized_black_scholes(self.forward, strike, self.time, 0.0, 0.0, volatility, true);
+    }
+
+    // P(S_T < level) from the slope of the call curve
+    pub fn probability_below(&self, level: f64) -> f64 {
+        if level <= 0.0 { return 0.0; }
+        let h: f64 = level * 1e-4;
+        return (1.0 + (self.call(level + h) - self.call(level - h)) / (2.0 * h)).clamp(0.0, 1.0);
+    }
+
+    pub fn probability_above(&self, level: f64) -> f64 {
+        return 1.0 - self.probability_below(level);
+    }
+
+    // Probability captured on the grid, close to one when the tails are thin
+    pub fn mass(&self) -> f64 {
+        return self.integrate(|_| 1.0);
+    }
+
+    // Whether the density goes negative anywhere on the grid, a sign of butterfly arbitrage in the smile
+    pub fn negative(&self) -> bool {
+        return self.points.iter().any(|(_, density)| *density < -1e-8);
+    }
+
+    // Log return moments, normalised by the mass on the grid
+    pub fn moments(&self) -> Moments {
+
+        let mass: f64 = self.mass();
+        let log_return = |strike: f64| (strike / self.forward).ln();
+
+        let mean_return: f64 = self.integrate(log_return) / mass;
+        let central = |power: i32| self.integrate(|strike| (log_return(strike) - mean_return).powi(power)) / mass;
+        let variance: f64 = central(2);
+
+        return Moments {
+            mean: self.integrate(|strike| strike) / mass,
+            volatility: (variance / self.time).sqrt(),
+            skewness: central(3) / variance.powf(1.5),
+            kurtosis: central(4) / variance.powi(2) - 3.0,
+        };
+    }
+
+    // Expected absolute move from the forward, the undiscounted at-the-forward straddle
+    pub fn expected_move(&self) -> f64 {
+        return 2.0 * self.call(self.forward);
+    }
+
+    // Trapezoid rule of f(strike) times the density over the grid
+    fn integrate<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+        return self.points.windows(2).map(|pair| {
+            let ((k0, p0), (k1, p1)) = (pair[0], pair[1]);
+            0.5 * (k1 - k0) * (f(k0) * p0 + f(k1) * p1)
+        }).sum();
+    }
+}
@@ -0,0 +1,106 @@
+/*
+
+JDA Options Pricing
+/src/digital.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::black_scholes::{d1, d2, generalized_black_scholes};
+
+
+
+// The payoffs priced by the digital command
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DigitalType {
+    Cash,
+    Asset,
+    Gap,
+    Supershare,
+}
+
+
+
+// Price a cash-or-nothing option, paying a fixed cash amount if it finishes in the money
+pub fn cash_or_nothing(spot: f64, strike: f64, payout: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let d2: f64 = d2(spot, strike, time, rate, volatility);
+
+    if call {
+        return payout * (-1.0 * rate * time).exp() * standard_normal.cdf(d2);
+    } else {
+        return payout * (-1.0 * rate * time).exp() * standard_normal.cdf(-1.0 * d2);
+    }
+}
+
+
+
+// Price an asset-or-nothing option, paying the stock itself if it finishes in the money
+pub fn asset_or_nothing(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let d1: f64 = d1(spot, strike, time, rate, volatility);
+
+    // No dividends, so the stock is worth its spot price today
+    if call {
+        return spot * standard_normal.cdf(d1);
+    } else {
+        return spot * standard_normal.cdf(-1.0 * d1);
+    }
+}
+
+
+
+// Price a gap option, which pays the stock less the strike but only once the trigger is crossed
+    // The payoff can be negative when the trigger and strike are on opposite sides
+pub fn gap(spot: f64, strike: f64, trigger: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    let standard_normal = Normal::standard();
+
+    // Exercise is decided by the trigger, so d1/d2 are taken against it
+    let d1: f64 = d1(spot, trigger, time, rate, volatility);
+    let d2: f64 = d2(spot, trigger, time, rate, volatility);
+
+    if call {
+        return spot * standard_normal.cdf(d1) - strike * (-1.0 * rate * time).exp() * standard_normal.cdf(d2);
+    } else {
+        return strike * (-1.0 * rate * time).exp() * standard_normal.cdf(-1.0 * d2) - spot * standard_normal.cdf(-1.0 * d1);
+    }
+}
+
+
+
+// Price a supershare, which pays the final stock price divided by the lower strike if it finishes between the two strikes
+pub fn supershare(spot: f64, lower: f64, upper: f64, time: f64, rate: f64, volatility: f64) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let d_lower: f64 = d1(spot, lower, time, rate, volatility);
+    let d_upper: f64 = d1(spot, upper, time, rate, volatility);
+
+    return (spot / lower) * (standard_normal.cdf(d_lower) - standard_normal.cdf(d_upper));
+}
+
+
+
+// Replicate a cash-or-nothing payout with a tight vanilla spread either side of the strike
+    // As the width shrinks this converges to the closed form, so the gap is a check on the digital price
+pub fn call_spread_replication(spot: f64, strike: f64, payout: f64, width: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    let lower: f64 = generalized_black_scholes(spot, strike - 0.5 * width, time, rate, rate, volatility, call);
+    let upper: f64 = generalized_black_scholes(spot, strike + 0.5 * width, time, rate, rate, volatility, call);
+
+    // Long the lower strike call and short the upper (the reverse for puts), scaled so the spread pays the cash amount
+    if call {
+        return payout * (lower - upper) / width;
+    } else {
+        return payout * (upper - lower) / width;
+    }
+}
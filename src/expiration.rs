@@ -0,0 +1,83 @@
+/*
+
+JDA Options Pricing
+/src/expiration.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::Args;
+use chrono::NaiveDate;
+use dialoguer::Select;
+use std::error::Error;
+use std::io::{IsTerminal, stdin};
+
+// Modules
+use crate::calendar::new_york_time;
+use crate::provider::MarketDataProvider;
+
+
+
+// How to pick expiration dates without the interactive prompt, at most one may be given
+#[derive(Debug, Clone, Args)]
+pub struct ExpirationArgs {
+
+    // Expiration date as YYYY-MM-DD, which must be listed
+    #[arg(short = 'e', long = "expiration", conflicts_with_all = ["dte", "all_expirations"])]
+    pub expiration: Option<NaiveDate>,
+
+    // The listed expiration nearest to this many calendar days away
+    #[arg(long = "dte", conflicts_with = "all_expirations")]
+    pub dte: Option<u32>,
+
+    // Every listed expiration
+    #[arg(long = "all-expirations", default_value_t = false)]
+    pub all_expirations: bool,
+}
+
+
+
+// Expiration dates to work on, from the arguments or (on a terminal) chosen from a list of the listed dates
+pub async fn select_expirations<P: MarketDataProvider>(provider: &P, symbol: &str, call: bool, args: &ExpirationArgs) -> Result<Vec<NaiveDate>, Box<dyn Error>> {
+
+    // Fail straight away instead of waiting on a prompt nobody can answer
+    let interactive: bool = args.expiration.is_none() && args.dte.is_none() && !args.all_expirations;
+    if interactive && !stdin().is_terminal() {
+        return Err("no expiration given and stdin is not a terminal, pass --expiration YYYY-MM-DD, --dte N or --all-expirations".into());
+    }
+
+    let dates: Vec<NaiveDate> = provider.expirations(symbol, call).await?;
+    if dates.is_empty() {
+        return Err(format!("{} has no listed expirations", symbol).into());
+    }
+
+    if let Some(expiration) = args.expiration {
+        if !dates.contains(&expiration) {
+            let listed: Vec<String> = dates.iter().take(8).map(|d| d.to_string()).collect();
+            return Err(format!("{} is not a listed expiration for {}, listed dates start {}", expiration, symbol, listed.join(", ")).into());
+        }
+        return Ok(vec![expiration]);
+    }
+
+    if let Some(dte) = args.dte {
+        let today: NaiveDate = new_york_time(provider.now()).date();
+        let nearest = dates.iter().min_by_key(|d| ((**d - today).num_days() - (dte as i64)).abs()).copied();
+        return Ok(nearest.into_iter().collect());
+    }
+
+    if args.all_expirations {
+        return Ok(dates);
+    }
+
+    // Get the user to select the expiration date from drop down
+    let selection = Select::new()
+        .with_prompt("Choose an options expiration date")
+        .items(&dates)
+        .default(0)
+        .interact()?;
+
+    Ok(vec![dates[selection]])
+}
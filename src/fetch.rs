@@ -9,153 +9,287 @@ JohnDavid Abe
 
 
 // Packages
-use reqwest::Client;
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::error::Error;
 
+// Modules
+use crate::calendar::{Calendar, Settlement, new_york_time};
+use crate::day_count::DayCount;
+use crate::implied_carry::{ImpliedCarry, implied_carry};
+use crate::provider::{ChainQuote, MarketDataProvider, StockQuote, VendorGreeks};
+use crate::smile::{SmileAxis, SmileMethod, SmileVolatility, smile_volatility};
+use crate::yield_curve::{Interpolation, YieldCurve};
+
 
 
 // Struct to hold data on a chosen option from the chain
 #[derive(Debug)]
 pub struct OptionData {
     pub symbol: String,
+    pub option_symbol: String,
     pub spot: f64,
     pub strike: f64,
     pub expiration: f64,
     pub volatility: f64,
     pub rate: f64,
+
+    // Market quote on the contract
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub mid: Option<f64>,
+    pub last: Option<f64>,
+    pub volume: u64,
+    pub open_interest: u64,
+    pub vendor_greeks: VendorGreeks,
+    pub quote_time: Option<DateTime<Utc>>,
+
+    // Market quote on the underlying
+    pub underlying_bid: Option<f64>,
+    pub underlying_ask: Option<f64>,
+    pub underlying_time: Option<DateTime<Utc>>,
+
+    // Listed quotes behind an interpolated vol, when the exact strike was kept
+    pub smile: Option<SmileVolatility>,
+
+    // Forward, discount rate and dividend or borrow rate implied by put-call parity on the expiry
+    pub carry: Option<ImpliedCarry>,
 }
 
-// Fetch expiration dates from the option chain for a particular symbol
-pub async fn fetch_expiration_dates(symbol: &str, call: bool) -> Result<Vec<NaiveDate>, Box<dyn Error>> {
 
-    // Make request to the options chain
-    let client = Client::new();
-    let side = if call { "call" } else { "put" };
-    let url = format!("https://api.marketdata.app/v1/options/expirations/{}?side={}", symbol, side);
-    let resp = client.get(&url).send().await?;
-    let json: serde_json::Value = resp.json().await?;
 
-    // Return expirations as a list of NaiveDate objects
-    let expirations = json["expirations"]
-        .as_array()
-        .ok_or("Invalid expiration data")?
+// Fetch option data given an expiration date and a symbol and a type, choosing the option from the options chain with the closest strike price to the target_strike
+    // With a smile method the target strike is kept as is and its vol is interpolated across the listed strikes instead
+pub async fn fetch_american_option_data<P: MarketDataProvider>(provider: &P, symbol: &str, target_strike: f64, expiration: NaiveDate, call: bool, day_count: DayCount, smile: Option<(SmileMethod, SmileAxis)>, carry: bool) -> Result<OptionData, Box<dyn Error>> {
+
+    // Get spot price
+    let underlying = provider.quote(symbol).await?;
+
+    // Build the US treasury curve
+    let curve = fetch_treasury_curve(provider, Interpolation::MonotoneCubic).await?;
+
+    fetch_option_data(provider, symbol, target_strike, expiration, call, day_count, smile, carry, &underlying, &curve).await
+}
+
+
+
+// The same for one expiry of several, off an underlying quote and curve fetched once and shared by every expiry
+pub async fn fetch_option_data<P: MarketDataProvider>(provider: &P, symbol: &str, target_strike: f64, expiration: NaiveDate, call: bool, day_count: DayCount, smile: Option<(SmileMethod, SmileAxis)>, carry: bool, underlying: &StockQuote, curve: &YieldCurve) -> Result<OptionData, Box<dyn Error>> {
+
+    // Get the options chain
+    let chain = provider.chain(symbol, expiration, call).await?;
+
+
+    // Find the option w/ the strike price closest to the user inputted strike price
+    let option = chain
         .iter()
-        .filter_map(|d| d.as_str())
-        .filter_map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+        .min_by(|a, b| (a.strike - target_strike).abs().total_cmp(&(b.strike - target_strike).abs()))
+        .ok_or("Empty option chain")?;
+
+
+
+    // Get the years to expiration of the option, whose root decides AM or PM settlement
+    let (expiry, calendar_years) = time_to_expiry(provider, settlement_symbol(std::slice::from_ref(option), symbol), expiration, day_count)?;
+
+
+    // Read off the zero rate to the expiration of the option, curve tenors are in calendar years
+    let rate: f64 = curve.zero_rate(calendar_years);
+
+
+    // Back the forward and carry out of both sides of the chain
+    let carry: Option<ImpliedCarry> = if carry {
+        let other = provider.chain(symbol, expiration, !call).await?;
+        let (calls, puts) = if call { (&chain, &other) } else { (&other, &chain) };
+        Some(implied_carry(calls, puts, underlying.last, expiry, curve.discount(calendar_years))?)
+    } else {
+        None
+    };
+
+
+    // Interpolate the vol at the exact strike across the smile, which prices a contract that isn't listed
+    if let Some((method, axis)) = smile {
+
+        let quotes: Vec<(f64, f64)> = chain.iter().map(|q| (q.strike, q.volatility)).collect();
+        let forward: f64 = carry.map(|c| c.forward).unwrap_or(underlying.last * (rate * expiry).exp());
+        let fitted = smile_volatility(&quotes, target_strike, forward, expiry, method, axis)?;
+
+        return Ok(OptionData {
+            symbol: symbol.to_string(),
+            option_symbol: String::new(),
+            spot: underlying.last,
+            strike: target_strike,
+            expiration: expiry,
+            volatility: fitted.volatility,
+            rate,
+            bid: None,
+            ask: None,
+            mid: None,
+            last: None,
+            volume: 0,
+            open_interest: 0,
+            vendor_greeks: VendorGreeks::default(),
+            quote_time: None,
+            underlying_bid: underlying.bid,
+            underlying_ask: underlying.ask,
+            underlying_time: underlying.updated,
+            smile: Some(fitted),
+            carry,
+        });
+    }
+
+    // Return the wrapped data
+    Ok(OptionData {
+        symbol: symbol.to_string(),
+        option_symbol: option.option_symbol.clone(),
+        spot: underlying.last,
+        strike: option.strike,
+        expiration: expiry,
+        volatility: option.volatility,
+        rate,
+        bid: option.bid,
+        ask: option.ask,
+        mid: option.mid_price(),
+        last: option.last,
+        volume: option.volume,
+        open_interest: option.open_interest,
+        vendor_greeks: option.greeks,
+        quote_time: option.updated,
+        underlying_bid: underlying.bid,
+        underlying_ask: underlying.ask,
+        underlying_time: underlying.updated,
+        smile: None,
+        carry,
+    })
+}
+
+
+
+// Years until an option expires, from now until the exchange close (or open for AM settled options) on the expiration date
+    // Takes the OCC option symbol, since an index's monthly and weekly roots settle differently on the same date
+    // Returns the time under the day count for pricing, and in calendar years (ACT/365) for reading rates off the curve
+pub fn time_to_expiry<P: MarketDataProvider>(provider: &P, option_symbol: &str, expiration: NaiveDate, day_count: DayCount) -> Result<(f64, f64), Box<dyn Error>> {
+
+    let calendar = Calendar::nyse();
+    let now = new_york_time(provider.now());
+    let settlement = Settlement::for_option(option_symbol, expiration);
+    let expires = calendar.expiry_time(expiration, settlement).ok_or("No trading session on or before the expiration date")?;
+    let expiry: f64 = day_count.year_fraction_between(now, expires, &calendar);
+
+    if expiry <= 0.0 {
+        return Err(format!("The {} option has already expired at {}", expiration, expires).into());
+    }
+
+    Ok((expiry, DayCount::Act365Fixed.year_fraction_between(now, expires, &calendar)))
+}
+
+
+
+// Option symbol that stands for a chain's expiry when reading its settlement, the underlying when the vendor leaves the symbols out
+pub fn settlement_symbol<'a>(chain: &'a [ChainQuote], symbol: &'a str) -> &'a str {
+    return chain.iter().map(|quote| quote.option_symbol.as_str()).find(|option_symbol| !option_symbol.is_empty()).unwrap_or(symbol);
+}
+
+
+
+// Forward of one expiry and its out-of-the-money (log-moneyness, vol) quotes, puts below the forward and calls at or above it
+    // The forward is from put-call parity on the chain, or grown off the curve when too few strikes have both sides quoted
+pub fn out_of_the_money_quotes(calls: &[ChainQuote], puts: &[ChainQuote], spot: f64, time: f64, calendar_years: f64, curve: &YieldCurve) -> (f64, Vec<(f64, f64)>) {
+
+    let forward: f64 = implied_carry(calls, puts, spot, time, curve.discount(calendar_years))
+        .map(|carry| carry.forward)
+        .unwrap_or(spot * (curve.zero_rate(calendar_years) * calendar_years).exp());
+
+    let quotes: Vec<(f64, f64)> = puts.iter().filter(|q| q.strike < forward)
+        .chain(calls.iter().filter(|q| q.strike >= forward))
+        .filter(|q| q.volatility > 0.0)
+        .map(|q| ((q.strike / forward).ln(), q.volatility))
         .collect();
 
-    Ok(expirations)
+    return (forward, quotes);
 }
 
 
 
-// Fetch option data given an expiration date and a symbol and a type, choosing the option from the options chain with the closest strike price to the target_strike
-pub async fn fetch_american_option_data(symbol: &str, target_strike: f64, expiration: NaiveDate, call: bool) -> Result<OptionData, Box<dyn Error>> {
+// Pull the latest par yield of every US treasury tenor and bootstrap them into a zero curve
+    // The constant maturity yields are semiannual bond equivalent par yields
+pub async fn fetch_treasury_curve<P: MarketDataProvider>(provider: &P, interpolation: Interpolation) -> Result<YieldCurve, Box<dyn Error>> {
+    let par_yields: Vec<(f64, f64)> = provider.treasury_yields().await?;
+    Ok(YieldCurve::bootstrap(&par_yields, 2, interpolation)?)
+}
 
-    // Make the request to the options chain
-    let client = Client::new();
-    let side = if call { "call" } else { "put" };
-    let url = format!(
-        "https://api.marketdata.app/v1/options/chain/{}?side={}&expiration={}",
-        symbol, side, expiration
-    );
-    let resp = client.get(&url).send().await?;
-    let json: serde_json::Value = resp.json().await?;
 
 
-    // Get all strike price data
-    let strike_prices = json["strike"]
-        .as_array()
-        .ok_or("Missing options strike data")?;
+// Replays the recorded AAPL fixtures through the auto flow, so it runs without a network, and runs the expiry clock at set times
+#[cfg(test)]
+mod tests {
 
+    use std::path::PathBuf;
+    use super::*;
+    use crate::expiration::{ExpirationArgs, select_expirations};
+    use crate::provider::{Bar, FixtureProvider};
 
-    // Find the option w/ the strike price closest to the user inputted strike price
-    let mut option_index: u32 = 0;
-    let mut min_strike_diff: f64 = ((strike_prices[0 as usize]).as_f64().unwrap() - target_strike).abs();
-    let mut current_diff: f64 = 0.0;
-
-    // Loop through all the strike prices
-    for i in 1..strike_prices.len() {
-        current_diff = ((strike_prices[i as usize]).as_f64().unwrap() - target_strike).abs();
-        
-        // Find the minimum difference between desired strike and each contract's strike
-        if current_diff < min_strike_diff {
-            min_strike_diff = current_diff;
-            option_index = i as u32;
-        }
+    fn fixtures() -> FixtureProvider {
+        return FixtureProvider::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures")).expect("fixtures load");
     }
 
+    // A provider that only tells the time, for the expiry clock
+    struct Clock(DateTime<Utc>);
 
-    // Get the strike from the closest option
-    let option_strike: f64 = (strike_prices[option_index as usize]).as_f64().unwrap();
+    impl MarketDataProvider for Clock {
+        fn now(&self) -> DateTime<Utc> { self.0 }
+        async fn expirations(&self, _: &str, _: bool) -> Result<Vec<NaiveDate>, Box<dyn Error>> { Err("no market data".into()) }
+        async fn chain(&self, _: &str, _: NaiveDate, _: bool) -> Result<Vec<ChainQuote>, Box<dyn Error>> { Err("no market data".into()) }
+        async fn quote(&self, _: &str) -> Result<StockQuote, Box<dyn Error>> { Err("no market data".into()) }
+        async fn candles(&self, _: &str, _: usize) -> Result<Vec<Bar>, Box<dyn Error>> { Err("no market data".into()) }
+        async fn treasury_yields(&self) -> Result<Vec<(f64, f64)>, Box<dyn Error>> { Err("no market data".into()) }
+    }
+
+    fn clock(instant: &str) -> Clock {
+        return Clock(DateTime::parse_from_rfc3339(instant).unwrap().with_timezone(&Utc));
+    }
 
+    #[tokio::test]
+    async fn auto_flow_from_fixtures() {
 
-    // Get the implied volatility (iv) of the option selected
-    let ivs = json["iv"]
-        .as_array()
-        .ok_or("Missing options iv data")?;
+        let provider: FixtureProvider = fixtures();
+        assert_eq!(provider.now().to_rfc3339(), "2025-06-02T18:00:00+00:00");
 
-    let option_iv: f64 = (ivs[option_index as usize]).as_f64().unwrap();
+        // A fixed expiration is taken as given, without the prompt
+        let args = ExpirationArgs { expiration: NaiveDate::from_ymd_opt(2025, 6, 20), dte: None, all_expirations: false };
+        let expirations: Vec<NaiveDate> = select_expirations(&provider, "AAPL", true, &args).await.unwrap();
+        assert_eq!(expirations, vec![NaiveDate::from_ymd_opt(2025, 6, 20).unwrap()]);
 
+        let data: OptionData = fetch_american_option_data(&provider, "AAPL", 201.0, expirations[0], true, DayCount::Act365Fixed, None, false).await.unwrap();
 
-    // Get spot price
-    let quote_url = format!("https://api.marketdata.app/v1/stocks/quotes/{}", symbol);
-    let quote_json: serde_json::Value = client.get(&quote_url).send().await?.json().await?;
-    let wrapped_mid = quote_json["last"]
-        .as_array()
-        .ok_or("Missing spot/last price")?;
-
-    let mid_price: f64 = (wrapped_mid[0 as usize]).as_f64().unwrap();
-
-
-
-    // Get the years to expiration of the option
-    let today = Utc::now().date_naive();
-    let days_until = (expiration - today).num_days();
-    let expiry: f64 = (days_until as f64) / 365.0;
-
-
-    // Select the US treasury risk free interest rate with the maturity time closest matching the expiration of the option
-    let mut rates: Vec<(f64, String)> = Vec::new();
-    rates.push((0.125, "GS1M".to_string()));
-    rates.push((0.25, "GS3M".to_string()));
-    rates.push((0.5, "GS6M".to_string()));
-    rates.push((1.0, "GS1".to_string()));
-    rates.push((2.0, "GS2".to_string()));
-    rates.push((3.0, "GS3".to_string()));
-    rates.push((5.0, "GS5".to_string()));
-    rates.push((7.0, "GS7".to_string()));
-    rates.push((10.0, "GS10".to_string()));
-    rates.push((20.0, "GS20".to_string()));
-    rates.push((30.0, "GS30".to_string()));
-
-    let mut closest_rate = &rates[0 as usize];
-
-    for i in 1..rates.len() {
-        if ((rates[i as usize].0) - expiry).abs() < (closest_rate.0 - expiry).abs() {
-            closest_rate = &rates[i as usize];
-        }
+        // Nearest listed strike, and 18 days and 2 hours from 14:00 New York time to the 16:00 close on expiry
+        assert_eq!(data.strike, 200.0);
+        assert_eq!(data.option_symbol, "AAPL250620C00200000");
+        assert_eq!(data.spot, 201.7);
+        assert!((data.expiration - (18.0 + 2.0 / 24.0) / 365.0).abs() < 1e-9);
+        assert!((data.rate - 0.043521).abs() < 1e-6);
     }
 
-    // Pull the according risk free rate from US Treasury via FRED API
-    let fred_url = format!("https://api.stlouisfed.org/fred/series/observations?series_id={}&api_key=730334457025754885efeced5149e476&file_type=json&sort_order=desc&limit=1", closest_rate.1);
-    let fred_json: serde_json::Value = client.get(&fred_url).send().await?.json().await?;
-    let wrapped_rate = fred_json["observations"]
-        .as_array()
-        .ok_or("Missing spot/mid price")?;
+    #[tokio::test]
+    async fn unlisted_expiration_is_rejected() {
+        let args = ExpirationArgs { expiration: NaiveDate::from_ymd_opt(2025, 6, 13), dte: None, all_expirations: false };
+        assert!(select_expirations(&fixtures(), "AAPL", true, &args).await.is_err());
+    }
 
-    let rate_str = wrapped_rate.first().unwrap()["value"].as_str().unwrap();
-    let rate: f64 = rate_str.parse().unwrap();
+    #[test]
+    fn same_day_expiry() {
+        let expiration: NaiveDate = NaiveDate::from_ymd_opt(2025, 6, 20).unwrap();
 
-    // Return the wrapped data
-    Ok(OptionData {
-        symbol: symbol.to_string(),
-        spot: mid_price,
-        strike: option_strike,
-        expiration: expiry,
-        volatility: option_iv,
-        rate: rate / 100.0,
-    })
-}
+        // An hour before the 16:00 close, in calendar years and in sessions
+        let (expiry, calendar_years) = time_to_expiry(&clock("2025-06-20T19:00:00Z"), "AAPL250620C00200000", expiration, DayCount::Trading252).unwrap();
+        assert!((calendar_years - 1.0 / 24.0 / 365.0).abs() < 1e-12);
+        assert!((expiry - 1.0 / 6.5 / 252.0).abs() < 1e-12);
 
+        // Gone once the bell rings
+        assert!(time_to_expiry(&clock("2025-06-20T20:00:00Z"), "AAPL250620C00200000", expiration, DayCount::Act365Fixed).is_err());
+        assert!(time_to_expiry(&clock("2025-06-20T20:30:00Z"), "AAPL250620C00200000", expiration, DayCount::Act365Fixed).is_err());
+
+        // The AM settled monthly stopped at the open, the PM settled weekly on the same date has until the close
+        assert!(time_to_expiry(&clock("2025-06-20T14:00:00Z"), "SPX250620C06000000", expiration, DayCount::Act365Fixed).is_err());
+        let (_, weekly) = time_to_expiry(&clock("2025-06-20T14:00:00Z"), "SPXW250620C06000000", expiration, DayCount::Act365Fixed).unwrap();
+        assert!((weekly - 6.0 / 24.0 / 365.0).abs() < 1e-12);
+    }
+}
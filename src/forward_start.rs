@@ -0,0 +1,63 @@
+/*
+
+JDA Options Pricing
+/src/forward_start.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Modules
+use crate::black_scholes::generalized_black_scholes;
+use crate::monte_carlo::{MonteCarloEstimate, monte_carlo};
+
+
+
+// Price a forward start option, whose strike is set to moneyness times the stock price at start_time (Rubinstein)
+pub fn forward_start(spot: f64, moneyness: f64, start_time: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = rate;
+
+    // The option is homogeneous in the stock price, so price one on a unit stock and scale by the forward value of the stock at the start
+    return spot * ((carry - rate) * start_time).exp() * generalized_black_scholes(1.0, moneyness, time - start_time, rate, carry, volatility, call);
+}
+
+
+
+// Price a cliquet as a strip of forward start options over equal reset periods from start_time to expiry
+    // Each period's payoff is paid at the end of that period
+pub fn cliquet(spot: f64, moneyness: f64, start_time: f64, time: f64, resets: u32, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    let period: f64 = (time - start_time) / (resets as f64);
+
+    return (0..resets)
+        .map(|i| forward_start(spot, moneyness, start_time + period * (i as f64), start_time + period * ((i + 1) as f64), rate, volatility, call))
+        .sum();
+}
+
+
+
+// Price the same cliquet (a single period is a forward start option) by simulating the stock at each reset date
+    // Each period's payoff is rolled forward to expiry so the whole path can be discounted once
+pub fn monte_carlo_cliquet(spot: f64, moneyness: f64, start_time: f64, time: f64, resets: u32, rate: f64, volatility: f64, paths: u32, seed: Option<u64>, call: bool) -> MonteCarloEstimate {
+
+    let period: f64 = (time - start_time) / (resets as f64);
+    let mut times: Vec<f64> = (0..=resets).map(|i| start_time + period * (i as f64)).collect();
+
+    // A strike set today is already known, so there is nothing to simulate at time zero
+    let starts_today: bool = start_time <= 0.0;
+    if starts_today { times.remove(0); }
+
+    return monte_carlo(spot, rate, rate, volatility, &times, time, paths, seed, |path| {
+
+        let fixings: Vec<f64> = if starts_today { std::iter::once(spot).chain(path.iter().copied()).collect() } else { path.to_vec() };
+
+        (1..fixings.len()).map(|i| {
+            let strike: f64 = moneyness * fixings[i - 1];
+            let payoff: f64 = if call { (fixings[i] - strike).max(0.0) } else { (strike - fixings[i]).max(0.0) };
+            payoff * (rate * (time - (start_time + period * (i as f64)))).exp()
+        }).sum::<f64>()
+    });
+}
@@ -0,0 +1,210 @@
+/*
+
+JDA Options Pricing
+/src/garch.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use std::f64::consts::PI;
+use std::fmt;
+
+// Modules
+use crate::historical::TRADING_DAYS;
+use crate::solver::nelder_mead;
+
+
+
+// Conditional variance models for daily returns
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum GarchModel {
+    // h = omega + alpha * e^2 + beta * h
+    Garch,
+    // GARCH with an extra gamma * e^2 after falls (Glosten-Jagannathan-Runkle)
+    Gjr,
+    // ln h = omega + alpha * (|z| - E|z|) + gamma * z + beta * ln h (Nelson's exponential GARCH)
+    Egarch,
+}
+
+impl fmt::Display for GarchModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            GarchModel::Garch => "GARCH(1,1)",
+            GarchModel::Gjr => "GJR-GARCH(1,1)",
+            GarchModel::Egarch => "EGARCH(1,1)",
+        })
+    }
+}
+
+
+
+// A fitted model, gamma is zero for plain GARCH
+#[derive(Debug, Clone, Copy)]
+pub struct Garch {
+    pub model: GarchModel,
+    pub omega: f64,
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    // Conditional variance of the next day's return
+    pub next_variance: f64,
+    pub log_likelihood: f64,
+}
+
+impl Garch {
+
+    // Fit by Gaussian maximum likelihood to daily log returns, which are demeaned first
+    pub fn fit(returns: &[f64], model: GarchModel) -> Result<Garch, String> {
+
+        if returns.len() < 30 {
+            return Err(format!("a GARCH fit needs at least 30 returns, only {} available", returns.len()));
+        }
+
+        let mean: f64 = returns.iter().sum::<f64>() / (returns.len() as f64);
+        let residuals: Vec<f64> = returns.iter().map(|r| r - mean).collect();
+        let variance: f64 = residuals.iter().map(|e| e * e).sum::<f64>() / (residuals.len() as f64);
+
+        if variance <= 0.0 {
+            return Err("the returns have no variance".to_string());
+        }
+
+        // Omega is searched relative to the sample variance (or its log for EGARCH) so every parameter is of order one
+        let unscale = |x: &[f64]| -> Garch {
+            let (omega, alpha, beta, gamma) = match model {
+                GarchModel::Garch => (x[0] * variance, x[1], x[2], 0.0),
+                GarchModel::Gjr => (x[0] * variance, x[1], x[2], x[3]),
+                GarchModel::Egarch => (x[0] * variance.ln(), x[1], x[2], x[3]),
+            };
+            Garch { model, omega, alpha, beta, gamma, next_variance: variance, log_likelihood: 0.0 }
+        };
+
+        let objective = |x: &[f64]| -> f64 {
+            let garch: Garch = unscale(x);
+            if !garch.valid() { return 1e10; }
+            match garch.filter(&residuals, variance) {
+                Some((log_likelihood, _)) => -1.0 * log_likelihood,
+                None => 1e10,
+            }
+        };
+
+        let (start, step): (Vec<f64>, Vec<f64>) = match model {
+            GarchModel::Garch => (vec![0.05, 0.08, 0.9], vec![0.03, 0.04, 0.04]),
+            GarchModel::Gjr => (vec![0.05, 0.04, 0.9, 0.08], vec![0.03, 0.02, 0.04, 0.04]),
+            GarchModel::Egarch => (vec![0.05, 0.1, 0.95, -0.05], vec![0.03, 0.05, 0.03, 0.05]),
+        };
+
+        // Restart from the best point to get out of any collapsed simplex
+        let (mut x, mut value) = nelder_mead(objective, &start, &step, 1e-10, 4000);
+        for _ in 0..3 {
+            let (next, next_value) = nelder_mead(objective, &x, &step, 1e-10, 4000);
+            if value - next_value < 1e-8 { break; }
+            (x, value) = (next, next_value);
+        }
+
+        if value >= 1e10 {
+            return Err(format!("unable to fit {} to the returns", model));
+        }
+
+        let mut garch: Garch = unscale(&x);
+        let (log_likelihood, next_variance) = garch.filter(&residuals, variance).ok_or(format!("unable to fit {} to the returns", model))?;
+        garch.log_likelihood = log_likelihood;
+        garch.next_variance = next_variance;
+
+        return Ok(garch);
+    }
+
+    // Stationarity and positivity constraints
+    fn valid(&self) -> bool {
+        match self.model {
+            GarchModel::Garch | GarchModel::Gjr => self.omega > 0.0 && self.alpha >= 0.0 && self.beta >= 0.0 && self.alpha + self.gamma >= 0.0 && self.persistence() < 1.0,
+            GarchModel::Egarch => self.beta.abs() < 1.0,
+        }
+    }
+
+    // Run the variance recursion over the residuals from a starting variance, returning the log likelihood and the next day's variance
+    fn filter(&self, residuals: &[f64], start: f64) -> Option<(f64, f64)> {
+
+        let mut h: f64 = start;
+        let mut log_likelihood: f64 = 0.0;
+
+        for e in residuals {
+            if !(h > 0.0 && h.is_finite()) { return None; }
+            log_likelihood -= 0.5 * ((2.0 * PI).ln() + h.ln() + e * e / h);
+            h = self.next(h, *e);
+        }
+
+        if !(h > 0.0 && h.is_finite()) { return None; }
+        return Some((log_likelihood, h));
+    }
+
+    // Variance after a day with conditional variance h and residual e
+    fn next(&self, h: f64, e: f64) -> f64 {
+        match self.model {
+            GarchModel::Garch => self.omega + self.alpha * e * e + self.beta * h,
+            GarchModel::Gjr => self.omega + (self.alpha + if e < 0.0 { self.gamma } else { 0.0 }) * e * e + self.beta * h,
+            GarchModel::Egarch => {
+                let z: f64 = e / h.sqrt();
+                (self.omega + self.alpha * (z.abs() - (2.0 / PI).sqrt()) + self.gamma * z + self.beta * h.ln()).exp()
+            }
+        }
+    }
+
+    // How much of a variance shock is left the next day (beta for EGARCH, whose persistence is in log variance)
+    pub fn persistence(&self) -> f64 {
+        match self.model {
+            GarchModel::Garch => self.alpha + self.beta,
+            GarchModel::Gjr => self.alpha + 0.5 * self.gamma + self.beta,
+            GarchModel::Egarch => self.beta,
+        }
+    }
+
+    // Days for a variance shock to halve
+    pub fn half_life(&self) -> f64 {
+        return 0.5_f64.ln() / self.persistence().ln();
+    }
+
+    // Daily variance forecasts revert to
+    pub fn long_run_variance(&self) -> f64 {
+        match self.model {
+            GarchModel::Egarch => (self.omega / (1.0 - self.beta)).exp(),
+            _ => self.omega / (1.0 - self.persistence()),
+        }
+    }
+
+    // Expected daily variance of each of the next days
+        // EGARCH forecasts the log variance, so its variance forecasts are exp of the expected log variance
+    pub fn forecast(&self, days: usize) -> Vec<f64> {
+
+        let long_run: f64 = self.long_run_variance();
+        let mut forecasts: Vec<f64> = Vec::with_capacity(days);
+
+        for k in 0..days {
+            forecasts.push(match self.model {
+                GarchModel::Egarch => (long_run.ln() + self.beta.powi(k as i32) * (self.next_variance.ln() - long_run.ln())).exp(),
+                _ => long_run + self.persistence().powi(k as i32) * (self.next_variance - long_run),
+            });
+        }
+
+        return forecasts;
+    }
+
+    // Annualised volatility from the average forecast variance over the next days (part days count pro rata)
+    pub fn term_volatility(&self, days: f64) -> f64 {
+
+        let whole: usize = days.ceil().max(1.0) as usize;
+        let forecasts: Vec<f64> = self.forecast(whole);
+        let last_weight: f64 = days - ((whole - 1) as f64);
+
+        let total: f64 = if days < 1.0 {
+            forecasts[0] * days
+        } else {
+            forecasts[..whole - 1].iter().sum::<f64>() + forecasts[whole - 1] * last_weight
+        };
+
+        return (total / days.max(1e-12) * TRADING_DAYS).sqrt();
+    }
+}
@@ -79,3 +79,32 @@ pub fn calculate_greeks(spot: f64, strike: f64, time: f64, rate: f64, volatility
     })
 }
 
+
+
+
+// Estimate the five greeks of any pricer by finite differences
+    // The pricer takes (spot, time, rate, volatility) and must return an unrounded price
+    // Theta is the change in value as a day passes, expressed per year like the closed form
+pub fn numerical_greeks<F>(spot: f64, time: f64, rate: f64, volatility: f64, price: F) -> Greeks
+where
+    F: Fn(f64, f64, f64, f64) -> f64,
+{
+
+    // Bump sizes
+    let ds: f64 = spot * 1e-3;
+    let dv: f64 = 1e-3;
+    let dr: f64 = 1e-4;
+    let dt: f64 = (1.0 / 365.0_f64).min(0.5 * time);
+
+    let value: f64 = price(spot, time, rate, volatility);
+    let up: f64 = price(spot + ds, time, rate, volatility);
+    let down: f64 = price(spot - ds, time, rate, volatility);
+
+    Greeks {
+        delta: (up - down) / (2.0 * ds),
+        gamma: (up - 2.0 * value + down) / ds.powi(2),
+        vega: (price(spot, time, rate, volatility + dv) - price(spot, time, rate, volatility - dv)) / (2.0 * dv),
+        theta: (price(spot, time - dt, rate, volatility) - value) / dt,
+        rho: (price(spot, time, rate + dr, volatility) - price(spot, time, rate - dr, volatility)) / (2.0 * dr),
+    }
+}
@@ -0,0 +1,253 @@
+/*
+
+JDA Options Pricing
+/src/historical.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use chrono::NaiveDate;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+// Modules
+use crate::garch::{Garch, GarchModel};
+use crate::provider::Bar;
+
+
+
+// Trading days in a year, for annualising daily variance
+pub const TRADING_DAYS: f64 = 252.0;
+
+
+
+// Realised volatility estimators
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Estimator {
+    // Sample standard deviation of close to close log returns
+    CloseToClose,
+    // High to low range, assumes no drift and no overnight gaps
+    Parkinson,
+    // Open, high, low and close, assumes no drift and no overnight gaps
+    GarmanKlass,
+    // Open, high, low and close, allows drift but not overnight gaps
+    RogersSatchell,
+    // Overnight, open to close and Rogers-Satchell variances combined, allows drift and overnight gaps
+    YangZhang,
+}
+
+impl Estimator {
+
+    // Bars needed for a window of n days, the estimators that use the previous close need one more
+    pub fn bars(&self, window: usize) -> usize {
+        match self {
+            Estimator::CloseToClose | Estimator::YangZhang => window + 1,
+            _ => window,
+        }
+    }
+}
+
+impl fmt::Display for Estimator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Estimator::CloseToClose => "Close-to-Close",
+            Estimator::Parkinson => "Parkinson",
+            Estimator::GarmanKlass => "Garman-Klass",
+            Estimator::RogersSatchell => "Rogers-Satchell",
+            Estimator::YangZhang => "Yang-Zhang",
+        })
+    }
+}
+
+
+
+// Annualised realised volatility over the last window days of bars
+pub fn realized_volatility(bars: &[Bar], window: usize, estimator: Estimator) -> Result<f64, String> {
+
+    if window < 2 {
+        return Err("the window must be at least two days".to_string());
+    }
+    if bars.len() < estimator.bars(window) {
+        return Err(format!("{} over {} days needs {} bars, only {} available", estimator, window, estimator.bars(window), bars.len()));
+    }
+    if bars.iter().any(|bar| bar.open <= 0.0 || bar.high <= 0.0 || bar.low <= 0.0 || bar.close <= 0.0 || bar.high < bar.low) {
+        return Err("bars must have positive prices with the high at or above the low".to_string());
+    }
+
+    let recent: &[Bar] = &bars[bars.len() - window..];
+    let n: f64 = window as f64;
+
+    // Each bar with the previous one
+    let pairs: Vec<(&Bar, &Bar)> = bars[bars.len() - estimator.bars(window)..].windows(2).map(|pair| (&pair[0], &pair[1])).collect();
+
+    let rogers_satchell = |bar: &Bar| (bar.high / bar.close).ln() * (bar.high / bar.open).ln() + (bar.low / bar.close).ln() * (bar.low / bar.open).ln();
+
+    let variance: f64 = match estimator {
+        Estimator::CloseToClose => {
+            let returns: Vec<f64> = pairs.iter().map(|(previous, bar)| (bar.close / previous.close).ln()).collect();
+            sample_variance(&returns)
+        }
+        Estimator::Parkinson => {
+            recent.iter().map(|bar| (bar.high / bar.low).ln().powi(2)).sum::<f64>() / (4.0 * n * 2.0_f64.ln())
+        }
+        Estimator::GarmanKlass => {
+            recent.iter().map(|bar| 0.5 * (bar.high / bar.low).ln().powi(2) - (2.0 * 2.0_f64.ln() - 1.0) * (bar.close / bar.open).ln().powi(2)).sum::<f64>() / n
+        }
+        Estimator::RogersSatchell => {
+            recent.iter().map(rogers_satchell).sum::<f64>() / n
+        }
+        Estimator::YangZhang => {
+            let overnight: Vec<f64> = pairs.iter().map(|(previous, bar)| (bar.open / previous.close).ln()).collect();
+            let intraday: Vec<f64> = recent.iter().map(|bar| (bar.close / bar.open).ln()).collect();
+            let k: f64 = 0.34 / (1.34 + (n + 1.0) / (n - 1.0));
+            sample_variance(&overnight) + k * sample_variance(&intraday) + (1.0 - k) * recent.iter().map(rogers_satchell).sum::<f64>() / n
+        }
+    };
+
+    return Ok((variance.max(0.0) * TRADING_DAYS).sqrt());
+}
+
+
+
+// Close to close log returns, oldest first
+pub fn log_returns(bars: &[Bar]) -> Vec<f64> {
+    return bars.windows(2).map(|pair| (pair[1].close / pair[0].close).ln()).collect();
+}
+
+
+
+fn sample_variance(values: &[f64]) -> f64 {
+    let mean: f64 = values.iter().sum::<f64>() / (values.len() as f64);
+    return values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / ((values.len() - 1) as f64);
+}
+
+
+
+// Read daily bars from a CSV of date,open,high,low,close (any further columns such as volume are ignored), oldest or newest first
+pub fn read_bars(path: &str) -> Result<Vec<Bar>, String> {
+
+    let text: String = fs::read_to_string(path).map_err(|e| format!("unable to read {}, {}", path, e))?;
+    let mut bars: Vec<Bar> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+
+        let line: &str = line.trim();
+        if line.is_empty() { continue; }
+
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+        let date = fields.first().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        let prices: Result<Vec<f64>, _> = fields.iter().skip(1).take(4).map(|field| field.parse::<f64>()).collect();
+
+        match (date, prices) {
+            (Some(date), Ok(prices)) if prices.len() == 4 => bars.push(Bar { date, open: prices[0], high: prices[1], low: prices[2], close: prices[3] }),
+            _ if number == 0 => continue,
+            _ => return Err(format!("line {} of {} is not date,open,high,low,close", number + 1, path)),
+        }
+    }
+
+    bars.sort_by_key(|bar| bar.date);
+    return Ok(bars);
+}
+
+
+
+// A volatility given directly, a realised volatility to compute such as hv:30 or hv:30:parkinson (Yang-Zhang by default),
+// or a GARCH forecast to the option's expiry such as garch, garch:gjr or garch:egarch:750 (GARCH(1,1) on 500 days by default)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolatilityInput {
+    Fixed(f64),
+    Historical { window: usize, estimator: Estimator },
+    Forecast { model: GarchModel, history: usize },
+}
+
+impl VolatilityInput {
+
+    // Daily bars needed to compute it
+    pub fn bars(&self) -> usize {
+        match self {
+            VolatilityInput::Fixed(_) => 0,
+            VolatilityInput::Historical { window, estimator } => estimator.bars(*window),
+            VolatilityInput::Forecast { history, .. } => history + 1,
+        }
+    }
+
+    // Compute it from the bars, ready to read off at any expiry
+    pub fn estimate(&self, bars: &[Bar]) -> Result<VolatilityEstimate, String> {
+        match self {
+            VolatilityInput::Fixed(volatility) => Ok(VolatilityEstimate::Flat(*volatility)),
+            VolatilityInput::Historical { window, estimator } => Ok(VolatilityEstimate::Flat(realized_volatility(bars, *window, *estimator)?)),
+            VolatilityInput::Forecast { model, history } => {
+                let start: usize = bars.len().saturating_sub(history + 1);
+                Ok(VolatilityEstimate::Forecast(Garch::fit(&log_returns(&bars[start..]), *model)?))
+            }
+        }
+    }
+}
+
+impl fmt::Display for VolatilityInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VolatilityInput::Fixed(volatility) => write!(f, "{}", volatility),
+            VolatilityInput::Historical { window, estimator } => write!(f, "{} over {} days", estimator, window),
+            VolatilityInput::Forecast { model, history } => write!(f, "{} forecast from {} days", model, history),
+        }
+    }
+}
+
+impl FromStr for VolatilityInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<VolatilityInput, String> {
+
+        if let Some(rest) = s.strip_prefix("garch") {
+            let mut parts = rest.split(':').skip(1);
+            let model: GarchModel = match parts.next() {
+                Some(model) => GarchModel::from_str(model, true).map_err(|_| format!("unknown GARCH model {}", model))?,
+                None if rest.is_empty() => GarchModel::Garch,
+                None => return Err(format!("{} is not garch[:<model>[:<days>]]", s)),
+            };
+            let history: usize = match parts.next() {
+                Some(days) => days.parse().map_err(|_| format!("{} is not a number of days", days))?,
+                None => 500,
+            };
+            return Ok(VolatilityInput::Forecast { model, history });
+        }
+
+        let Some(rest) = s.strip_prefix("hv:") else {
+            return s.parse::<f64>().map(VolatilityInput::Fixed).map_err(|_| format!("{} is not a volatility, hv:<days>[:<estimator>] or garch[:<model>[:<days>]]", s));
+        };
+
+        let (window, estimator) = match rest.split_once(':') {
+            Some((window, estimator)) => (window, Estimator::from_str(estimator, true).map_err(|_| format!("unknown estimator {}", estimator))?),
+            None => (rest, Estimator::YangZhang),
+        };
+        let window: usize = window.parse().map_err(|_| format!("{} is not a number of days", window))?;
+
+        return Ok(VolatilityInput::Historical { window, estimator });
+    }
+}
+
+
+
+// A volatility ready to price with, flat or following a GARCH term structure
+#[derive(Debug, Clone, Copy)]
+pub enum VolatilityEstimate {
+    Flat(f64),
+    Forecast(Garch),
+}
+
+impl VolatilityEstimate {
+
+    // Annualised volatility to an expiry time years away, counted in trading days for forecasts
+    pub fn volatility(&self, time: f64) -> f64 {
+        match self {
+            VolatilityEstimate::Flat(volatility) => *volatility,
+            VolatilityEstimate::Forecast(garch) => garch.term_volatility(time * TRADING_DAYS),
+        }
+    }
+}
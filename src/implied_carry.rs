@@ -0,0 +1,103 @@
+/*
+
+JDA Options Pricing
+/src/implied_carry.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Modules
+use crate::provider::ChainQuote;
+
+
+
+// Strikes further than this from the spot in log-moneyness are left out, deep in-the-money American options carry early exercise premium that breaks parity
+const MONEYNESS_BAND: f64 = 0.2;
+
+// Largest standard error on the implied rate to trust it, beyond this only the forward is backed out at the curve's discount factor
+    // Short expiries divide small errors in C - P by a small time, so a few cents of noise can move the rate by hundreds of basis points
+const MAX_RATE_ERROR: f64 = 0.005;
+
+
+
+// Forward and rates an expiry's calls and puts imply through put-call parity
+#[derive(Debug, Clone, Copy)]
+pub struct ImpliedCarry {
+    pub forward: f64,
+    pub discount: f64,
+    // Continuously compounded discount rate
+    pub rate: f64,
+    // Dividend yield plus borrow cost, the rate minus the cost of carry
+    pub dividend: f64,
+    // Cost of carry ln(F / S) / T, the drift to price with
+    pub carry: f64,
+    // Strikes in the regression and the root mean square error of its fitted call minus put
+    pub strikes: usize,
+    pub fit_error: f64,
+    // Standard error of the implied rate, from the regression slope's
+    pub rate_error: f64,
+    // Whether the implied rate was too noisy, so the discount factor is the curve's and only the forward is implied
+    pub from_curve: bool,
+}
+
+
+
+// Regress call minus put mids against the strike, C - P = D * F - D * K, so the slope is minus the discount factor and the intercept over it is the forward
+    // Falls back to the discount factor off the curve when the slope is too uncertain to give a usable rate
+pub fn implied_carry(calls: &[ChainQuote], puts: &[ChainQuote], spot: f64, time: f64, curve_discount: f64) -> Result<ImpliedCarry, String> {
+
+    if spot <= 0.0 || time <= 0.0 || curve_discount <= 0.0 {
+        return Err("the spot, time and curve discount factor must be positive".to_string());
+    }
+
+    let pairs = |band: f64| -> Vec<(f64, f64)> {
+        calls.iter()
+            .filter(|call| (call.strike / spot).ln().abs() <= band)
+            .filter_map(|call| {
+                let put = puts.iter().find(|put| put.strike == call.strike)?;
+                Some((call.strike, call.mid_price()? - put.mid_price()?))
+            })
+            .collect()
+    };
+
+    // Fall back to every strike when too few are near the money
+    let mut points: Vec<(f64, f64)> = pairs(MONEYNESS_BAND);
+    if points.len() < 2 {
+        points = pairs(f64::INFINITY);
+    }
+    if points.len() < 2 {
+        return Err("put-call parity needs calls and puts quoted at two or more strikes".to_string());
+    }
+
+    // Ordinary least squares
+    let n: f64 = points.len() as f64;
+    let mean_strike: f64 = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_difference: f64 = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(k, y)| (k - mean_strike) * (y - mean_difference)).sum();
+    let variance: f64 = points.iter().map(|(k, _)| (k - mean_strike).powi(2)).sum();
+
+    let slope: f64 = covariance / variance;
+    let intercept: f64 = mean_difference - slope * mean_strike;
+    let squared_errors: f64 = points.iter().map(|(k, y)| (intercept + slope * k - y).powi(2)).sum();
+
+    // Standard error of the slope carried through to the rate by r = -ln(D) / T, unknown with only two strikes
+    let slope_error: f64 = if points.len() > 2 { (squared_errors / (n - 2.0) / variance).sqrt() } else { f64::INFINITY };
+    let rate_error: f64 = slope_error / (slope.abs() * time);
+
+    // Too noisy to trust, so take the curve's discount factor and fit only the forward, F = K + (C - P) / D on average
+    let from_curve: bool = slope >= 0.0 || rate_error.is_nan() || rate_error > MAX_RATE_ERROR;
+    let discount: f64 = if from_curve { curve_discount } else { -1.0 * slope };
+    let forward: f64 = if from_curve { mean_strike + mean_difference / discount } else { intercept / discount };
+
+    if discount <= 0.0 || forward <= 0.0 {
+        return Err(format!("the quotes imply a discount factor of {:.4} and no positive forward", discount));
+    }
+
+    let rate: f64 = -1.0 * discount.ln() / time;
+    let carry: f64 = (forward / spot).ln() / time;
+    let fit_error: f64 = (points.iter().map(|(k, y)| (discount * (forward - k) - y).powi(2)).sum::<f64>() / n).sqrt();
+
+    return Ok(ImpliedCarry { forward, discount, rate, dividend: rate - carry, carry, strikes: points.len(), fit_error, rate_error, from_curve });
+}
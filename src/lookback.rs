@@ -0,0 +1,106 @@
+/*
+
+JDA Options Pricing
+/src/lookback.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::black_scholes::{d1, d2};
+
+
+
+// The lookback formulas divide by the cost of carry, so nudge it away from zero
+fn carry_of(rate: f64) -> f64 {
+    return if rate.abs() < 1e-8 { 1e-8 } else { rate };
+}
+
+
+
+// Price a floating strike lookback option (Goldman-Sosin-Gatto)
+    // A call pays the final price less the lowest price seen, a put the highest price seen less the final price
+    // The running minimum/maximum is the extreme observed so far (the spot for a new option)
+pub fn floating_lookback(spot: f64, running_extreme: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    // Standard normal distribution
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = carry_of(rate);
+
+    let discount: f64 = (-1.0 * rate * time).exp();
+    let carry_discount: f64 = ((carry - rate) * time).exp();
+    let reflection: f64 = volatility.powi(2) / (2.0 * carry);
+
+    // d1/d2 taken against the running extreme in place of a strike
+    let a1: f64 = d1(spot, running_extreme, time, carry, volatility);
+    let a2: f64 = d2(spot, running_extreme, time, carry, volatility);
+
+    if call {
+        return spot * carry_discount * n(a1) - running_extreme * discount * n(a2)
+            + spot * discount * reflection * ((spot / running_extreme).powf(-2.0 * carry / volatility.powi(2)) * n(-1.0 * a1 + 2.0 * carry / volatility * time.sqrt()) - (carry * time).exp() * n(-1.0 * a1));
+    } else {
+        return running_extreme * discount * n(-1.0 * a2) - spot * carry_discount * n(-1.0 * a1)
+            + spot * discount * reflection * (-1.0 * (spot / running_extreme).powf(-2.0 * carry / volatility.powi(2)) * n(a1 - 2.0 * carry / volatility * time.sqrt()) + (carry * time).exp() * n(a1));
+    }
+}
+
+
+
+// Price a fixed strike lookback option (Conze-Viswanathan)
+    // A call pays the highest price seen less the strike, a put the strike less the lowest price seen
+    // The running maximum (call) or minimum (put) is the extreme observed so far (the spot for a new option)
+pub fn fixed_lookback(spot: f64, strike: f64, running_extreme: f64, time: f64, rate: f64, volatility: f64, call: bool) -> f64 {
+
+    // Standard normal distribution
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+
+    // No dividends, so the cost of carry is the risk free rate
+    let carry: f64 = carry_of(rate);
+
+    let discount: f64 = (-1.0 * rate * time).exp();
+    let carry_discount: f64 = ((carry - rate) * time).exp();
+    let reflection: f64 = volatility.powi(2) / (2.0 * carry);
+    let shift: f64 = 2.0 * carry / volatility * time.sqrt();
+
+    if call {
+
+        // Out of the money so far, the strike is the level to beat
+        if strike > running_extreme {
+            let d1: f64 = d1(spot, strike, time, carry, volatility);
+            let d2: f64 = d2(spot, strike, time, carry, volatility);
+            return spot * carry_discount * n(d1) - strike * discount * n(d2)
+                + spot * discount * reflection * (-1.0 * (spot / strike).powf(-2.0 * carry / volatility.powi(2)) * n(d1 - shift) + (carry * time).exp() * n(d1));
+        }
+
+        // Already in the money, lock in the intrinsic value and own a lookback on any new high
+        let e1: f64 = d1(spot, running_extreme, time, carry, volatility);
+        let e2: f64 = d2(spot, running_extreme, time, carry, volatility);
+        return discount * (running_extreme - strike) + spot * carry_discount * n(e1) - running_extreme * discount * n(e2)
+            + spot * discount * reflection * (-1.0 * (spot / running_extreme).powf(-2.0 * carry / volatility.powi(2)) * n(e1 - shift) + (carry * time).exp() * n(e1));
+
+    } else {
+
+        // Out of the money so far, the strike is the level to beat
+        if strike < running_extreme {
+            let d1: f64 = d1(spot, strike, time, carry, volatility);
+            let d2: f64 = d2(spot, strike, time, carry, volatility);
+            return strike * discount * n(-1.0 * d2) - spot * carry_discount * n(-1.0 * d1)
+                + spot * discount * reflection * ((spot / strike).powf(-2.0 * carry / volatility.powi(2)) * n(-1.0 * d1 + shift) - (carry * time).exp() * n(-1.0 * d1));
+        }
+
+        // Already in the money, lock in the intrinsic value and own a lookback on any new low
+        let f1: f64 = d1(spot, running_extreme, time, carry, volatility);
+        let f2: f64 = d2(spot, running_extreme, time, carry, volatility);
+        return discount * (strike - running_extreme) - spot * carry_discount * n(-1.0 * f1) + running_extreme * discount * n(-1.0 * f2)
+            + spot * discount * reflection * ((spot / running_extreme).powf(-2.0 * carry / volatility.powi(2)) * n(-1.0 * f1 + shift) - (carry * time).exp() * n(-1.0 * f1));
+    }
+}
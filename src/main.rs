@@ -14,6 +14,40 @@ mod fetch;
 mod greeks;
 mod binomial;
 mod black_scholes;
+mod barrier;
+mod asian;
+mod monte_carlo;
+mod lookback;
+mod digital;
+mod bivariate;
+mod solver;
+mod compound;
+mod chooser;
+mod forward_start;
+mod two_asset;
+mod basket;
+mod quanto;
+mod payoff;
+mod bond;
+mod short_rate;
+mod trinomial;
+mod vol_cube;
+mod rate_options;
+mod yield_curve;
+mod day_count;
+mod calendar;
+mod provider;
+mod config;
+mod expiration;
+mod svi;
+mod smile;
+mod vol_surface;
+mod arbitrage;
+mod implied_carry;
+mod historical;
+mod garch;
+mod density;
+mod variance;
 
 // Packages
 use clap::{Parser, Subcommand};
@@ -29,7 +63,6 @@ use clap::{Parser, Subcommand};
     version = "0.4",
     arg_required_else_help = true
 )]
-
 // Struct to dispatch commands
 struct Cli {
     #[command(subcommand)]
@@ -60,6 +93,34 @@ enum Commands {
 
         #[arg(short = 'g', long = "greeks", default_value_t = false)]
         greeks: bool,
+
+        #[arg(long = "day-count", value_enum, default_value_t = day_count::DayCount::Act365Fixed)]
+        day_count: day_count::DayCount,
+
+        // Keep the exact strike and interpolate its vol across the smile instead of snapping to the nearest listed strike
+        #[arg(long = "smile", value_enum)]
+        smile: Option<smile::SmileMethod>,
+
+        #[arg(long = "smile-axis", value_enum, default_value_t = smile::SmileAxis::LogMoneyness)]
+        smile_axis: smile::SmileAxis,
+
+        // Price at the forward, discount rate and dividend or borrow rate implied by put-call parity instead of the curve rate with no dividend
+        #[arg(long = "implied-carry")]
+        implied_carry: bool,
+
+        // Price at this volatility, hv:<days>[:<estimator>] for the realised volatility of the symbol or garch[:<model>[:<days>]] for a forecast to expiry, instead of the market's implied vol
+        #[arg(long = "volatility", conflicts_with = "smile")]
+        volatility: Option<historical::VolatilityInput>,
+
+        // CSV of date,open,high,low,close to compute the historical or forecast volatility from instead of the data provider
+        #[arg(long = "bars")]
+        bars: Option<String>,
+
+        #[command(flatten)]
+        expiration: expiration::ExpirationArgs,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
     },
 
     // Price options using manually inputted data
@@ -75,12 +136,82 @@ enum Commands {
         #[arg(short = 't', long = "time", required = true)]
         time: f64,
 
-        #[arg(short = 'r', long = "rate", required = true)]
-        rate: f64,
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        // A volatility, hv:<days>[:<estimator>] for the realised volatility of the bars in --bars or garch[:<model>[:<days>]] for a forecast to expiry
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: historical::VolatilityInput,
+
+        // CSV of date,open,high,low,close for a historical or forecast volatility
+        #[arg(long = "bars")]
+        bars: Option<String>,
+
+        #[arg(short = 'n', long = "steps", default_value_t = 100)]
+        steps: u32,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
+        #[arg(short = 'a', long = "american", default_value_t = false)]
+        american: bool,
+
+        #[arg(short = 'e', long = "european", default_value_t = false)]
+        european: bool,
+
+        // Bermudan exercise date in years from today, repeat for each date
+        #[arg(short = 'b', long = "bermudan")]
+        bermudan: Vec<f64>,
+
+        // American exercise only after this many years (e.g. a vesting cliff)
+        #[arg(short = 'l', long = "lockout")]
+        lockout: Option<f64>,
+
+        #[arg(short = 'g', long = "greeks", default_value_t = false)]
+        greeks: bool,
+
+    },
+
+    // Price single and double barrier options
+    Barrier {
+
+        // Command argument list
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        #[arg(short = 'k', long = "strike", required = true)]
+        strike: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
 
         #[arg(short = 'v', long = "volatility", required = true)]
         volatility: f64,
 
+        // The barrier, or the lower barrier when an upper barrier is given
+        #[arg(short = 'b', long = "barrier", required = true)]
+        barrier: f64,
+
+        // Upper barrier, makes the option a double barrier (only the in/out part of the kind is used)
+        #[arg(short = 'u', long = "upper")]
+        upper: Option<f64>,
+
+        #[arg(long = "rebate", default_value_t = 0.0)]
+        rebate: f64,
+
+        #[arg(long = "kind", value_enum, default_value_t = barrier::BarrierType::DownOut)]
+        kind: barrier::BarrierType,
+
+        // Years between barrier observations for discretely monitored barriers (e.g. 0.004 for daily)
+        #[arg(short = 'm', long = "monitoring")]
+        monitoring: Option<f64>,
+
         #[arg(short = 'n', long = "steps", default_value_t = 100)]
         steps: u32,
 
@@ -96,9 +227,840 @@ enum Commands {
         #[arg(short = 'e', long = "european", default_value_t = false)]
         european: bool,
 
+    },
+
+    // Price average price (Asian) options
+    Asian {
+
+        // Command argument list
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        #[arg(short = 'k', long = "strike", required = true)]
+        strike: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        // Number of fixings still to come (default: daily until expiry)
+        #[arg(short = 'f', long = "fixings")]
+        fixings: Option<u32>,
+
+        // Years until the first remaining fixing (default: one fixing interval)
+        #[arg(long = "first")]
+        first: Option<f64>,
+
+        // A fixing that has already been set, repeat for each one
+        #[arg(short = 'x', long = "fixing")]
+        past: Vec<f64>,
+
+        #[arg(long = "paths", default_value_t = 100000)]
+        paths: u32,
+
+        #[arg(long = "seed")]
+        seed: Option<u64>,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
+    },
+
+    // Price fixed and floating strike lookback options
+    Lookback {
+
+        // Command argument list
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        // Strike for a fixed strike lookback, leave out for a floating strike
+        #[arg(short = 'k', long = "strike")]
+        strike: Option<f64>,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        // Lowest (or highest) price seen so far, defaults to the spot for a new option
+        #[arg(short = 'x', long = "extreme")]
+        extreme: Option<f64>,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
+        #[arg(short = 'g', long = "greeks", default_value_t = false)]
+        greeks: bool,
+
+    },
+
+    // Price digital, gap and supershare options
+    Digital {
+
+        // Command argument list
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        // Strike (the lower strike for supershares)
+        #[arg(short = 'k', long = "strike", required = true)]
+        strike: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        #[arg(long = "kind", value_enum, default_value_t = digital::DigitalType::Cash)]
+        kind: digital::DigitalType,
+
+        // Cash paid by a cash-or-nothing option
+        #[arg(long = "payout", default_value_t = 1.0)]
+        payout: f64,
+
+        // Price that decides exercise for a gap option
+        #[arg(long = "trigger")]
+        trigger: Option<f64>,
+
+        // Upper strike of a supershare
+        #[arg(short = 'u', long = "upper")]
+        upper: Option<f64>,
+
+        // Strike width of the call spread used to check digital prices (default: 0.1% of strike)
+        #[arg(long = "width")]
+        width: Option<f64>,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
         #[arg(short = 'g', long = "greeks", default_value_t = false)]
         greeks: bool,
+    },
+
+    // Price options on options
+    Compound {
+
+        // Command argument list
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        // Strike of the underlying option
+        #[arg(short = 'k', long = "strike", required = true)]
+        strike: f64,
+
+        // Years to maturity of the underlying option
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        // Price paid for the underlying option if the compound option is exercised
+        #[arg(short = 'K', long = "compound-strike", required = true)]
+        compound_strike: f64,
+
+        // Years to maturity of the compound option
+        #[arg(short = 'T', long = "compound-time", required = true)]
+        compound_time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        // The underlying option is a put rather than a call
+        #[arg(long = "underlying-put", default_value_t = false)]
+        underlying_put: bool,
+
+        #[arg(long = "paths", default_value_t = 100000)]
+        paths: u32,
+
+        #[arg(long = "seed")]
+        seed: Option<u64>,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
+    },
+
+    // Price simple and complex chooser options
+    Chooser {
+
+        // Command argument list
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        // Strike of both options, or of the call for a complex chooser
+        #[arg(short = 'k', long = "strike", required = true)]
+        strike: f64,
+
+        // Years to maturity of both options, or of the call for a complex chooser
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        // Years until the holder chooses between the call and the put
+        #[arg(short = 'w', long = "choose", required = true)]
+        choose: f64,
+
+        // Put strike, makes it a complex chooser
+        #[arg(long = "put-strike")]
+        put_strike: Option<f64>,
+
+        // Put maturity, makes it a complex chooser
+        #[arg(long = "put-time")]
+        put_time: Option<f64>,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        #[arg(long = "paths", default_value_t = 100000)]
+        paths: u32,
+
+        #[arg(long = "seed")]
+        seed: Option<u64>,
+
+    },
+
+    // Price forward start and cliquet options
+    Forward {
+
+        // Command argument list
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        // Strike as a multiple of the stock price when each period starts
+        #[arg(short = 'm', long = "moneyness", default_value_t = 1.0)]
+        moneyness: f64,
+
+        // Years until the first strike is set
+        #[arg(long = "start", default_value_t = 0.0)]
+        start: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        // Number of equal reset periods, more than one makes it a cliquet
+        #[arg(long = "resets", default_value_t = 1)]
+        resets: u32,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        #[arg(long = "paths", default_value_t = 100000)]
+        paths: u32,
+
+        #[arg(long = "seed")]
+        seed: Option<u64>,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+    },
+
+    // Price exchange, spread and rainbow options on two correlated underlyings
+    TwoAsset {
+
+        // Command argument list
+        #[arg(long = "spot1", required = true)]
+        spot1: f64,
+
+        #[arg(long = "spot2", required = true)]
+        spot2: f64,
+
+        #[arg(long = "volatility1", required = true)]
+        volatility1: f64,
+
+        #[arg(long = "volatility2", required = true)]
+        volatility2: f64,
+
+        #[arg(short = 'o', long = "correlation", required = true, allow_negative_numbers = true)]
+        correlation: f64,
+
+        // Strike on the spread (first less second), or on the best/worst for rainbows
+        #[arg(short = 'k', long = "strike", default_value_t = 0.0)]
+        strike: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        // Both underlyings are futures contracts (no cost of carry)
+        #[arg(long = "futures", default_value_t = false)]
+        futures: bool,
+
+        // Price a best-of or worst-of option instead of a spread
+        #[arg(long = "rainbow", value_enum)]
+        rainbow: Option<two_asset::Rainbow>,
+
+        #[arg(long = "paths", default_value_t = 100000)]
+        paths: u32,
+
+        #[arg(long = "seed")]
+        seed: Option<u64>,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
+    },
+
+    // Price options on a weighted basket of correlated underlyings
+    Basket {
+
+        // Command argument list, repeat each of spot, volatility and weight once per asset
+        #[arg(short = 's', long = "spot", required = true)]
+        spots: Vec<f64>,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatilities: Vec<f64>,
+
+        // Weight of each asset (default: equal weights)
+        #[arg(short = 'w', long = "weight", allow_negative_numbers = true)]
+        weights: Vec<f64>,
+
+        // One pairwise correlation for every pair, or the upper triangle of the matrix in row order
+        #[arg(short = 'o', long = "correlation", required = true, allow_negative_numbers = true)]
+        correlations: Vec<f64>,
+
+        #[arg(short = 'k', long = "strike", required = true)]
+        strike: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        // The underlyings are futures contracts (no cost of carry)
+        #[arg(long = "futures", default_value_t = false)]
+        futures: bool,
+
+        #[arg(long = "paths", default_value_t = 100000)]
+        paths: u32,
+
+        #[arg(long = "seed")]
+        seed: Option<u64>,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+    },
+
+    // Price quanto and composite options on foreign-listed underlyings
+    Quanto {
+
+        // Command argument list
+        // Spot price in foreign currency
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        // Strike in foreign currency (domestic currency for composites)
+        #[arg(short = 'k', long = "strike", required = true)]
+        strike: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        // Domestic risk free rate
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        // Exchange rate in domestic currency per unit of foreign currency
+        #[arg(short = 'x', long = "fx", required = true)]
+        fx: f64,
+
+        #[arg(long = "fx-volatility", required = true)]
+        fx_volatility: f64,
+
+        #[arg(short = 'f', long = "foreign-rate", required = true)]
+        foreign_rate: f64,
+
+        // Correlation between the underlying and the exchange rate
+        #[arg(short = 'o', long = "correlation", required = true, allow_negative_numbers = true)]
+        correlation: f64,
+
+        // Exchange rate the quanto payoff is converted at (default: today's rate)
+        #[arg(long = "fixed-rate")]
+        fixed_rate: Option<f64>,
+
+        // Price a composite option (domestic strike, converted at the rate prevailing at expiry)
+        #[arg(long = "composite", default_value_t = false)]
+        composite: bool,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
+    },
+
+    // Price a payoff written in the payoff language, loaded from a file
+    Price {
+
+        // Command argument list
+        // File holding the payoff
+        #[arg(long = "payoff", required = true)]
+        payoff: String,
+
+        #[arg(short = 's', long = "spot", required = true)]
+        spot: f64,
+
+        #[arg(short = 't', long = "time", required = true)]
+        time: f64,
+
+        #[command(flatten)]
+        rate: yield_curve::RateArgs,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        // Set a value used by the payoff as NAME=VALUE, repeat for each one
+        #[arg(short = 'd', long = "define")]
+        defines: Vec<String>,
+
+        #[arg(short = 'n', long = "steps", default_value_t = 100)]
+        steps: u32,
+
+        // Number of fixings for path dependent payoffs (default: daily until expiry)
+        #[arg(short = 'f', long = "fixings")]
+        fixings: Option<u32>,
+
+        #[arg(long = "paths", default_value_t = 100000)]
+        paths: u32,
+
+        #[arg(long = "seed")]
+        seed: Option<u64>,
+
+    },
+
+    // Price bonds, bond options and callable/putable bonds under a short rate model
+    Bond {
+
+        // Command argument list
+        #[arg(long = "model", value_enum, default_value_t = short_rate::ShortRateType::Vasicek)]
+        model: short_rate::ShortRateType,
+
+        // Current short rate (Hull-White fits a flat curve at this rate unless a curve file is given)
+        #[arg(short = 'r', long = "rate", required = true, allow_negative_numbers = true)]
+        rate: f64,
+
+        // CSV file of tenor,par yield rows to bootstrap the Hull-White curve from
+        #[arg(long = "curve")]
+        curve: Option<String>,
+
+        #[arg(long = "interpolation", value_enum, default_value_t = yield_curve::Interpolation::MonotoneCubic)]
+        interpolation: yield_curve::Interpolation,
+
+        // Mean reversion speed
+        #[arg(short = 'a', long = "speed", default_value_t = 0.1)]
+        speed: f64,
+
+        // Long run mean of the short rate (default: current rate)
+        #[arg(short = 'b', long = "mean")]
+        mean: Option<f64>,
+
+        #[arg(short = 'v', long = "volatility", required = true)]
+        volatility: f64,
+
+        #[arg(short = 't', long = "maturity", required = true)]
+        maturity: f64,
+
+        // Annual coupon rate (default: zero coupon)
+        #[arg(long = "coupon", default_value_t = 0.0)]
+        coupon: f64,
+
+        #[arg(long = "frequency", default_value_t = 2)]
+        frequency: u32,
+
+        #[arg(long = "face", default_value_t = 100.0)]
+        face: f64,
+
+        // Strike of an option on the bond's full price
+        #[arg(short = 'k', long = "strike")]
+        strike: Option<f64>,
+
+        // Years until the bond option expires
+        #[arg(short = 'x', long = "expiry")]
+        expiry: Option<f64>,
+
+        // Price the issuer can redeem the bond at on coupon dates
+        #[arg(long = "call-price")]
+        call_price: Option<f64>,
+
+        #[arg(long = "call-from")]
+        call_from: Option<f64>,
+
+        // Price the holder can sell the bond back at on coupon dates
+        #[arg(long = "put-price")]
+        put_price: Option<f64>,
+
+        #[arg(long = "put-from")]
+        put_from: Option<f64>,
+
+        #[arg(short = 'n', long = "steps", default_value_t = 500)]
+        steps: u32,
+
+        #[arg(short = 'c', long = "call", default_value_t = false)]
+        call: bool,
+
+        #[arg(short = 'p', long = "put", default_value_t = false)]
+        put: bool,
+
+    },
+
+    // Price caps and floors on a strip of forward rates
+    Cap {
+
+        // Command argument list
+        // Flat risk free rate the forwards are read off
+        #[arg(short = 'r', long = "rate", required_unless_present = "curve", allow_negative_numbers = true)]
+        rate: Option<f64>,
+
+        // CSV file of tenor,par yield rows to bootstrap the curve from instead
+        #[arg(long = "curve")]
+        curve: Option<String>,
+
+        #[arg(long = "interpolation", value_enum, default_value_t = yield_curve::Interpolation::MonotoneCubic)]
+        interpolation: yield_curve::Interpolation,
+
+        // Strike rate
+        #[arg(short = 'k', long = "strike", required = true, allow_negative_numbers = true)]
+        strike: f64,
+
+        #[arg(short = 't', long = "maturity", required = true)]
+        maturity: f64,
+
+        #[arg(long = "frequency", default_value_t = 4)]
+        frequency: u32,
+
+        #[arg(long = "notional", default_value_t = 100.0)]
+        notional: f64,
+
+        #[arg(long = "model", value_enum, default_value_t = rate_options::RateModel::Black)]
+        model: rate_options::RateModel,
+
+        // Vol for every option (lognormal for black, normal for bachelier)
+        #[arg(short = 'v', long = "volatility")]
+        volatility: Option<f64>,
+
+        // CSV file of expiry,tenor,strike,vol rows to interpolate vols from instead
+        #[arg(long = "cube")]
+        cube: Option<String>,
+
+        // Price a floor instead of a cap
+        #[arg(long = "floor", default_value_t = false)]
+        floor: bool,
+
+    },
+
+    // Price swaptions on a forward starting swap
+    Swaption {
+
+        // Command argument list
+        // Flat risk free rate the forwards are read off
+        #[arg(short = 'r', long = "rate", required_unless_present = "curve", allow_negative_numbers = true)]
+        rate: Option<f64>,
+
+        // CSV file of tenor,par yield rows to bootstrap the curve from instead
+        #[arg(long = "curve")]
+        curve: Option<String>,
+
+        #[arg(long = "interpolation", value_enum, default_value_t = yield_curve::Interpolation::MonotoneCubic)]
+        interpolation: yield_curve::Interpolation,
+
+        // Strike rate
+        #[arg(short = 'k', long = "strike", required = true, allow_negative_numbers = true)]
+        strike: f64,
+
+        // Years until the swaption expires
+        #[arg(short = 'x', long = "expiry", required = true)]
+        expiry: f64,
+
+        // Length of the underlying swap in years
+        #[arg(short = 't', long = "tenor", required = true)]
+        tenor: f64,
+
+        #[arg(long = "frequency", default_value_t = 2)]
+        frequency: u32,
+
+        #[arg(long = "notional", default_value_t = 100.0)]
+        notional: f64,
+
+        #[arg(long = "model", value_enum, default_value_t = rate_options::RateModel::Black)]
+        model: rate_options::RateModel,
+
+        // Vol for every option (lognormal for black, normal for bachelier)
+        #[arg(short = 'v', long = "volatility")]
+        volatility: Option<f64>,
+
+        // CSV file of expiry,tenor,strike,vol rows to interpolate vols from instead
+        #[arg(long = "cube")]
+        cube: Option<String>,
+
+        // Price a receiver swaption instead of a payer
+        #[arg(long = "receiver", default_value_t = false)]
+        receiver: bool,
+
+    },
+
+    // Bootstrap and display the zero curve
+    Curve {
+
+        // Command argument list
+        // CSV file of tenor,par yield rows (default: latest US Treasury yields)
+        #[arg(short = 'f', long = "file")]
+        file: Option<String>,
+
+        #[arg(long = "interpolation", value_enum, default_value_t = yield_curve::Interpolation::MonotoneCubic)]
+        interpolation: yield_curve::Interpolation,
+
+        // Extra maturity to show, repeat for each one
+        #[arg(long = "at")]
+        at: Vec<f64>,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+
+    },
+
+    // Show every contract on an expiration with market quotes next to model prices and Greeks
+    Chain {
+
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required = true)]
+        symbol: String,
+
+        #[command(flatten)]
+        expiration: expiration::ExpirationArgs,
+
+        #[arg(short = 'n', long = "steps", default_value_t = 100)]
+        steps: u32,
+
+        // Flag contracts whose mid is further than this fraction from the model price
+        #[arg(long = "threshold", default_value_t = 0.05)]
+        threshold: f64,
+
+        #[arg(long = "day-count", value_enum, default_value_t = day_count::DayCount::Act365Fixed)]
+        day_count: day_count::DayCount,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+    },
+
+    // Fit an implied volatility surface across every expiration
+    Surface {
+
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required = true)]
+        symbol: String,
+
+        #[arg(long = "model", value_enum, default_value_t = vol_surface::SurfaceModel::Ssvi)]
+        model: vol_surface::SurfaceModel,
+
+        // Leave out expirations more than this many days away
+        #[arg(long = "max-dte")]
+        max_dte: Option<u32>,
+
+        // Log-moneyness range of the output grid, from -range to +range
+        #[arg(long = "range", default_value_t = 0.3)]
+        range: f64,
+
+        #[arg(long = "points", default_value_t = 25)]
+        points: usize,
+
+        #[arg(long = "csv")]
+        csv: Option<String>,
+
+        #[arg(long = "json")]
+        json: Option<String>,
+
+        // Print an ASCII heatmap of the surface
+        #[arg(long = "heatmap")]
+        heatmap: bool,
+
+        #[arg(long = "svg")]
+        svg: Option<String>,
+
+        #[arg(long = "day-count", value_enum, default_value_t = day_count::DayCount::Act365Fixed)]
+        day_count: day_count::DayCount,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+    },
+
+    // Check option chains for static arbitrage at the quoted bids and asks
+    Arbitrage {
+
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required = true)]
+        symbol: String,
+
+        #[command(flatten)]
+        expiration: expiration::ExpirationArgs,
+
+        // Check against European bounds instead of American ones
+        #[arg(long = "european")]
+        european: bool,
+
+        // Ignore violations worth less than this per share
+        #[arg(long = "tolerance", default_value_t = 0.01)]
+        tolerance: f64,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+    },
+
+    // Back the forward, discount rate and dividend or borrow rate out of put-call parity on each expiration
+    Carry {
+
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required = true)]
+        symbol: String,
+
+        #[command(flatten)]
+        expiration: expiration::ExpirationArgs,
+
+        #[arg(long = "day-count", value_enum, default_value_t = day_count::DayCount::Act365Fixed)]
+        day_count: day_count::DayCount,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+    },
+
+    // Estimate realised volatility from daily bars
+    Hv {
+
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required_unless_present = "file")]
+        symbol: Option<String>,
+
+        // CSV of date,open,high,low,close instead of the data provider
+        #[arg(short = 'f', long = "file")]
+        file: Option<String>,
+
+        // Window in trading days, repeat for each one (default: 10, 20, 30 and 60)
+        #[arg(short = 'w', long = "window")]
+        window: Vec<usize>,
+
+        // Show one estimator instead of all of them
+        #[arg(long = "estimator", value_enum)]
+        estimator: Option<historical::Estimator>,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+    },
+
+    // Fit GARCH models to daily returns and forecast volatility
+    Garch {
+
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required_unless_present = "file")]
+        symbol: Option<String>,
+
+        // CSV of date,open,high,low,close instead of the data provider
+        #[arg(short = 'f', long = "file")]
+        file: Option<String>,
+
+        // Fit one model instead of comparing all of them
+        #[arg(long = "model", value_enum)]
+        model: Option<garch::GarchModel>,
+
+        // Daily returns to fit to
+        #[arg(long = "history", default_value_t = 500)]
+        history: usize,
+
+        // Forecast horizon in trading days, repeat for each one (default: 5, 21, 63, 126 and 252)
+        #[arg(long = "horizon")]
+        horizon: Vec<usize>,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+    },
+
+    // Extract the risk-neutral density of the price at expiry from the smile
+    Density {
 
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required = true)]
+        symbol: String,
+
+        #[command(flatten)]
+        expiration: expiration::ExpirationArgs,
+
+        // Price level to show the probability of finishing above and below, repeat for each one (default: 5% and 10% either side of the spot)
+        #[arg(long = "level")]
+        level: Vec<f64>,
+
+        #[arg(long = "points", default_value_t = 801)]
+        points: usize,
+
+        #[arg(long = "csv")]
+        csv: Option<String>,
+
+        #[arg(long = "day-count", value_enum, default_value_t = day_count::DayCount::Act365Fixed)]
+        day_count: day_count::DayCount,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
+    },
+
+    // Model-free implied variance index (VIX methodology) and variance swap fair strikes
+    Vix {
+
+        // Command argument list
+        #[arg(short = 's', long = "symbol", required = true)]
+        symbol: String,
+
+        // Constant maturity horizon in calendar days, repeat for each one (default: 30)
+        #[arg(short = 'd', long = "days")]
+        days: Vec<u32>,
+
+        // Leave out expirations fewer than this many calendar days away
+        #[arg(long = "min-days", default_value_t = 7)]
+        min_days: u32,
+
+        #[command(flatten)]
+        provider: provider::ProviderArgs,
     }
 }
 
@@ -115,13 +1077,131 @@ async fn main() {
     match cli.command {
 
         // Price options automatically
-        Commands::Auto { symbol, strike, steps, call, put, greeks } => {
-            commands::auto::run(symbol, strike, steps, call, put, greeks).await;
+        Commands::Auto { symbol, strike, steps, call, put, greeks, day_count, smile, smile_axis, implied_carry, volatility, bars, expiration, provider } => {
+            commands::auto::run(symbol, strike, steps, call, put, greeks, day_count, smile, smile_axis, implied_carry, volatility, bars, expiration, provider).await;
         }
 
         // Price symbols manually
-        Commands::Manual { spot, strike, time, rate, volatility, steps, call, put, american, european, greeks } => {
-            commands::manual::run(spot, strike, time, rate, volatility, steps, call, put, american, european, greeks);
+        Commands::Manual { spot, strike, time, rate, volatility, bars, steps, call, put, american, european, bermudan, lockout, greeks } => {
+            commands::manual::run(spot, strike, time, rate, volatility, bars, steps, call, put, american, european, bermudan, lockout, greeks);
+        }
+
+        // Price barrier options
+        Commands::Barrier { spot, strike, time, rate, volatility, barrier, upper, rebate, kind, monitoring, steps, call, put, american, european } => {
+            commands::barrier::run(spot, strike, time, rate, volatility, barrier, upper, rebate, kind, monitoring, steps, call, put, american, european);
+        }
+
+        // Price Asian options
+        Commands::Asian { spot, strike, time, rate, volatility, fixings, first, past, paths, seed, call, put } => {
+            commands::asian::run(spot, strike, time, rate, volatility, fixings, first, past, paths, seed, call, put);
+        }
+
+        // Price lookback options
+        Commands::Lookback { spot, strike, time, rate, volatility, extreme, call, put, greeks } => {
+            commands::lookback::run(spot, strike, time, rate, volatility, extreme, call, put, greeks);
+        }
+
+        // Price digital options
+        Commands::Digital { spot, strike, time, rate, volatility, kind, payout, trigger, upper, width, call, put, greeks } => {
+            commands::digital::run(spot, strike, time, rate, volatility, kind, payout, trigger, upper, width, call, put, greeks);
+        }
+
+        // Price compound options
+        Commands::Compound { spot, strike, time, compound_strike, compound_time, rate, volatility, underlying_put, paths, seed, call, put } => {
+            commands::compound::run(spot, strike, time, compound_strike, compound_time, rate, volatility, underlying_put, paths, seed, call, put);
+        }
+
+        // Price chooser options
+        Commands::Chooser { spot, strike, time, choose, put_strike, put_time, rate, volatility, paths, seed } => {
+            commands::chooser::run(spot, strike, time, choose, put_strike, put_time, rate, volatility, paths, seed);
+        }
+
+        // Price forward start options
+        Commands::Forward { spot, moneyness, start, time, resets, rate, volatility, paths, seed, call, put } => {
+            commands::forward::run(spot, moneyness, start, time, resets, rate, volatility, paths, seed, call, put);
+        }
+
+        // Price two asset options
+        Commands::TwoAsset { spot1, spot2, volatility1, volatility2, correlation, strike, time, rate, futures, rainbow, paths, seed, call, put } => {
+            let pair = two_asset::AssetPair { spot1, spot2, volatility1, volatility2, carry1: 0.0, carry2: 0.0, correlation };
+            commands::two_asset::run(pair, strike, time, rate, futures, rainbow, paths, seed, call, put);
+        }
+
+        // Price basket options
+        Commands::Basket { spots, volatilities, weights, correlations, strike, time, rate, futures, paths, seed, call, put } => {
+            commands::basket::run(spots, volatilities, weights, correlations, strike, time, rate, futures, paths, seed, call, put);
+        }
+
+        // Price quanto options
+        Commands::Quanto { spot, strike, time, rate, volatility, fx, fx_volatility, foreign_rate, correlation, fixed_rate, composite, call, put } => {
+            let market = quanto::FxMarket { rate: fx, volatility: fx_volatility, foreign_rate, correlation };
+            commands::quanto::run(spot, strike, time, rate, volatility, market, fixed_rate, composite, call, put);
+        }
+
+        // Price a payoff from a file
+        Commands::Price { payoff, spot, time, rate, volatility, defines, steps, fixings, paths, seed } => {
+            commands::price::run(payoff, spot, time, rate, volatility, defines, steps, fixings, paths, seed);
+        }
+
+        // Price bonds under a short rate model
+        Commands::Bond { model, rate, curve, interpolation, speed, mean, volatility, maturity, coupon, frequency, face, strike, expiry, call_price, call_from, put_price, put_from, steps, call, put } => {
+            let bond = bond::Bond { face, coupon, frequency, maturity };
+            commands::bond::run(model, rate, curve, interpolation, speed, mean, volatility, bond, strike, expiry, call_price, call_from, put_price, put_from, steps, call, put);
+        }
+
+        // Price caps and floors
+        Commands::Cap { rate, curve, interpolation, strike, maturity, frequency, notional, model, volatility, cube, floor } => {
+            commands::cap::run(rate, curve, interpolation, strike, maturity, frequency, notional, model, volatility, cube, floor);
+        }
+
+        // Price swaptions
+        Commands::Swaption { rate, curve, interpolation, strike, expiry, tenor, frequency, notional, model, volatility, cube, receiver } => {
+            commands::swaption::run(rate, curve, interpolation, strike, expiry, tenor, frequency, notional, model, volatility, cube, receiver);
+        }
+
+        // Show the zero curve
+        Commands::Curve { file, interpolation, at, provider } => {
+            commands::curve::run(file, interpolation, at, provider).await;
+        }
+
+        // Show an option chain
+        Commands::Chain { symbol, expiration, steps, threshold, day_count, provider } => {
+            commands::chain::run(symbol, expiration, steps, threshold, day_count, provider).await;
+        }
+
+        // Build a volatility surface
+        Commands::Surface { symbol, model, max_dte, range, points, csv, json, heatmap, svg, day_count, provider } => {
+            commands::surface::run(symbol, model, max_dte, range, points, csv, json, heatmap, svg, day_count, provider).await;
+        }
+
+        // Check chains for static arbitrage
+        Commands::Arbitrage { symbol, expiration, european, tolerance, provider } => {
+            commands::arbitrage::run(symbol, expiration, european, tolerance, provider).await;
+        }
+
+        // Back out implied carry
+        Commands::Carry { symbol, expiration, day_count, provider } => {
+            commands::carry::run(symbol, expiration, day_count, provider).await;
+        }
+
+        // Estimate historical volatility
+        Commands::Hv { symbol, file, window, estimator, provider } => {
+            commands::hv::run(symbol, file, window, estimator, provider).await;
+        }
+
+        // Forecast volatility with GARCH
+        Commands::Garch { symbol, file, model, history, horizon, provider } => {
+            commands::garch::run(symbol, file, model, history, horizon, provider).await;
+        }
+
+        // Extract the risk-neutral density
+        Commands::Density { symbol, expiration, level, points, csv, day_count, provider } => {
+            commands::density::run(symbol, expiration, level, points, csv, day_count, provider).await;
+        }
+
+        // Compute the variance index
+        Commands::Vix { symbol, days, min_days, provider } => {
+            commands::vix::run(symbol, days, min_days, provider).await;
         }
     }
 }
@@ -0,0 +1,196 @@
+/*
+
+JDA Options Pricing
+/src/monte_carlo.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use rand::SeedableRng;
+use rand::distributions::Distribution;
+use rand::rngs::StdRng;
+use statrs::distribution::Normal;
+
+
+
+// Struct to hold a Monte Carlo price and its standard error
+#[derive(Debug, Clone, Copy)]
+pub struct MonteCarloEstimate {
+    pub price: f64,
+    pub std_error: f64,
+}
+
+
+
+// Build the random number generator, seeded if the caller wants reproducible prices
+pub fn generator(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+
+
+// Walk a geometric brownian motion through the given (increasing) times from a set of standard normal draws
+pub fn gbm_path(spot: f64, carry: f64, volatility: f64, times: &[f64], draws: &[f64]) -> Vec<f64> {
+
+    let mut path = Vec::with_capacity(times.len());
+    let mut price: f64 = spot;
+    let mut previous: f64 = 0.0;
+
+    for (t, z) in times.iter().zip(draws) {
+        let dt: f64 = t - previous;
+        price *= ((carry - 0.5 * volatility.powi(2)) * dt + volatility * dt.sqrt() * z).exp();
+        path.push(price);
+        previous = *t;
+    }
+
+    return path;
+}
+
+
+
+// Price a path dependent payoff by simulating the underlying at the given times, using antithetic pairs
+    // The payoff is handed the simulated prices at each time and is discounted from the expiry
+pub fn monte_carlo<F>(spot: f64, rate: f64, carry: f64, volatility: f64, times: &[f64], expiry: f64, paths: u32, seed: Option<u64>, payoff: F) -> MonteCarloEstimate
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let samples = antithetic_samples(spot, carry, volatility, times, paths, seed, |path| (payoff(path), 0.0));
+    let payoffs: Vec<f64> = samples.iter().map(|s| s.0).collect();
+
+    return estimate(&payoffs, (-1.0 * rate * expiry).exp());
+}
+
+
+
+// Price a path dependent payoff using a second payoff with a known value as a control variate
+    // The control coefficient is estimated by regression on the same paths
+pub fn monte_carlo_control_variate<F, G>(spot: f64, rate: f64, carry: f64, volatility: f64, times: &[f64], expiry: f64, paths: u32, seed: Option<u64>, payoff: F, control: G, control_value: f64) -> MonteCarloEstimate
+where
+    F: Fn(&[f64]) -> f64,
+    G: Fn(&[f64]) -> f64,
+{
+    let discount: f64 = (-1.0 * rate * expiry).exp();
+    let samples = antithetic_samples(spot, carry, volatility, times, paths, seed, |path| (payoff(path), control(path)));
+
+    // Regress the payoff on the control to get the optimal coefficient
+    let count: f64 = samples.len() as f64;
+    let mean_payoff: f64 = samples.iter().map(|s| s.0).sum::<f64>() / count;
+    let mean_control: f64 = samples.iter().map(|s| s.1).sum::<f64>() / count;
+
+    let covariance: f64 = samples.iter().map(|s| (s.0 - mean_payoff) * (s.1 - mean_control)).sum::<f64>();
+    let variance: f64 = samples.iter().map(|s| (s.1 - mean_control).powi(2)).sum::<f64>();
+    let beta: f64 = if variance > 0.0 { covariance / variance } else { 0.0 };
+
+    // Undiscounted value the control should average to
+    let control_target: f64 = control_value / discount;
+
+    let adjusted: Vec<f64> = samples.iter().map(|s| s.0 - beta * (s.1 - control_target)).collect();
+
+    return estimate(&adjusted, discount);
+}
+
+
+
+// Simulate antithetic path pairs and average each pair's payoffs into one sample
+fn antithetic_samples<F>(spot: f64, carry: f64, volatility: f64, times: &[f64], paths: u32, seed: Option<u64>, payoff: F) -> Vec<(f64, f64)>
+where
+    F: Fn(&[f64]) -> (f64, f64),
+{
+    let mut rng = generator(seed);
+    let standard_normal = Normal::standard();
+
+    let pairs: u32 = (paths / 2).max(1);
+    let mut samples = Vec::with_capacity(pairs as usize);
+
+    for _ in 0..pairs {
+        let draws: Vec<f64> = (0..times.len()).map(|_| standard_normal.sample(&mut rng)).collect();
+        let mirrored: Vec<f64> = draws.iter().map(|z| -z).collect();
+
+        let first = payoff(&gbm_path(spot, carry, volatility, times, &draws));
+        let second = payoff(&gbm_path(spot, carry, volatility, times, &mirrored));
+
+        samples.push((0.5 * (first.0 + second.0), 0.5 * (first.1 + second.1)));
+    }
+
+    return samples;
+}
+
+
+
+// Discounted sample mean and its standard error
+fn estimate(samples: &[f64], discount: f64) -> MonteCarloEstimate {
+
+    let count: f64 = samples.len() as f64;
+    let mean: f64 = samples.iter().sum::<f64>() / count;
+    let variance: f64 = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (count - 1.0).max(1.0);
+
+    return MonteCarloEstimate {
+        price: discount * mean,
+        std_error: discount * (variance / count).sqrt(),
+    };
+}
+
+
+
+// Cholesky factor (lower triangular) of a correlation matrix, used to correlate independent draws
+pub fn cholesky(matrix: &[Vec<f64>]) -> Result<Vec<Vec<f64>>, String> {
+
+    let size: usize = matrix.len();
+    let mut lower = vec![vec![0.0; size]; size];
+
+    for i in 0..size {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                let pivot: f64 = matrix[i][i] - sum;
+                if pivot <= 0.0 {
+                    return Err("correlation matrix is not positive definite".to_string());
+                }
+                lower[i][j] = pivot.sqrt();
+            } else {
+                lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+            }
+        }
+    }
+
+    return Ok(lower);
+}
+
+
+
+// Price a payoff on several correlated assets at a single expiry, using antithetic pairs
+    // The payoff is handed the simulated terminal price of every asset
+pub fn monte_carlo_terminal<F>(spots: &[f64], carries: &[f64], volatilities: &[f64], correlation: &[Vec<f64>], rate: f64, time: f64, paths: u32, seed: Option<u64>, payoff: F) -> Result<MonteCarloEstimate, String>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let lower = cholesky(correlation)?;
+    let mut rng = generator(seed);
+    let standard_normal = Normal::standard();
+
+    let pairs: u32 = (paths / 2).max(1);
+    let mut samples = Vec::with_capacity(pairs as usize);
+
+    // Terminal prices of every asset from one vector of correlated draws
+    let terminal = |draws: &[f64]| -> Vec<f64> {
+        (0..spots.len()).map(|i| {
+            let z: f64 = (0..=i).map(|k| lower[i][k] * draws[k]).sum();
+            spots[i] * ((carries[i] - 0.5 * volatilities[i].powi(2)) * time + volatilities[i] * time.sqrt() * z).exp()
+        }).collect()
+    };
+
+    for _ in 0..pairs {
+        let draws: Vec<f64> = (0..spots.len()).map(|_| standard_normal.sample(&mut rng)).collect();
+        let mirrored: Vec<f64> = draws.iter().map(|z| -z).collect();
+
+        samples.push(0.5 * (payoff(&terminal(&draws)) + payoff(&terminal(&mirrored))));
+    }
+
+    return Ok(estimate(&samples, (-1.0 * rate * time).exp()));
+}
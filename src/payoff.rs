@@ -0,0 +1,694 @@
+/*
+
+JDA Options Pricing
+/src/payoff.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use std::cell::Cell;
+use std::collections::HashMap;
+
+// Modules
+use crate::binomial::{Exercise, backward_induction};
+use crate::monte_carlo::{MonteCarloEstimate, monte_carlo};
+
+
+
+// A payoff written in a small expression language, for example
+    // max(S_T - K, 0)
+    // avg(S) > B ? 1 : 0
+// S and S_T are the price when the payoff is paid (or exercised), S_0 is today's spot and T the expiry
+// avg(S), gavg(S), max(S) and min(S) run over the fixing dates and make the payoff path dependent
+// Comparisons and logic evaluate to 1 or 0, so they can be used as indicators
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Price,
+    Spot,
+    Expiry,
+    Aggregate(Aggregate),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Function(Function, Vec<Expr>),
+}
+
+// Statistics of the simulated prices over the fixing dates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregate {
+    Average,
+    GeometricAverage,
+    Maximum,
+    Minimum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Negate,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Max,
+    Min,
+    Abs,
+    Exp,
+    Log,
+    Sqrt,
+}
+
+
+
+// The market state a payoff is evaluated in
+pub struct Scenario<'a> {
+    pub spot: f64,
+    pub expiry: f64,
+    pub price: f64,
+    pub path: &'a [f64],
+}
+
+impl Expr {
+
+    // Value of the expression in a given scenario
+    pub fn evaluate(&self, scenario: &Scenario) -> f64 {
+
+        let truth = |value: bool| if value { 1.0 } else { 0.0 };
+
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Price => scenario.price,
+            Expr::Spot => scenario.spot,
+            Expr::Expiry => scenario.expiry,
+            Expr::Aggregate(aggregate) => {
+                let path = scenario.path;
+                match aggregate {
+                    Aggregate::Average => path.iter().sum::<f64>() / (path.len() as f64),
+                    Aggregate::GeometricAverage => (path.iter().map(|p| p.ln()).sum::<f64>() / (path.len() as f64)).exp(),
+                    Aggregate::Maximum => path.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    Aggregate::Minimum => path.iter().copied().fold(f64::INFINITY, f64::min),
+                }
+            }
+            Expr::Unary(op, operand) => {
+                let value: f64 = operand.evaluate(scenario);
+                match op {
+                    UnaryOp::Negate => -1.0 * value,
+                    UnaryOp::Not => truth(value == 0.0),
+                }
+            }
+            Expr::Binary(op, left, right) => {
+                let (a, b) = (left.evaluate(scenario), right.evaluate(scenario));
+                match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Subtract => a - b,
+                    BinaryOp::Multiply => a * b,
+                    BinaryOp::Divide => a / b,
+                    BinaryOp::Power => a.powf(b),
+                    BinaryOp::Less => truth(a < b),
+                    BinaryOp::LessEqual => truth(a <= b),
+                    BinaryOp::Greater => truth(a > b),
+                    BinaryOp::GreaterEqual => truth(a >= b),
+                    BinaryOp::Equal => truth(a == b),
+                    BinaryOp::NotEqual => truth(a != b),
+                    BinaryOp::And => truth(a != 0.0 && b != 0.0),
+                    BinaryOp::Or => truth(a != 0.0 || b != 0.0),
+                }
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                if condition.evaluate(scenario) != 0.0 { then.evaluate(scenario) } else { otherwise.evaluate(scenario) }
+            }
+            Expr::Function(function, args) => {
+                let values: Vec<f64> = args.iter().map(|arg| arg.evaluate(scenario)).collect();
+                match function {
+                    Function::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    Function::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+                    Function::Abs => values[0].abs(),
+                    Function::Exp => values[0].exp(),
+                    Function::Log => values[0].ln(),
+                    Function::Sqrt => values[0].sqrt(),
+                }
+            }
+        }
+    }
+
+    // Whether any part of the expression matches a test
+    fn any(&self, test: &dyn Fn(&Expr) -> bool) -> bool {
+        if test(self) { return true; }
+        match self {
+            Expr::Number(_) | Expr::Price | Expr::Spot | Expr::Expiry | Expr::Aggregate(_) => false,
+            Expr::Unary(_, operand) => operand.any(test),
+            Expr::Binary(_, left, right) => left.any(test) || right.any(test),
+            Expr::Conditional(condition, then, otherwise) => condition.any(test) || then.any(test) || otherwise.any(test),
+            Expr::Function(_, args) => args.iter().any(|arg| arg.any(test)),
+        }
+    }
+
+    // Whether the expression looks at the path rather than just the price when it is paid
+    pub fn path_dependent(&self) -> bool {
+        return self.any(&|e| matches!(e, Expr::Aggregate(_)));
+    }
+
+    // Value of an expression that does not depend on the market, such as an exercise date
+    fn constant(&self) -> Option<f64> {
+        if self.any(&|e| matches!(e, Expr::Price | Expr::Spot | Expr::Expiry | Expr::Aggregate(_))) {
+            return None;
+        }
+        return Some(self.evaluate(&Scenario { spot: 0.0, expiry: 0.0, price: 0.0, path: &[] }));
+    }
+}
+
+
+
+// A payoff loaded from a payoff file, together with when it may be exercised
+#[derive(Debug, Clone)]
+pub struct Payoff {
+    pub text: String,
+    pub expression: Expr,
+    pub exercise: Exercise,
+}
+
+impl Payoff {
+
+    // Parse a payoff file, one statement per line with # starting a comment
+        // NAME = expression     defines a constant (or sub-expression) used by later lines
+        // payoff = expression   the payoff itself (a line with a bare expression also works)
+        // exercise = american, european, bermudan(t1, t2, ...) or lockout(t)
+    // Values passed in by the caller take precedence over definitions in the file
+    pub fn parse(source: &str, defines: &[(String, f64)]) -> Result<Payoff, String> {
+
+        let mut definitions: HashMap<String, Expr> = defines.iter().map(|(name, value)| (name.clone(), Expr::Number(*value))).collect();
+        let mut payoff: Option<(String, Expr)> = None;
+        let mut exercise: Exercise = Exercise::European;
+
+        for (number, line) in source.lines().enumerate() {
+
+            let statement: &str = line.split('#').next().unwrap_or("").trim();
+            if statement.is_empty() { continue; }
+
+            let fail = |e: String| format!("line {}: {}", number + 1, e);
+            let tokens: Vec<Token> = tokenize(statement).map_err(fail)?;
+
+            // Split off the name being assigned, if any
+            let (name, body): (Option<&str>, &[Token]) = match tokens.as_slice() {
+                [Token::Ident(name), Token::Symbol("="), rest @ ..] => (Some(name.as_str()), rest),
+                _ => (None, tokens.as_slice()),
+            };
+
+            if name == Some("exercise") {
+                exercise = parse_exercise(body).map_err(fail)?;
+                continue;
+            }
+
+            let mut parser = Parser { tokens: body, position: 0, definitions: &definitions };
+            let expression: Expr = parser.parse_all().map_err(fail)?;
+
+            match name {
+                Some("payoff") | None => {
+                    if payoff.is_some() {
+                        return Err(fail("the payoff is given more than once".to_string()));
+                    }
+                    let text: String = if name.is_some() { statement.split_once('=').map(|(_, rest)| rest.trim().to_string()).unwrap_or_default() } else { statement.to_string() };
+                    payoff = Some((text, expression));
+                }
+                Some(name) => {
+                    // Keep the caller's value over the file's
+                    if !defines.iter().any(|(defined, _)| defined == name) {
+                        definitions.insert(name.to_string(), expression);
+                    }
+                }
+            }
+        }
+
+        let (text, expression) = payoff.ok_or("no payoff was given".to_string())?;
+
+        if expression.path_dependent() && exercise != Exercise::European {
+            return Err("early exercise needs a payoff that only depends on the current price".to_string());
+        }
+
+        return Ok(Payoff { text, expression, exercise });
+    }
+
+    // Whether the payoff needs Monte Carlo rather than the lattice
+    pub fn path_dependent(&self) -> bool {
+        return self.expression.path_dependent();
+    }
+}
+
+
+
+// Price a payoff that only depends on the current price by rolling it back through a binomial tree
+    // Early exercise pays the same payoff at the node, whenever the exercise schedule allows it
+pub fn lattice_price(payoff: &Payoff, spot: f64, time: f64, rate: f64, volatility: f64, steps: u32) -> Result<f64, String> {
+
+    let dt: f64 = time / (steps as f64);
+    let u: f64 = (volatility * dt.sqrt()).exp();
+
+    // Remember any node where the payoff is infinite or undefined, as taking the max with the continuation value can hide a NaN
+    let finite: Cell<bool> = Cell::new(true);
+    let value = |price: f64| {
+        let value: f64 = payoff.expression.evaluate(&Scenario { spot, expiry: time, price, path: &[] });
+        if !value.is_finite() { finite.set(false); }
+        value
+    };
+
+    let price: f64 = backward_induction(spot, time, rate, steps, u, value, |step, _i, price, expected_value| {
+        if payoff.exercise.allowed(step, dt) { expected_value.max(value(price)) } else { expected_value }
+    })?;
+
+    if !finite.get() || !price.is_finite() {
+        return Err(format!("the payoff {} is not a finite number at every price", payoff.text));
+    }
+
+    return Ok(price);
+}
+
+
+
+// Price a European payoff by simulating the underlying at the fixing dates, the last of which is expiry
+pub fn monte_carlo_price(payoff: &Payoff, spot: f64, time: f64, rate: f64, volatility: f64, fixings: &[f64], paths: u32, seed: Option<u64>) -> Result<MonteCarloEstimate, String> {
+
+    // No dividends, so the cost of carry is the risk free rate
+    let estimate: MonteCarloEstimate = monte_carlo(spot, rate, rate, volatility, fixings, time, paths, seed, |path| {
+        payoff.expression.evaluate(&Scenario { spot, expiry: time, price: path[path.len() - 1], path })
+    });
+
+    // An infinite or undefined payoff on any path carries through to the average
+    if !estimate.price.is_finite() {
+        return Err(format!("the payoff {} is not a finite number on every path", payoff.text));
+    }
+
+    return Ok(estimate);
+}
+
+
+
+// Read an exercise schedule from the right hand side of an exercise statement
+fn parse_exercise(tokens: &[Token]) -> Result<Exercise, String> {
+
+    let dates = |args: &[Token]| -> Result<Vec<f64>, String> {
+        let mut parser = Parser { tokens: args, position: 0, definitions: &HashMap::new() };
+        parser.expect("(")?;
+        let values = parser.arguments()?;
+        if parser.position != args.len() {
+            return Err("unexpected input after the exercise dates".to_string());
+        }
+        return values.iter().map(|v| v.constant().ok_or("exercise dates must be numbers".to_string())).collect();
+    };
+
+    match tokens {
+        [Token::Ident(style)] if style == "european" => Ok(Exercise::European),
+        [Token::Ident(style)] if style == "american" => Ok(Exercise::American),
+        [Token::Ident(style), rest @ ..] if style == "bermudan" => Ok(Exercise::Bermudan(dates(rest)?)),
+        [Token::Ident(style), rest @ ..] if style == "lockout" => {
+            match dates(rest)?.as_slice() {
+                [lockout] => Ok(Exercise::Lockout(*lockout)),
+                _ => Err("lockout takes a single date".to_string()),
+            }
+        }
+        _ => Err("exercise must be american, european, bermudan(...) or lockout(...)".to_string()),
+    }
+}
+
+
+
+// Lexical tokens of the payoff language
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 20] = ["<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "^", "(", ")", ",", "?", ":", "<", ">", "!", "="];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i: usize = 0;
+
+    while i < chars.len() {
+        let c: char = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start: usize = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') { i += 1; }
+            // Allow scientific notation such as 1e-4
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                i += 1;
+                if i < chars.len() && (chars[i] == '+' || chars[i] == '-') { i += 1; }
+                while i < chars.len() && chars[i].is_ascii_digit() { i += 1; }
+            }
+            let literal: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(literal.parse().map_err(|_| format!("invalid number '{}'", literal))?));
+        } else if c.is_alphabetic() || c == '_' {
+            let start: usize = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { i += 1; }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..].iter().take(2).collect();
+            match SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+                Some(symbol) => {
+                    tokens.push(Token::Symbol(symbol));
+                    i += symbol.len();
+                }
+                None => return Err(format!("unexpected character '{}'", c)),
+            }
+        }
+    }
+
+    return Ok(tokens);
+}
+
+
+
+// Recursive descent parser, from the loosest binding operator to the tightest
+    // ternary, ||, &&, comparisons, + -, * /, unary - !, ^
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    definitions: &'a HashMap<String, Expr>,
+}
+
+impl Parser<'_> {
+
+    fn peek(&self) -> Option<&Token> {
+        return self.tokens.get(self.position);
+    }
+
+    // Consume the next token if it is the given symbol
+    fn accept(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.position += 1;
+            return true;
+        }
+        return false;
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.accept(symbol) { Ok(()) } else { Err(format!("expected '{}'", symbol)) }
+    }
+
+    // Parse a whole statement, rejecting anything left over
+    fn parse_all(&mut self) -> Result<Expr, String> {
+        let expression = self.expression()?;
+        match self.peek() {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expr, String> {
+        let condition = self.or()?;
+        if self.accept("?") {
+            let then = self.expression()?;
+            self.expect(":")?;
+            let otherwise = self.expression()?;
+            return Ok(Expr::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)));
+        }
+        return Ok(condition);
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.accept("||") {
+            left = Expr::Binary(BinaryOp::Or, Box::new(left), Box::new(self.and()?));
+        }
+        return Ok(left);
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.comparison()?;
+        while self.accept("&&") {
+            left = Expr::Binary(BinaryOp::And, Box::new(left), Box::new(self.comparison()?));
+        }
+        return Ok(left);
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.sum()?;
+        let operators = [("<=", BinaryOp::LessEqual), (">=", BinaryOp::GreaterEqual), ("==", BinaryOp::Equal), ("!=", BinaryOp::NotEqual), ("<", BinaryOp::Less), (">", BinaryOp::Greater)];
+        for (symbol, op) in operators {
+            if self.accept(symbol) {
+                return Ok(Expr::Binary(op, Box::new(left), Box::new(self.sum()?)));
+            }
+        }
+        return Ok(left);
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.product()?;
+        loop {
+            if self.accept("+") {
+                left = Expr::Binary(BinaryOp::Add, Box::new(left), Box::new(self.product()?));
+            } else if self.accept("-") {
+                left = Expr::Binary(BinaryOp::Subtract, Box::new(left), Box::new(self.product()?));
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            if self.accept("*") {
+                left = Expr::Binary(BinaryOp::Multiply, Box::new(left), Box::new(self.unary()?));
+            } else if self.accept("/") {
+                left = Expr::Binary(BinaryOp::Divide, Box::new(left), Box::new(self.unary()?));
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.accept("-") {
+            return Ok(Expr::Unary(UnaryOp::Negate, Box::new(self.unary()?)));
+        }
+        if self.accept("!") {
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(self.unary()?)));
+        }
+        return self.power();
+    }
+
+    // Powers bind tighter than a leading minus and group to the right
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.primary()?;
+        if self.accept("^") {
+            return Ok(Expr::Binary(BinaryOp::Power, Box::new(base), Box::new(self.unary()?)));
+        }
+        return Ok(base);
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+
+        let token = self.peek().cloned().ok_or("unexpected end of expression".to_string())?;
+        self.position += 1;
+
+        match token {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Symbol("(") => {
+                let inner = self.expression()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Token::Ident(name) => {
+                if self.accept("(") {
+                    return self.call(&name);
+                }
+                match name.as_str() {
+                    "S" | "S_T" => Ok(Expr::Price),
+                    "S_0" => Ok(Expr::Spot),
+                    "T" => Ok(Expr::Expiry),
+                    _ => self.definitions.get(&name).cloned().ok_or(format!("unknown variable '{}'", name)),
+                }
+            }
+            Token::Symbol(symbol) => Err(format!("unexpected '{}'", symbol)),
+        }
+    }
+
+    // Parse a function call, the opening bracket having been consumed
+    fn call(&mut self, name: &str) -> Result<Expr, String> {
+
+        // A path statistic takes the bare path S
+        let aggregate = match name {
+            "avg" => Some(Aggregate::Average),
+            "gavg" => Some(Aggregate::GeometricAverage),
+            "max" => Some(Aggregate::Maximum),
+            "min" => Some(Aggregate::Minimum),
+            _ => None,
+        };
+        let path_argument: bool = matches!(self.tokens.get(self.position..self.position + 2), Some([Token::Ident(s), Token::Symbol(")")]) if s == "S");
+
+        if let (Some(aggregate), true) = (aggregate, path_argument) {
+            self.position += 2;
+            return Ok(Expr::Aggregate(aggregate));
+        }
+
+        let args = self.arguments()?;
+
+        let (function, arity): (Function, Option<usize>) = match name {
+            "max" => (Function::Max, None),
+            "min" => (Function::Min, None),
+            "abs" => (Function::Abs, Some(1)),
+            "exp" => (Function::Exp, Some(1)),
+            "log" | "ln" => (Function::Log, Some(1)),
+            "sqrt" => (Function::Sqrt, Some(1)),
+            "avg" | "gavg" => return Err(format!("{} takes the path S", name)),
+            _ => return Err(format!("unknown function '{}'", name)),
+        };
+
+        if args.is_empty() || arity.is_some_and(|arity| args.len() != arity) {
+            return Err(format!("wrong number of arguments to {}", name));
+        }
+
+        return Ok(Expr::Function(function, args));
+    }
+
+    // Comma separated arguments up to the closing bracket
+    fn arguments(&mut self) -> Result<Vec<Expr>, String> {
+        let mut args = Vec::new();
+        if self.accept(")") {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expression()?);
+            if self.accept(")") {
+                return Ok(args);
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::binomial::binomial;
+
+    // Value of a payoff file at a price at expiry, with the path for the path statistics
+    fn value(source: &str, defines: &[(&str, f64)], price: f64, path: &[f64]) -> f64 {
+        let defines: Vec<(String, f64)> = defines.iter().map(|(name, value)| (name.to_string(), *value)).collect();
+        let payoff: Payoff = Payoff::parse(source, &defines).unwrap();
+        return payoff.expression.evaluate(&Scenario { spot: 80.0, expiry: 1.0, price, path });
+    }
+
+    fn error(source: &str) -> String {
+        return Payoff::parse(source, &[]).unwrap_err();
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(value("2 + 3 * 4 ^ 2", &[], 0.0, &[]), 50.0);
+        assert_eq!(value("(2 + 3) * 4", &[], 0.0, &[]), 20.0);
+        assert_eq!(value("10 - 4 - 3", &[], 0.0, &[]), 3.0);
+        assert_eq!(value("12 / 2 / 3", &[], 0.0, &[]), 2.0);
+        // Powers bind tighter than a leading minus and group to the right
+        assert_eq!(value("-2 ^ 2", &[], 0.0, &[]), -4.0);
+        assert_eq!(value("2 ^ 3 ^ 2", &[], 0.0, &[]), 512.0);
+        assert_eq!(value("1 + 2 > 2 && 3 < 4 || 0", &[], 0.0, &[]), 1.0);
+        assert_eq!(value("!(S >= 100)", &[], 100.0, &[]), 0.0);
+    }
+
+    #[test]
+    fn ternary() {
+        let source: &str = "payoff = S < 90 ? 1 : S < 110 ? 2 : 3";
+        assert_eq!(value(source, &[], 80.0, &[]), 1.0);
+        assert_eq!(value(source, &[], 100.0, &[]), 2.0);
+        assert_eq!(value(source, &[], 120.0, &[]), 3.0);
+        assert_eq!(value("S > 100 ? S - 100 : 0", &[], 112.5, &[]), 12.5);
+    }
+
+    #[test]
+    fn path_statistics() {
+        let path: [f64; 4] = [90.0, 100.0, 110.0, 120.0];
+        assert_eq!(value("avg(S)", &[], 120.0, &path), 105.0);
+        assert_eq!(value("min(S)", &[], 120.0, &path), 90.0);
+        assert_eq!(value("max(S)", &[], 120.0, &path), 120.0);
+        assert!((value("gavg(S)", &[], 120.0, &path) - (90.0_f64 * 100.0 * 110.0 * 120.0).powf(0.25)).abs() < 1e-9);
+        // The lookback's minimum includes today's spot of 80
+        assert_eq!(value("S_T - min(min(S), S_0)", &[], 120.0, &path), 40.0);
+
+        // max and min of expressions are plain functions on the price
+        assert!(Payoff::parse("payoff = avg(S) > 100 ? 1 : 0", &[]).unwrap().path_dependent());
+        assert!(!Payoff::parse("payoff = max(S - 100, 0)", &[]).unwrap().path_dependent());
+        assert_eq!(value("max(S - 100, 0, 5)", &[], 103.0, &path), 5.0);
+    }
+
+    #[test]
+    fn definitions_and_overrides() {
+        let source: &str = "K = 100\nW = K / 10\npayoff = max(K - S, 0) + W";
+        assert_eq!(value(source, &[], 95.0, &[]), 15.0);
+        // Values from -d replace the file's, and flow into later definitions
+        assert_eq!(value(source, &[("K", 110.0)], 95.0, &[]), 26.0);
+        assert_eq!(value(source, &[("W", 0.0)], 95.0, &[]), 5.0);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(error("payoff = max(S - X, 0)"), "line 1: unknown variable 'X'");
+        assert_eq!(error("# comment\npayoff = S 100"), "line 2: unexpected Number(100.0)");
+        assert_eq!(error("payoff = S * )"), "line 1: unexpected ')'");
+        assert_eq!(error("payoff = (S - 100"), "line 1: expected ')'");
+        assert_eq!(error("payoff = foo(S)"), "line 1: unknown function 'foo'");
+        assert_eq!(error("payoff = S $ 2"), "line 1: unexpected character '$'");
+        assert_eq!(error("K = 100"), "no payoff was given");
+        assert_eq!(error("payoff = avg(S)\nexercise = american"), "early exercise needs a payoff that only depends on the current price");
+    }
+
+    #[test]
+    fn bundled_american_put_matches_manual() {
+        let source: String = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/payoffs/american_put.payoff")).unwrap();
+        let payoff: Payoff = Payoff::parse(&source, &[]).unwrap();
+        assert_eq!(payoff.exercise, Exercise::American);
+
+        // rustquant manual -s 100 -k 100 -t 1 -r 0.05 -v 0.2 -p
+        let price: f64 = lattice_price(&payoff, 100.0, 1.0, 0.05, 0.2, 100).unwrap();
+        assert!((price - binomial(100.0, 100.0, 1.0, 0.05, 0.2, 100, false, &Exercise::American)).abs() < 0.005);
+        assert_eq!((price * 100.0).round() / 100.0, 6.08);
+    }
+
+    #[test]
+    fn non_finite_payoffs_are_rejected() {
+        let infinite: Payoff = Payoff::parse("payoff = 1/0", &[]).unwrap();
+        assert!(lattice_price(&infinite, 100.0, 1.0, 0.05, 0.2, 50).is_err());
+        assert!(monte_carlo_price(&infinite, 100.0, 1.0, 0.05, 0.2, &[1.0], 100, Some(1)).is_err());
+
+        // NaN below the strike, which early exercise would otherwise hide behind the max
+        let undefined: Payoff = Payoff::parse("payoff = sqrt(S - 100)\nexercise = american", &[]).unwrap();
+        assert!(lattice_price(&undefined, 100.0, 1.0, 0.05, 0.2, 50).is_err());
+
+        let path: Payoff = Payoff::parse("payoff = 1 / (avg(S) - avg(S))", &[]).unwrap();
+        assert!(monte_carlo_price(&path, 100.0, 1.0, 0.05, 0.2, &[0.5, 1.0], 100, Some(1)).is_err());
+
+        let fine: Payoff = Payoff::parse("payoff = max(S - 100, 0)", &[]).unwrap();
+        assert!(monte_carlo_price(&fine, 100.0, 1.0, 0.05, 0.2, &[1.0], 100, Some(1)).is_ok());
+    }
+}
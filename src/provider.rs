@@ -0,0 +1,551 @@
+/*
+
+JDA Options Pricing
+/src/provider.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::{Args, ValueEnum};
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::Client;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+// Modules
+use crate::calendar::new_york_time;
+use crate::config::{Config, Credentials, redact};
+
+
+
+// Where market data comes from
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ProviderType {
+    // Live requests to marketdata.app and FRED
+    Http,
+    // Saved JSON responses replayed from a directory, no network needed
+    Fixture,
+}
+
+// Market data arguments shared by every command that pulls live data
+    // Unset values fall back to the config file, then to live HTTP data and the fixtures directory
+#[derive(Debug, Clone, Args)]
+pub struct ProviderArgs {
+
+    #[arg(long = "provider", value_enum, env = "RUSTQUANT_PROVIDER")]
+    pub provider: Option<ProviderType>,
+
+    // Directory of saved responses for the fixture provider
+    #[arg(long = "fixtures", env = "RUSTQUANT_FIXTURES")]
+    pub fixtures: Option<PathBuf>,
+
+    // Save every live response into this directory so the run can be replayed later
+    #[arg(long = "record")]
+    pub record: Option<PathBuf>,
+}
+
+
+
+// One strike from an option chain, values the vendor leaves out are None
+#[derive(Debug, Clone)]
+pub struct ChainQuote {
+    // OCC option symbol, such as AAPL250620C00205000
+    pub option_symbol: String,
+    pub strike: f64,
+    pub volatility: f64,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub mid: Option<f64>,
+    pub last: Option<f64>,
+    pub volume: u64,
+    pub open_interest: u64,
+    pub greeks: VendorGreeks,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+impl ChainQuote {
+
+    // Vendor mid, or the middle of the bid and ask when the vendor leaves it out
+    pub fn mid_price(&self) -> Option<f64> {
+        match (self.mid, self.bid, self.ask) {
+            (Some(mid), _, _) => Some(mid),
+            (None, Some(bid), Some(ask)) => Some(0.5 * (bid + ask)),
+            _ => None,
+        }
+    }
+}
+
+// Greeks as the vendor quotes them, vega per vol point (1%) and theta per calendar day
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VendorGreeks {
+    pub delta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub vega: Option<f64>,
+    pub theta: Option<f64>,
+}
+
+// Daily open, high, low and close of the underlying
+#[derive(Debug, Clone, Copy)]
+pub struct Bar {
+    pub date: NaiveDate,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+// Quote on the underlying
+#[derive(Debug, Clone, Copy)]
+pub struct StockQuote {
+    pub last: f64,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+
+
+// Everything the live pricing commands need from a market data source
+pub trait MarketDataProvider {
+
+    // The moment the data is as of, live providers use the clock and fixtures the time they were recorded
+    fn now(&self) -> DateTime<Utc>;
+
+    // Listed expiration dates for calls (or puts) on a symbol
+    async fn expirations(&self, symbol: &str, call: bool) -> Result<Vec<NaiveDate>, Box<dyn Error>>;
+
+    // Every strike listed on an expiration date
+    async fn chain(&self, symbol: &str, expiration: NaiveDate, call: bool) -> Result<Vec<ChainQuote>, Box<dyn Error>>;
+
+    // Last traded price, bid and ask of the underlying
+    async fn quote(&self, symbol: &str) -> Result<StockQuote, Box<dyn Error>>;
+
+    // The latest count daily bars of the underlying up to now, oldest first
+    async fn candles(&self, symbol: &str, count: usize) -> Result<Vec<Bar>, Box<dyn Error>>;
+
+    // Latest US treasury par yields (in decimals) by tenor in years, for building the rate curve
+    async fn treasury_yields(&self) -> Result<Vec<(f64, f64)>, Box<dyn Error>>;
+}
+
+
+
+// Treasury constant maturity series on FRED and their tenors in years
+const TREASURY_SERIES: [(f64, &str); 11] = [
+    (1.0 / 12.0, "GS1M"),
+    (0.25, "GS3M"),
+    (0.5, "GS6M"),
+    (1.0, "GS1"),
+    (2.0, "GS2"),
+    (3.0, "GS3"),
+    (5.0, "GS5"),
+    (7.0, "GS7"),
+    (10.0, "GS10"),
+    (20.0, "GS20"),
+    (30.0, "GS30"),
+];
+
+// File in a fixture directory holding the time it was recorded
+const RECORDED_AT: &str = "recorded_at";
+
+
+
+// Live data over HTTP, chains and quotes from marketdata.app and treasury yields from FRED
+pub struct HttpProvider {
+    client: Client,
+    credentials: Credentials,
+    record: Option<PathBuf>,
+}
+
+impl HttpProvider {
+
+    pub fn new(credentials: Credentials, record: Option<PathBuf>) -> Result<HttpProvider, Box<dyn Error>> {
+
+        if let Some(dir) = &record {
+            fs::create_dir_all(dir)?;
+            fs::write(dir.join(RECORDED_AT), Utc::now().to_rfc3339())?;
+        }
+
+        Ok(HttpProvider { client: Client::new(), credentials, record })
+    }
+
+    // Request a JSON document, saving it under the fixture name when recording
+        // Errors never carry the raw URL, only the redacted one, since it can hold an API key
+    async fn get(&self, url: &str, bearer: Option<&str>, fixture: &str) -> Result<serde_json::Value, Box<dyn Error>> {
+
+        let mut request = self.client.get(url);
+        if let Some(token) = bearer {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(|e| format!("request to {} failed, {}", redact(url), e.without_url()))?;
+
+        let status = response.status();
+        if status.as_u16() == 401 || status.as_u16() == 403 {
+            return Err(format!("{} rejected the credentials (HTTP {}), check the configured token or API key", redact(url), status.as_u16()).into());
+        }
+        if !status.is_success() {
+            return Err(format!("request to {} failed with HTTP {}", redact(url), status.as_u16()).into());
+        }
+
+        let json: serde_json::Value = response.json().await.map_err(|e| format!("invalid response from {}, {}", redact(url), e.without_url()))?;
+
+        if let Some(dir) = &self.record {
+            let path: PathBuf = dir.join(fixture);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, serde_json::to_string_pretty(&json)?)?;
+        }
+
+        Ok(json)
+    }
+}
+
+impl MarketDataProvider for HttpProvider {
+
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    async fn expirations(&self, symbol: &str, call: bool) -> Result<Vec<NaiveDate>, Box<dyn Error>> {
+        let url = format!("https://api.marketdata.app/v1/options/expirations/{}?side={}", symbol, side(call));
+        parse_expirations(&self.get(&url, Some(self.credentials.marketdata_token()?), &expirations_fixture(symbol, call)).await?)
+    }
+
+    async fn chain(&self, symbol: &str, expiration: NaiveDate, call: bool) -> Result<Vec<ChainQuote>, Box<dyn Error>> {
+        let url = format!("https://api.marketdata.app/v1/options/chain/{}?side={}&expiration={}", symbol, side(call), expiration);
+        parse_chain(&self.get(&url, Some(self.credentials.marketdata_token()?), &chain_fixture(symbol, expiration, call)).await?)
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<StockQuote, Box<dyn Error>> {
+        let url = format!("https://api.marketdata.app/v1/stocks/quotes/{}", symbol);
+        parse_quote(&self.get(&url, Some(self.credentials.marketdata_token()?), &quote_fixture(symbol)).await?)
+    }
+
+    async fn candles(&self, symbol: &str, count: usize) -> Result<Vec<Bar>, Box<dyn Error>> {
+        let url = format!("https://api.marketdata.app/v1/stocks/candles/D/{}/?to={}&countback={}", symbol, new_york_time(self.now()).date(), count);
+        let bars: Vec<Bar> = parse_candles(&self.get(&url, Some(self.credentials.marketdata_token()?), &candles_fixture(symbol)).await?)?;
+        Ok(latest(bars, count))
+    }
+
+    async fn treasury_yields(&self) -> Result<Vec<(f64, f64)>, Box<dyn Error>> {
+
+        let api_key: &str = self.credentials.fred_api_key()?;
+        let mut par_yields: Vec<(f64, f64)> = Vec::new();
+
+        for (tenor, series) in TREASURY_SERIES {
+            let url = format!("https://api.stlouisfed.org/fred/series/observations?series_id={}&api_key={}&file_type=json&sort_order=desc&limit=1", series, api_key);
+            if let Some(rate) = parse_observation(&self.get(&url, None, &treasury_fixture(series)).await?, series)? {
+                par_yields.push((tenor, rate));
+            }
+        }
+
+        Ok(par_yields)
+    }
+}
+
+
+
+// Replays responses saved by --record (or written by hand) from a directory, laid out as
+    // recorded_at                          RFC 3339 time the data is as of
+    // <SYMBOL>/expirations_<side>.json     marketdata.app expirations response
+    // <SYMBOL>/chain_<side>_<date>.json    marketdata.app chain response
+    // <SYMBOL>/quote.json                  marketdata.app stock quote response
+    // <SYMBOL>/candles.json                marketdata.app daily candles response
+    // fred/<SERIES>.json                   FRED observations response
+pub struct FixtureProvider {
+    dir: PathBuf,
+    recorded_at: DateTime<Utc>,
+}
+
+impl FixtureProvider {
+
+    pub fn new(dir: PathBuf) -> Result<FixtureProvider, Box<dyn Error>> {
+
+        if !dir.is_dir() {
+            return Err(format!("fixture directory {} does not exist", dir.display()).into());
+        }
+
+        let stamp: String = fs::read_to_string(dir.join(RECORDED_AT)).map_err(|e| format!("unable to read {} in {}, {}", RECORDED_AT, dir.display(), e))?;
+        let recorded_at: DateTime<Utc> = DateTime::parse_from_rfc3339(stamp.trim())?.with_timezone(&Utc);
+
+        Ok(FixtureProvider { dir, recorded_at })
+    }
+
+    fn read(&self, fixture: &str) -> Result<serde_json::Value, Box<dyn Error>> {
+        let path: PathBuf = self.dir.join(fixture);
+        let text: String = fs::read_to_string(&path).map_err(|e| format!("no fixture {}, {}", path.display(), e))?;
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+impl MarketDataProvider for FixtureProvider {
+
+    fn now(&self) -> DateTime<Utc> {
+        self.recorded_at
+    }
+
+    async fn expirations(&self, symbol: &str, call: bool) -> Result<Vec<NaiveDate>, Box<dyn Error>> {
+        parse_expirations(&self.read(&expirations_fixture(symbol, call))?)
+    }
+
+    async fn chain(&self, symbol: &str, expiration: NaiveDate, call: bool) -> Result<Vec<ChainQuote>, Box<dyn Error>> {
+        parse_chain(&self.read(&chain_fixture(symbol, expiration, call))?)
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<StockQuote, Box<dyn Error>> {
+        parse_quote(&self.read(&quote_fixture(symbol))?)
+    }
+
+    async fn candles(&self, symbol: &str, count: usize) -> Result<Vec<Bar>, Box<dyn Error>> {
+        let bars: Vec<Bar> = parse_candles(&self.read(&candles_fixture(symbol))?)?;
+        let today: NaiveDate = new_york_time(self.recorded_at).date();
+        Ok(latest(bars.into_iter().filter(|bar| bar.date <= today).collect(), count))
+    }
+
+    async fn treasury_yields(&self) -> Result<Vec<(f64, f64)>, Box<dyn Error>> {
+
+        let mut par_yields: Vec<(f64, f64)> = Vec::new();
+
+        for (tenor, series) in TREASURY_SERIES {
+            if let Some(rate) = parse_observation(&self.read(&treasury_fixture(series))?, series)? {
+                par_yields.push((tenor, rate));
+            }
+        }
+
+        Ok(par_yields)
+    }
+}
+
+
+
+// The provider picked on the command line, from the environment or in the config file
+pub enum Provider {
+    Http(HttpProvider),
+    Fixture(FixtureProvider),
+}
+
+impl Provider {
+
+    pub fn load(args: &ProviderArgs) -> Result<Provider, Box<dyn Error>> {
+
+        let config: Config = Config::load()?;
+
+        // A fixtures directory given on the command line or in the environment means replay it, unless http was asked for as well
+        let chosen: Option<ProviderType> = match (args.provider, &args.fixtures) {
+            (Some(ProviderType::Http), Some(dir)) => return Err(format!("--fixtures {} only applies to the fixture provider, use --record to save live responses", dir.display()).into()),
+            (None, Some(_)) => Some(ProviderType::Fixture),
+            (provider, _) => provider,
+        };
+
+        match chosen.or(config.provider()?).unwrap_or(ProviderType::Http) {
+            ProviderType::Http => Ok(Provider::Http(HttpProvider::new(Credentials::load(&config), args.record.clone())?)),
+            ProviderType::Fixture => {
+                let fixtures: PathBuf = args.fixtures.clone().or(config.provider.fixtures).unwrap_or(PathBuf::from("fixtures"));
+                Ok(Provider::Fixture(FixtureProvider::new(fixtures)?))
+            }
+        }
+    }
+
+    // Short description of where the data comes from
+    pub fn source(&self) -> String {
+        match self {
+            Provider::Http(_) => "live".to_string(),
+            Provider::Fixture(provider) => format!("fixtures in {}", provider.dir.display()),
+        }
+    }
+}
+
+impl MarketDataProvider for Provider {
+
+    fn now(&self) -> DateTime<Utc> {
+        match self {
+            Provider::Http(provider) => provider.now(),
+            Provider::Fixture(provider) => provider.now(),
+        }
+    }
+
+    async fn expirations(&self, symbol: &str, call: bool) -> Result<Vec<NaiveDate>, Box<dyn Error>> {
+        match self {
+            Provider::Http(provider) => provider.expirations(symbol, call).await,
+            Provider::Fixture(provider) => provider.expirations(symbol, call).await,
+        }
+    }
+
+    async fn chain(&self, symbol: &str, expiration: NaiveDate, call: bool) -> Result<Vec<ChainQuote>, Box<dyn Error>> {
+        match self {
+            Provider::Http(provider) => provider.chain(symbol, expiration, call).await,
+            Provider::Fixture(provider) => provider.chain(symbol, expiration, call).await,
+        }
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<StockQuote, Box<dyn Error>> {
+        match self {
+            Provider::Http(provider) => provider.quote(symbol).await,
+            Provider::Fixture(provider) => provider.quote(symbol).await,
+        }
+    }
+
+    async fn candles(&self, symbol: &str, count: usize) -> Result<Vec<Bar>, Box<dyn Error>> {
+        match self {
+            Provider::Http(provider) => provider.candles(symbol, count).await,
+            Provider::Fixture(provider) => provider.candles(symbol, count).await,
+        }
+    }
+
+    async fn treasury_yields(&self) -> Result<Vec<(f64, f64)>, Box<dyn Error>> {
+        match self {
+            Provider::Http(provider) => provider.treasury_yields().await,
+            Provider::Fixture(provider) => provider.treasury_yields().await,
+        }
+    }
+}
+
+
+
+fn side(call: bool) -> &'static str {
+    if call { "call" } else { "put" }
+}
+
+fn expirations_fixture(symbol: &str, call: bool) -> String {
+    format!("{}/expirations_{}.json", symbol.to_uppercase(), side(call))
+}
+
+fn chain_fixture(symbol: &str, expiration: NaiveDate, call: bool) -> String {
+    format!("{}/chain_{}_{}.json", symbol.to_uppercase(), side(call), expiration)
+}
+
+fn quote_fixture(symbol: &str) -> String {
+    format!("{}/quote.json", symbol.to_uppercase())
+}
+
+fn candles_fixture(symbol: &str) -> String {
+    format!("{}/candles.json", symbol.to_uppercase())
+}
+
+fn treasury_fixture(series: &str) -> String {
+    format!("fred/{}.json", series)
+}
+
+
+
+// Expirations as a list of NaiveDate objects
+fn parse_expirations(json: &serde_json::Value) -> Result<Vec<NaiveDate>, Box<dyn Error>> {
+
+    let expirations = json["expirations"]
+        .as_array()
+        .ok_or("Invalid expiration data")?
+        .iter()
+        .filter_map(|d| d.as_str())
+        .filter_map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+        .collect();
+
+    Ok(expirations)
+}
+
+// Every option in a chain, skipping strikes without an iv
+    // The response is column oriented, one array per field with an entry per option
+fn parse_chain(json: &serde_json::Value) -> Result<Vec<ChainQuote>, Box<dyn Error>> {
+
+    let strikes = json["strike"]
+        .as_array()
+        .ok_or("Missing options strike data")?;
+
+    if !json["iv"].is_array() {
+        return Err("Missing options iv data".into());
+    }
+
+    let field = |name: &str, i: usize| json[name].get(i).and_then(|value| value.as_f64());
+    let count = |name: &str, i: usize| json[name].get(i).and_then(|value| value.as_u64()).unwrap_or(0);
+
+    let chain: Vec<ChainQuote> = (0..strikes.len())
+        .filter_map(|i| Some(ChainQuote {
+            option_symbol: json["optionSymbol"].get(i).and_then(|value| value.as_str()).unwrap_or("").to_string(),
+            strike: field("strike", i)?,
+            volatility: field("iv", i)?,
+            bid: field("bid", i),
+            ask: field("ask", i),
+            mid: field("mid", i),
+            last: field("last", i),
+            volume: count("volume", i),
+            open_interest: count("openInterest", i),
+            greeks: VendorGreeks { delta: field("delta", i), gamma: field("gamma", i), vega: field("vega", i), theta: field("theta", i) },
+            updated: timestamp(&json["updated"][i]),
+        }))
+        .collect();
+
+    if chain.is_empty() {
+        return Err("Empty option chain".into());
+    }
+
+    Ok(chain)
+}
+
+// Last traded price, bid and ask from a stock quote
+fn parse_quote(json: &serde_json::Value) -> Result<StockQuote, Box<dyn Error>> {
+
+    let last: f64 = json["last"][0].as_f64().ok_or("Missing spot/last price")?;
+
+    Ok(StockQuote {
+        last,
+        bid: json["bid"][0].as_f64(),
+        ask: json["ask"][0].as_f64(),
+        updated: timestamp(&json["updated"][0]),
+    })
+}
+
+// Daily bars from column-oriented candles, dated by the New York trading day they open on
+fn parse_candles(json: &serde_json::Value) -> Result<Vec<Bar>, Box<dyn Error>> {
+
+    let times = json["t"]
+        .as_array()
+        .ok_or("Missing candle data")?;
+
+    let field = |name: &str, i: usize| json[name].get(i).and_then(|value| value.as_f64());
+
+    let mut bars: Vec<Bar> = (0..times.len())
+        .filter_map(|i| Some(Bar {
+            date: new_york_time(timestamp(&times[i])?).date(),
+            open: field("o", i)?,
+            high: field("h", i)?,
+            low: field("l", i)?,
+            close: field("c", i)?,
+        }))
+        .collect();
+    bars.sort_by_key(|bar| bar.date);
+
+    if bars.is_empty() {
+        return Err("No candles".into());
+    }
+
+    Ok(bars)
+}
+
+// The last count bars
+fn latest(mut bars: Vec<Bar>, count: usize) -> Vec<Bar> {
+    let start: usize = bars.len().saturating_sub(count);
+    bars.drain(..start);
+    bars
+}
+
+// Unix timestamp in seconds
+fn timestamp(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(value.as_i64()?, 0)
+}
+
+// Latest value of a FRED series in decimals
+    // FRED marks missing observations with a dot, so those come back as None
+fn parse_observation(json: &serde_json::Value, series: &str) -> Result<Option<f64>, Box<dyn Error>> {
+
+    let observations = json["observations"]
+        .as_array()
+        .ok_or(format!("Missing {} observations", series))?;
+
+    let rate_str = observations.first().and_then(|o| o["value"].as_str()).unwrap_or(".");
+
+    Ok(rate_str.parse::<f64>().ok().map(|rate| rate / 100.0))
+}
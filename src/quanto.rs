@@ -0,0 +1,51 @@
+/*
+
+JDA Options Pricing
+/src/quanto.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Modules
+use crate::black_scholes::generalized_black_scholes;
+
+
+
+// Struct to hold the FX market data needed for options on a foreign-listed underlying
+    // The exchange rate is quoted as units of domestic currency per unit of foreign currency
+#[derive(Debug, Clone, Copy)]
+pub struct FxMarket {
+    pub rate: f64,
+    pub volatility: f64,
+    pub foreign_rate: f64,
+    pub correlation: f64,
+}
+
+
+
+// Price a quanto option, whose foreign currency payoff is converted to domestic currency at a fixed exchange rate
+    // The strike is in foreign currency and the price is in domestic currency
+pub fn quanto_option(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, fx: &FxMarket, fixed_rate: f64, call: bool) -> f64 {
+
+    // The underlying drifts at the foreign rate less the quanto adjustment for its correlation with the exchange rate
+    let carry: f64 = fx.foreign_rate - fx.correlation * volatility * fx.volatility;
+
+    // The payoff is paid in domestic currency, so it is discounted at the domestic rate
+    return fixed_rate * generalized_black_scholes(spot, strike, time, rate, carry, volatility, call);
+}
+
+
+
+// Price a composite option, which pays the foreign underlying converted at the prevailing exchange rate less a domestic strike
+    // The price is in domestic currency
+pub fn composite_option(spot: f64, strike: f64, time: f64, rate: f64, volatility: f64, fx: &FxMarket, call: bool) -> f64 {
+
+    // The underlying in domestic currency is a domestic asset whose volatility combines the stock and the exchange rate
+    let domestic_spot: f64 = spot * fx.rate;
+    let domestic_volatility: f64 = (volatility.powi(2) + fx.volatility.powi(2) + 2.0 * fx.correlation * volatility * fx.volatility).sqrt();
+
+    // No dividends, so the domestic asset carries at the domestic rate
+    return generalized_black_scholes(domestic_spot, strike, time, rate, rate, domestic_volatility, call);
+}
@@ -0,0 +1,159 @@
+/*
+
+JDA Options Pricing
+/src/rate_options.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+// Modules
+use crate::vol_cube::VolCube;
+
+
+
+// How the forward rate is assumed to move
+    // Black-76 quotes lognormal vols, Bachelier quotes normal (basis point) vols and allows negative rates
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum RateModel {
+    Black,
+    Bachelier,
+}
+
+
+
+// Where vols come from, a single vol for every option or a cube by expiry, tenor and strike
+#[derive(Debug, Clone)]
+pub enum Volatility {
+    Flat(f64),
+    Cube(VolCube),
+}
+
+impl Volatility {
+
+    // Pick the vol source from a flat vol or a cube file, exactly one of which should be given
+    pub fn load(volatility: Option<f64>, cube: Option<String>) -> Result<Volatility, String> {
+        match (volatility, cube) {
+            (Some(volatility), None) => Ok(Volatility::Flat(volatility)),
+            (None, Some(path)) => Ok(Volatility::Cube(VolCube::from_csv(&path)?)),
+            _ => Err("give either a volatility or a vol cube file".to_string()),
+        }
+    }
+
+    pub fn volatility(&self, expiry: f64, tenor: f64, strike: f64) -> f64 {
+        match self {
+            Volatility::Flat(volatility) => *volatility,
+            Volatility::Cube(cube) => cube.volatility(expiry, tenor, strike),
+        }
+    }
+}
+
+
+
+// Struct to hold one caplet (or floorlet) of a cap (or floor)
+#[derive(Debug, Clone, Copy)]
+pub struct Caplet {
+    pub fixing: f64,
+    pub payment: f64,
+    pub forward: f64,
+    pub volatility: f64,
+    pub price: f64,
+    pub vega: f64,
+}
+
+// Struct to hold a priced swaption
+#[derive(Debug, Clone, Copy)]
+pub struct Swaption {
+    pub swap_rate: f64,
+    pub annuity: f64,
+    pub volatility: f64,
+    pub price: f64,
+    pub vega: f64,
+}
+
+
+
+// Undiscounted price and vega of a call (or put) on a forward rate expiring at expiry
+pub fn forward_option(forward: f64, strike: f64, expiry: f64, volatility: f64, model: RateModel, call: bool) -> Result<(f64, f64), String> {
+
+    let standard_normal = Normal::standard();
+    let sign: f64 = if call { 1.0 } else { -1.0 };
+
+    // Options that have already fixed are worth their intrinsic value
+    if expiry <= 0.0 || volatility <= 0.0 {
+        return Ok(((sign * (forward - strike)).max(0.0), 0.0));
+    }
+
+    let deviation: f64 = volatility * expiry.sqrt();
+
+    match model {
+        RateModel::Black => {
+            if forward <= 0.0 || strike <= 0.0 {
+                return Err("Black-76 needs positive forwards and strikes, use the bachelier model".to_string());
+            }
+            let d1: f64 = ((forward / strike).ln() + 0.5 * deviation.powi(2)) / deviation;
+            let d2: f64 = d1 - deviation;
+            let price: f64 = sign * (forward * standard_normal.cdf(sign * d1) - strike * standard_normal.cdf(sign * d2));
+            return Ok((price, forward * standard_normal.pdf(d1) * expiry.sqrt()));
+        }
+        RateModel::Bachelier => {
+            let d: f64 = (forward - strike) / deviation;
+            let price: f64 = sign * (forward - strike) * standard_normal.cdf(sign * d) + deviation * standard_normal.pdf(d);
+            return Ok((price, standard_normal.pdf(d) * expiry.sqrt()));
+        }
+    }
+}
+
+
+
+// Price a cap (or floor) as a strip of caplets on the simple forward rate of each period
+    // The first period's rate is already fixed today, so caplets start from the second period
+pub fn cap_floor<D: Fn(f64) -> f64>(curve: D, strike: f64, maturity: f64, frequency: u32, notional: f64, volatility: &Volatility, model: RateModel, cap: bool) -> Result<Vec<Caplet>, String> {
+
+    let period: f64 = 1.0 / (frequency as f64);
+    let periods: u32 = (maturity * (frequency as f64)).round() as u32;
+
+    let mut caplets: Vec<Caplet> = Vec::new();
+
+    for i in 1..periods {
+
+        let fixing: f64 = (i as f64) * period;
+        let payment: f64 = fixing + period;
+
+        let discount: f64 = curve(payment);
+        let forward: f64 = (curve(fixing) / discount - 1.0) / period;
+        let vol: f64 = volatility.volatility(fixing, period, strike);
+
+        let (price, vega) = forward_option(forward, strike, fixing, vol, model, cap)?;
+
+        caplets.push(Caplet { fixing, payment, forward, volatility: vol, price: notional * period * discount * price, vega: notional * period * discount * vega });
+    }
+
+    return Ok(caplets);
+}
+
+
+
+// Price a payer (or receiver) swaption, an option at expiry to enter a swap paying (receiving) the strike for tenor years
+pub fn swaption<D: Fn(f64) -> f64>(curve: D, strike: f64, expiry: f64, tenor: f64, frequency: u32, notional: f64, volatility: &Volatility, model: RateModel, payer: bool) -> Result<Swaption, String> {
+
+    let period: f64 = 1.0 / (frequency as f64);
+    let periods: u32 = (tenor * (frequency as f64)).round() as u32;
+    if periods == 0 {
+        return Err("the swap needs at least one payment".to_string());
+    }
+
+    // Value today of receiving 1 a year on the fixed leg, and the rate that makes the swap worth nothing
+    let annuity: f64 = (1..=periods).map(|i| period * curve(expiry + (i as f64) * period)).sum();
+    let swap_rate: f64 = (curve(expiry) - curve(expiry + (periods as f64) * period)) / annuity;
+    let vol: f64 = volatility.volatility(expiry, tenor, strike);
+
+    let (price, vega) = forward_option(swap_rate, strike, expiry, vol, model, payer)?;
+
+    return Ok(Swaption { swap_rate, annuity, volatility: vol, price: notional * annuity * price, vega: notional * annuity * vega });
+}
@@ -0,0 +1,261 @@
+/*
+
+JDA Options Pricing
+/src/short_rate.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+use statrs::function::gamma::ln_gamma;
+
+// Modules
+use crate::bond::Bond;
+use crate::solver::bisection;
+
+
+
+// The one factor short rate models
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ShortRateType {
+    Vasicek,
+    Cir,
+    HullWhite,
+}
+
+
+
+// A one factor short rate model with closed form zero coupon bond prices and bond options
+pub trait ShortRateModel {
+
+    // Price at a future time of a zero coupon bond paying 1 at maturity, given the short rate at that time
+    fn zero_coupon_bond(&self, rate: f64, time: f64, maturity: f64) -> f64;
+
+    // Price today of a zero coupon bond paying 1 at maturity
+    fn discount(&self, maturity: f64) -> f64;
+
+    // Price today of a European option expiring at expiry on a zero coupon bond paying 1 at maturity
+    fn zero_bond_option(&self, strike: f64, expiry: f64, maturity: f64, call: bool) -> f64;
+}
+
+
+
+// Vasicek: dr = speed (mean - r) dt + volatility dW
+#[derive(Debug, Clone, Copy)]
+pub struct Vasicek {
+    pub rate: f64,
+    pub speed: f64,
+    pub mean: f64,
+    pub volatility: f64,
+}
+
+impl ShortRateModel for Vasicek {
+
+    fn zero_coupon_bond(&self, rate: f64, time: f64, maturity: f64) -> f64 {
+
+        let (a, sigma) = (self.speed, self.volatility);
+        let tau: f64 = maturity - time;
+        let b: f64 = decay(a, tau);
+        let log_a: f64 = (self.mean - sigma.powi(2) / (2.0 * a.powi(2))) * (b - tau) - sigma.powi(2) * b.powi(2) / (4.0 * a);
+
+        return (log_a - b * rate).exp();
+    }
+
+    fn discount(&self, maturity: f64) -> f64 {
+        return self.zero_coupon_bond(self.rate, 0.0, maturity);
+    }
+
+    fn zero_bond_option(&self, strike: f64, expiry: f64, maturity: f64, call: bool) -> f64 {
+        let volatility: f64 = gaussian_bond_volatility(self.speed, self.volatility, expiry, maturity);
+        return gaussian_bond_option(self.discount(expiry), self.discount(maturity), strike, volatility, call);
+    }
+}
+
+
+
+// Hull-White: dr = (theta(t) - speed r) dt + volatility dW, with theta fitted so the model reprices today's discount curve
+pub struct HullWhite<D: Fn(f64) -> f64> {
+    pub speed: f64,
+    pub volatility: f64,
+    pub curve: D,
+}
+
+impl<D: Fn(f64) -> f64> HullWhite<D> {
+
+    // Instantaneous forward rate of the curve, by differencing the log discount factors
+    pub fn forward(&self, time: f64) -> f64 {
+        let step: f64 = 1e-4;
+        let low: f64 = (time - step).max(0.0);
+        let high: f64 = time + step;
+        return -1.0 * ((self.curve)(high).ln() - (self.curve)(low).ln()) / (high - low);
+    }
+}
+
+impl<D: Fn(f64) -> f64> ShortRateModel for HullWhite<D> {
+
+    fn zero_coupon_bond(&self, rate: f64, time: f64, maturity: f64) -> f64 {
+
+        let (a, sigma) = (self.speed, self.volatility);
+        let b: f64 = decay(a, maturity - time);
+        let log_a: f64 = ((self.curve)(maturity) / (self.curve)(time)).ln() + b * self.forward(time)
+            - sigma.powi(2) / (4.0 * a) * (1.0 - (-2.0 * a * time).exp()) * b.powi(2);
+
+        return (log_a - b * rate).exp();
+    }
+
+    fn discount(&self, maturity: f64) -> f64 {
+        return (self.curve)(maturity);
+    }
+
+    fn zero_bond_option(&self, strike: f64, expiry: f64, maturity: f64, call: bool) -> f64 {
+        let volatility: f64 = gaussian_bond_volatility(self.speed, self.volatility, expiry, maturity);
+        return gaussian_bond_option(self.discount(expiry), self.discount(maturity), strike, volatility, call);
+    }
+}
+
+
+
+// Cox-Ingersoll-Ross: dr = speed (mean - r) dt + volatility sqrt(r) dW, which keeps rates positive
+#[derive(Debug, Clone, Copy)]
+pub struct Cir {
+    pub rate: f64,
+    pub speed: f64,
+    pub mean: f64,
+    pub volatility: f64,
+}
+
+impl Cir {
+
+    fn gamma(&self) -> f64 {
+        return (self.speed.powi(2) + 2.0 * self.volatility.powi(2)).sqrt();
+    }
+
+    // The A and B of P = A exp(-B r) over a period of length tau
+    fn affine(&self, tau: f64) -> (f64, f64) {
+
+        let (a, sigma, h) = (self.speed, self.volatility, self.gamma());
+        let growth: f64 = (h * tau).exp() - 1.0;
+        let denominator: f64 = 2.0 * h + (a + h) * growth;
+
+        let big_a: f64 = (2.0 * h * ((a + h) * tau / 2.0).exp() / denominator).powf(2.0 * a * self.mean / sigma.powi(2));
+        let big_b: f64 = 2.0 * growth / denominator;
+
+        return (big_a, big_b);
+    }
+}
+
+impl ShortRateModel for Cir {
+
+    fn zero_coupon_bond(&self, rate: f64, time: f64, maturity: f64) -> f64 {
+        let (big_a, big_b) = self.affine(maturity - time);
+        return big_a * (-1.0 * big_b * rate).exp();
+    }
+
+    fn discount(&self, maturity: f64) -> f64 {
+        return self.zero_coupon_bond(self.rate, 0.0, maturity);
+    }
+
+    fn zero_bond_option(&self, strike: f64, expiry: f64, maturity: f64, call: bool) -> f64 {
+
+        let (a, sigma, h) = (self.speed, self.volatility, self.gamma());
+        let (big_a, big_b) = self.affine(maturity - expiry);
+
+        let rho: f64 = 2.0 * h / (sigma.powi(2) * ((h * expiry).exp() - 1.0));
+        let psi: f64 = (a + h) / sigma.powi(2);
+        let dof: f64 = 4.0 * a * self.mean / sigma.powi(2);
+
+        // The option ends in the money when the short rate at expiry is below the critical rate
+        let critical: f64 = (big_a / strike).ln() / big_b;
+        let shift: f64 = 2.0 * rho.powi(2) * self.rate * (h * expiry).exp();
+
+        let call_value: f64 = self.discount(maturity) * noncentral_chi_squared_cdf(2.0 * critical * (rho + psi + big_b), dof, shift / (rho + psi + big_b))
+            - strike * self.discount(expiry) * noncentral_chi_squared_cdf(2.0 * critical * (rho + psi), dof, shift / (rho + psi));
+
+        // Puts follow from put-call parity on the zero coupon bond
+        if call {
+            return call_value;
+        } else {
+            return call_value - self.discount(maturity) + strike * self.discount(expiry);
+        }
+    }
+}
+
+
+
+// Price a European option on a coupon bond by splitting it into options on each remaining cash flow (Jamshidian)
+    // The strike is on the full price of the bond at expiry
+pub fn coupon_bond_option<M: ShortRateModel>(model: &M, bond: &Bond, strike: f64, expiry: f64, call: bool) -> Result<f64, String> {
+
+    let flows: Vec<(f64, f64)> = bond.cash_flows_after(expiry);
+    if flows.is_empty() {
+        return Err("the bond has no cash flows after the option expiry".to_string());
+    }
+
+    // Short rate at expiry at which the bond is worth exactly the strike
+    let bond_at_expiry = |rate: f64| flows.iter().map(|(t, amount)| amount * model.zero_coupon_bond(rate, expiry, *t)).sum::<f64>();
+    let critical: f64 = bisection(|rate| bond_at_expiry(rate) - strike, -2.0, 2.0, 1e-12)
+        .ok_or("the strike is out of reach of the bond price".to_string())?;
+
+    // Each cash flow gets an option struck at its value at the critical rate
+    return Ok(flows.iter()
+        .map(|(t, amount)| amount * model.zero_bond_option(model.zero_coupon_bond(critical, expiry, *t), expiry, *t, call))
+        .sum());
+}
+
+
+
+// Share of a unit shock still felt after tau years of mean reversion at the given speed, integrated over the period
+fn decay(speed: f64, tau: f64) -> f64 {
+    return (1.0 - (-1.0 * speed * tau).exp()) / speed;
+}
+
+
+
+// Volatility of the log price at expiry of a zero coupon bond maturing later, in a gaussian short rate model
+fn gaussian_bond_volatility(speed: f64, volatility: f64, expiry: f64, maturity: f64) -> f64 {
+    return volatility * decay(speed, maturity - expiry) * ((1.0 - (-2.0 * speed * expiry).exp()) / (2.0 * speed)).sqrt();
+}
+
+
+
+// Black style price of an option on a zero coupon bond whose log price is normal
+fn gaussian_bond_option(discount_expiry: f64, discount_maturity: f64, strike: f64, volatility: f64, call: bool) -> f64 {
+
+    let standard_normal = Normal::standard();
+
+    let h: f64 = (discount_maturity / (discount_expiry * strike)).ln() / volatility + 0.5 * volatility;
+
+    if call {
+        return discount_maturity * standard_normal.cdf(h) - strike * discount_expiry * standard_normal.cdf(h - volatility);
+    } else {
+        return strike * discount_expiry * standard_normal.cdf(-1.0 * h + volatility) - discount_maturity * standard_normal.cdf(-1.0 * h);
+    }
+}
+
+
+
+// Noncentral chi-squared distribution function, as a Poisson weighted mixture of central chi-squared distributions
+fn noncentral_chi_squared_cdf(x: f64, dof: f64, noncentrality: f64) -> f64 {
+
+    if x <= 0.0 {
+        return 0.0;
+    }
+
+    let half: f64 = 0.5 * noncentrality;
+
+    // Cover the Poisson weights well past their mode
+    let terms: u32 = (half + 12.0 * half.sqrt() + 50.0).ceil() as u32;
+
+    return (0..terms).map(|j| {
+        let j: f64 = j as f64;
+        let weight: f64 = if half > 0.0 { (-1.0 * half + j * half.ln() - ln_gamma(j + 1.0)).exp() } else if j == 0.0 { 1.0 } else { 0.0 };
+        match ChiSquared::new(dof + 2.0 * j) {
+            Ok(chi) => weight * chi.cdf(x),
+            Err(_) => 0.0,
+        }
+    }).sum();
+}
@@ -0,0 +1,172 @@
+/*
+
+JDA Options Pricing
+/src/smile.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+// Modules
+use crate::svi::Svi;
+
+
+
+// How vols are interpolated between listed strikes
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SmileMethod {
+    // Straight lines between neighbouring quotes
+    Linear,
+    // Natural cubic spline through every quote
+    Cubic,
+    // Raw SVI fit to every quote (always in log-moneyness)
+    Svi,
+}
+
+// Coordinate the smile is interpolated in
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SmileAxis {
+    // ln(strike / forward)
+    LogMoneyness,
+    // Undiscounted Black-Scholes call delta at each strike's own vol
+    Delta,
+}
+
+
+
+// Result of reading a vol off the smile
+#[derive(Debug, Clone)]
+pub struct SmileVolatility {
+    pub volatility: f64,
+    // Listed (strike, vol) quotes that set the vol
+    pub quotes: Vec<(f64, f64)>,
+    // Root mean square error of the fitted vols for SVI
+    pub fit_error: Option<f64>,
+}
+
+
+
+// Implied vol at any strike from the listed (strike, vol) quotes of one expiry, held flat beyond the listed strikes
+pub fn smile_volatility(quotes: &[(f64, f64)], strike: f64, forward: f64, time: f64, method: SmileMethod, axis: SmileAxis) -> Result<SmileVolatility, String> {
+
+    let mut quotes: Vec<(f64, f64)> = quotes.iter().copied().filter(|(k, v)| *k > 0.0 && *v > 0.0 && v.is_finite()).collect();
+    quotes.sort_by(|a, b| a.0.total_cmp(&b.0));
+    quotes.dedup_by(|a, b| a.0 == b.0);
+
+    if quotes.len() < 2 {
+        return Err("the smile needs at least two quotes with a vol".to_string());
+    }
+    if strike <= 0.0 || forward <= 0.0 || time <= 0.0 {
+        return Err("the strike, forward and time must be positive".to_string());
+    }
+
+    let log_moneyness = |k: f64| (k / forward).ln();
+
+    // SVI is fit to every quote in total variance
+    if method == SmileMethod::Svi {
+        if axis == SmileAxis::Delta {
+            return Err("SVI is fit in log-moneyness, use --smile-axis log-moneyness".to_string());
+        }
+        let points: Vec<(f64, f64)> = quotes.iter().map(|(k, v)| (log_moneyness(*k), v * v * time)).collect();
+        let (svi, _) = Svi::fit(&points)?;
+        let fit_error: f64 = (quotes.iter().map(|(k, v)| (svi.volatility(log_moneyness(*k), time) - v).powi(2)).sum::<f64>() / (quotes.len() as f64)).sqrt();
+        return Ok(SmileVolatility { volatility: svi.volatility(log_moneyness(strike), time), quotes, fit_error: Some(fit_error) });
+    }
+
+    // Each quote's position on the axis, in increasing order
+    let delta = |k: f64, v: f64| Normal::standard().cdf(((forward / k).ln() + 0.5 * v * v * time) / (v * time.sqrt()));
+    let mut points: Vec<(f64, f64)> = quotes.iter().map(|(k, v)| match axis {
+        SmileAxis::LogMoneyness => (log_moneyness(*k), *v),
+        SmileAxis::Delta => (delta(*k, *v), *v),
+    }).collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let curve = |x: f64| match method {
+        SmileMethod::Cubic => cubic_spline(&points, x),
+        _ => linear(&points, x),
+    };
+
+    // A strike's delta depends on its own vol, so iterate the two to a fixed point
+    let volatility: f64 = match axis {
+        SmileAxis::LogMoneyness => curve(log_moneyness(strike)),
+        SmileAxis::Delta => {
+            let mut vol: f64 = linear(&quotes, strike);
+            for _ in 0..50 {
+                let next: f64 = curve(delta(strike, vol));
+                if (next - vol).abs() < 1e-10 { vol = next; break; }
+                vol = next;
+            }
+            vol
+        }
+    };
+
+    // Linear interpolation only uses the two listed strikes either side, the spline uses all of them
+    let used: Vec<(f64, f64)> = match method {
+        SmileMethod::Linear => {
+            let above: usize = quotes.iter().position(|(k, _)| *k >= strike).unwrap_or(quotes.len() - 1);
+            quotes[above.saturating_sub(1)..=above].to_vec()
+        }
+        _ => quotes,
+    };
+
+    return Ok(SmileVolatility { volatility, quotes: used, fit_error: None });
+}
+
+
+
+// Piecewise linear through sorted points, flat beyond the ends
+fn linear(points: &[(f64, f64)], x: f64) -> f64 {
+
+    let last: usize = points.len() - 1;
+    if x <= points[0].0 { return points[0].1; }
+    if x >= points[last].0 { return points[last].1; }
+
+    let upper: usize = points.iter().position(|p| p.0 > x).unwrap_or(last);
+    let (x0, y0) = points[upper - 1];
+    let (x1, y1) = points[upper];
+
+    return y0 + (y1 - y0) * (x - x0) / (x1 - x0);
+}
+
+
+
+// Natural cubic spline through sorted points, flat beyond the ends
+fn cubic_spline(points: &[(f64, f64)], x: f64) -> f64 {
+
+    let n: usize = points.len();
+    if n < 3 || x <= points[0].0 || x >= points[n - 1].0 {
+        return linear(points, x);
+    }
+
+    // Second derivatives at the knots from the tridiagonal system (zero at both ends)
+    let h: Vec<f64> = (0..n - 1).map(|i| points[i + 1].0 - points[i].0).collect();
+    let mut second: Vec<f64> = vec![0.0; n];
+    let mut diagonal: Vec<f64> = vec![1.0; n];
+    let mut rhs: Vec<f64> = vec![0.0; n];
+
+    for i in 1..n - 1 {
+        diagonal[i] = 2.0 * (h[i - 1] + h[i]);
+        rhs[i] = 6.0 * ((points[i + 1].1 - points[i].1) / h[i] - (points[i].1 - points[i - 1].1) / h[i - 1]);
+    }
+
+    // Forward sweep then back substitution
+    for i in 2..n - 1 {
+        let factor: f64 = h[i - 1] / diagonal[i - 1];
+        diagonal[i] -= factor * h[i - 1];
+        rhs[i] -= factor * rhs[i - 1];
+    }
+    for i in (1..n - 1).rev() {
+        second[i] = (rhs[i] - h[i] * second[i + 1]) / diagonal[i];
+    }
+
+    let i: usize = points.iter().position(|p| p.0 > x).unwrap_or(n - 1) - 1;
+    let t: f64 = (x - points[i].0) / h[i];
+
+    return (1.0 - t) * points[i].1 + t * points[i + 1].1
+        + h[i] * h[i] / 6.0 * (((1.0 - t).powi(3) - (1.0 - t)) * second[i] + (t.powi(3) - t) * second[i + 1]);
+}
@@ -0,0 +1,169 @@
+/*
+
+JDA Options Pricing
+/src/solver.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Maximum number of halvings before giving up on a root
+const MAX_ITERATIONS: u32 = 200;
+
+
+
+// Find a root of a continuous function inside [low, high] by bisection
+    // Returns None if the function does not change sign over the interval
+pub fn bisection<F>(f: F, mut low: f64, mut high: f64, tolerance: f64) -> Option<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut f_low: f64 = f(low);
+    let f_high: f64 = f(high);
+
+    if f_low == 0.0 { return Some(low); }
+    if f_high == 0.0 { return Some(high); }
+    if f_low.signum() == f_high.signum() { return None; }
+
+    for _ in 0..MAX_ITERATIONS {
+
+        let mid: f64 = 0.5 * (low + high);
+        let f_mid: f64 = f(mid);
+
+        if f_mid == 0.0 || (high - low) < tolerance { return Some(mid); }
+
+        // Keep the half where the sign change is
+        if f_mid.signum() == f_low.signum() {
+            low = mid;
+            f_low = f_mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    return Some(0.5 * (low + high));
+}
+
+
+
+// Find a positive root (such as a critical stock price) by growing a bracket out from a starting guess and then bisecting
+pub fn positive_root<F>(f: F, guess: f64, tolerance: f64) -> Option<f64>
+where
+    F: Fn(f64) -> f64,
+{
+    let mut low: f64 = guess;
+    let mut high: f64 = guess;
+
+    // Double the bracket each way until the sign changes
+    for _ in 0..60 {
+        low /= 2.0;
+        high *= 2.0;
+        if f(low).signum() != f(high).signum() {
+            return bisection(&f, low, high, tolerance);
+        }
+    }
+
+    return None;
+}
+
+
+
+// Solve a small linear system by Gaussian elimination with partial pivoting
+    // Returns None if the matrix is singular
+pub fn solve_linear<const N: usize>(mut matrix: [[f64; N]; N], mut vector: [f64; N]) -> Option<[f64; N]> {
+
+    for col in 0..N {
+
+        let pivot: usize = (col..N).max_by(|a, b| matrix[*a][col].abs().total_cmp(&matrix[*b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-14 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        vector.swap(col, pivot);
+
+        let pivot_row: [f64; N] = matrix[col];
+        for row in col + 1..N {
+            let factor: f64 = matrix[row][col] / pivot_row[col];
+            for (value, pivot_value) in matrix[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            vector[row] -= factor * vector[col];
+        }
+    }
+
+    let mut solution = [0.0; N];
+    for row in (0..N).rev() {
+        let sum: f64 = (row + 1..N).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (vector[row] - sum) / matrix[row][row];
+    }
+
+    return Some(solution);
+}
+
+
+
+// Minimise a function of several variables with the Nelder-Mead simplex, starting from a point and initial step sizes
+    // Returns the best point found and the value there, stopping when the simplex values agree to within tolerance
+pub fn nelder_mead<F>(f: F, start: &[f64], step: &[f64], tolerance: f64, max_iterations: u32) -> (Vec<f64>, f64)
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let n: usize = start.len();
+
+    // Simplex of n + 1 points around the start
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::new();
+    simplex.push((start.to_vec(), f(start)));
+    for i in 0..n {
+        let mut point: Vec<f64> = start.to_vec();
+        point[i] += step[i];
+        let value: f64 = f(&point);
+        simplex.push((point, value));
+    }
+
+    // Point along the line from the centroid through a vertex
+    let along = |centroid: &[f64], vertex: &[f64], t: f64| -> Vec<f64> {
+        centroid.iter().zip(vertex).map(|(c, v)| c + t * (v - c)).collect()
+    };
+
+    for _ in 0..max_iterations {
+
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if (simplex[n].1 - simplex[0].1).abs() <= tolerance * (simplex[0].1.abs() + tolerance) {
+            break;
+        }
+
+        let centroid: Vec<f64> = (0..n).map(|j| simplex[..n].iter().map(|(p, _)| p[j]).sum::<f64>() / (n as f64)).collect();
+        let worst: Vec<f64> = simplex[n].0.clone();
+
+        // Reflect the worst point, then try expanding further or contracting back
+        let reflected: Vec<f64> = along(&centroid, &worst, -1.0);
+        let reflected_value: f64 = f(&reflected);
+
+        if reflected_value < simplex[0].1 {
+            let expanded: Vec<f64> = along(&centroid, &worst, -2.0);
+            let expanded_value: f64 = f(&expanded);
+            simplex[n] = if expanded_value < reflected_value { (expanded, expanded_value) } else { (reflected, reflected_value) };
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted: Vec<f64> = if reflected_value < simplex[n].1 { along(&centroid, &worst, -0.5) } else { along(&centroid, &worst, 0.5) };
+            let contracted_value: f64 = f(&contracted);
+
+            if contracted_value < reflected_value.min(simplex[n].1) {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                // Shrink everything towards the best point
+                let best: Vec<f64> = simplex[0].0.clone();
+                for vertex in simplex.iter_mut().skip(1) {
+                    let point: Vec<f64> = along(&best, &vertex.0, 0.5);
+                    let value: f64 = f(&point);
+                    *vertex = (point, value);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    return simplex.swap_remove(0);
+}
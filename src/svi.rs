@@ -0,0 +1,118 @@
+/*
+
+JDA Options Pricing
+/src/svi.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Modules
+use crate::solver::{nelder_mead, solve_linear};
+
+
+
+// Raw SVI (stochastic volatility inspired) smile for one expiry, in total implied variance w = vol^2 * T against log-moneyness k = ln(K / F)
+    // w(k) = a + b * (rho * (k - m) + sqrt((k - m)^2 + sigma^2))
+#[derive(Debug, Clone, Copy)]
+pub struct Svi {
+    pub a: f64,
+    pub b: f64,
+    pub rho: f64,
+    pub m: f64,
+    pub sigma: f64,
+}
+
+impl Svi {
+
+    pub fn total_variance(&self, k: f64) -> f64 {
+        return self.a + self.b * (self.rho * (k - self.m) + ((k - self.m).powi(2) + self.sigma.powi(2)).sqrt());
+    }
+
+    // Implied vol at log-moneyness k for an expiry time years away
+    pub fn volatility(&self, k: f64, time: f64) -> f64 {
+        return (self.total_variance(k).max(0.0) / time).sqrt();
+    }
+
+    // Total variance and its first two derivatives in k
+    pub fn derivatives(&self, k: f64) -> (f64, f64, f64) {
+        let root: f64 = ((k - self.m).powi(2) + self.sigma.powi(2)).sqrt();
+        let first: f64 = self.b * (self.rho + (k - self.m) / root);
+        let second: f64 = self.b * self.sigma.powi(2) / root.powi(3);
+        return (self.total_variance(k), first, second);
+    }
+
+    // Gatheral's g(k), which must be non-negative everywhere for the smile to be free of butterfly arbitrage
+        // The risk-neutral density of log-moneyness is g(k) / sqrt(2 pi w) * exp(-d2^2 / 2)
+    pub fn butterfly(&self, k: f64) -> f64 {
+        let (w, first, second) = self.derivatives(k);
+        return (1.0 - k * first / (2.0 * w)).powi(2) - first.powi(2) / 4.0 * (1.0 / w + 0.25) + second / 2.0;
+    }
+
+    // Whether the parameters give a smile with non-negative variance everywhere
+    pub fn valid(&self) -> bool {
+        return self.b >= 0.0 && self.rho.abs() < 1.0 && self.sigma > 0.0 && self.a + self.b * self.sigma * (1.0 - self.rho.powi(2)).sqrt() >= 0.0;
+    }
+
+    // Fit to (log-moneyness, total variance) points by least squares, returning the smile and the root mean square error in total variance
+        // a, b * rho and b enter linearly, so they are solved exactly on a grid of (m, sigma) and the best grid point is refined with Nelder-Mead
+    pub fn fit(points: &[(f64, f64)]) -> Result<(Svi, f64), String> {
+
+        if points.len() < 5 {
+            return Err("an SVI fit needs at least five quotes".to_string());
+        }
+
+        let low: f64 = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+        let high: f64 = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+        let width: f64 = (high - low).max(0.01);
+
+        let error = |svi: &Svi| -> f64 { points.iter().map(|(k, w)| (svi.total_variance(*k) - w).powi(2)).sum() };
+
+        let mut best: Option<(f64, Svi)> = None;
+        for i in 0..25 {
+            let m: f64 = low - 0.25 * width + 1.5 * width * (i as f64) / 24.0;
+            for j in 0..20 {
+                let sigma: f64 = 0.002 * (500.0_f64).powf((j as f64) / 19.0) * width;
+
+                // Normal equations for w = a + d * (k - m) + c * sqrt((k - m)^2 + sigma^2)
+                let mut matrix = [[0.0; 3]; 3];
+                let mut vector = [0.0; 3];
+                for (k, w) in points {
+                    let basis = [1.0, k - m, ((k - m).powi(2) + sigma.powi(2)).sqrt()];
+                    for r in 0..3 {
+                        vector[r] += basis[r] * w;
+                        for c in 0..3 {
+                            matrix[r][c] += basis[r] * basis[c];
+                        }
+                    }
+                }
+
+                let Some([a, d, c]) = solve_linear(matrix, vector) else { continue; };
+                if c <= 0.0 { continue; }
+
+                let svi = Svi { a, b: c, rho: (d / c).clamp(-0.999, 0.999), m, sigma };
+                if !svi.valid() { continue; }
+
+                let e: f64 = error(&svi);
+                if best.is_none_or(|(best_error, _)| e < best_error) {
+                    best = Some((e, svi));
+                }
+            }
+        }
+
+        let (_, start) = best.ok_or("unable to fit an SVI smile to the quotes".to_string())?;
+
+        // Refine all five parameters, with invalid smiles ruled out by a large penalty
+        let objective = |x: &[f64]| -> f64 {
+            let svi = Svi { a: x[0], b: x[1], rho: x[2], m: x[3], sigma: x[4] };
+            if svi.valid() { error(&svi) } else { 1e10 }
+        };
+        let scale: f64 = start.b.max(1e-4);
+        let (x, _) = nelder_mead(objective, &[start.a, start.b, start.rho, start.m, start.sigma], &[0.1 * scale, 0.1 * scale, 0.05, 0.05 * width, 0.1 * start.sigma], 1e-12, 2000);
+        let svi = Svi { a: x[0], b: x[1], rho: x[2], m: x[3], sigma: x[4] };
+
+        let fitted: Svi = if svi.valid() && error(&svi) <= error(&start) { svi } else { start };
+        return Ok((fitted, (error(&fitted) / (points.len() as f64)).sqrt()));
+    }
+}
@@ -0,0 +1,192 @@
+/*
+
+JDA Options Pricing
+/src/trinomial.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Modules
+use crate::binomial::Exercise;
+use crate::bond::Bond;
+
+
+
+// Hull-White trinomial tree for the short rate, fitted to today's discount curve
+    // Nodes sit at alpha(step) + j dx, with the branching switched at the edges so the tree stops widening (Hull-White 1994)
+#[derive(Debug, Clone)]
+pub struct HullWhiteTree {
+    pub dt: f64,
+    pub dx: f64,
+    pub steps: u32,
+    jmax: i64,
+    drift: f64,
+    alphas: Vec<f64>,
+}
+
+impl HullWhiteTree {
+
+    // Build the tree out to a given time, shifting each step so the tree reprices the curve's discount factors
+    pub fn new<D: Fn(f64) -> f64>(speed: f64, volatility: f64, curve: D, time: f64, steps: u32) -> Result<HullWhiteTree, String> {
+
+        if speed <= 0.0 {
+            return Err("the mean reversion speed must be positive to build the tree".to_string());
+        }
+        if steps == 0 || time <= 0.0 {
+            return Err("the tree needs at least one step and a positive maturity".to_string());
+        }
+
+        let dt: f64 = time / (steps as f64);
+
+        // Expected change and variance of the mean reverting part over one step
+        let drift: f64 = (-1.0 * speed * dt).exp() - 1.0;
+        let variance: f64 = volatility.powi(2) * (1.0 - (-2.0 * speed * dt).exp()) / (2.0 * speed);
+        let dx: f64 = (3.0 * variance).sqrt();
+
+        // Widest node before the branching turns inwards, which keeps every probability positive
+        let jmax: i64 = ((0.184 / (-1.0 * drift)).ceil() as i64).max(1);
+
+        let mut tree = HullWhiteTree { dt, dx, steps, jmax, drift, alphas: Vec::with_capacity(steps as usize) };
+
+        // Forward induction on the price of a security paying 1 at each node (Arrow-Debreu prices)
+        let mut prices: Vec<f64> = vec![1.0];
+
+        for step in 0..steps {
+
+            let width: i64 = tree.width(step);
+
+            // Shift the rates at this step so a zero coupon bond maturing at the next step is priced off the curve
+            let sum: f64 = (-width..=width).map(|j| prices[(j + width) as usize] * (-1.0 * (j as f64) * dx * dt).exp()).sum();
+            let alpha: f64 = (sum.ln() - curve((step + 1) as f64 * dt).ln()) / dt;
+            tree.alphas.push(alpha);
+
+            let next_width: i64 = tree.width(step + 1);
+            let mut next: Vec<f64> = vec![0.0; (2 * next_width + 1) as usize];
+
+            for j in -width..=width {
+                let discounted: f64 = prices[(j + width) as usize] * (-1.0 * tree.rate(step, j) * dt).exp();
+                for (k, probability) in tree.branches(j) {
+                    next[(k + next_width) as usize] += discounted * probability;
+                }
+            }
+
+            prices = next;
+        }
+
+        return Ok(tree);
+    }
+
+    // Highest node index at a step
+    fn width(&self, step: u32) -> i64 {
+        return (step as i64).min(self.jmax);
+    }
+
+    // The three nodes a node branches to and the probability of each
+    fn branches(&self, j: i64) -> [(i64, f64); 3] {
+
+        let m: f64 = self.drift;
+        let jm: f64 = (j as f64) * m;
+
+        if j >= self.jmax {
+            // Branch down at the top edge
+            return [(j, 7.0 / 6.0 + (jm * jm + 3.0 * jm) / 2.0), (j - 1, -1.0 / 3.0 - jm * jm - 2.0 * jm), (j - 2, 1.0 / 6.0 + (jm * jm + jm) / 2.0)];
+        }
+        if j <= -self.jmax {
+            // Branch up at the bottom edge
+            return [(j + 2, 1.0 / 6.0 + (jm * jm - jm) / 2.0), (j + 1, -1.0 / 3.0 - jm * jm + 2.0 * jm), (j, 7.0 / 6.0 + (jm * jm - 3.0 * jm) / 2.0)];
+        }
+
+        return [(j + 1, 1.0 / 6.0 + (jm * jm + jm) / 2.0), (j, 2.0 / 3.0 - jm * jm), (j - 1, 1.0 / 6.0 + (jm * jm - jm) / 2.0)];
+    }
+
+    // Short rate (over one step) at a node
+    pub fn rate(&self, step: u32, j: i64) -> f64 {
+        return self.alphas[step as usize] + (j as f64) * self.dx;
+    }
+
+    // Discounted expected value at every node of a step, from the values at the following step
+    pub fn roll_back(&self, step: u32, next: &[f64]) -> Vec<f64> {
+
+        let width: i64 = self.width(step);
+        let next_width: i64 = self.width(step + 1);
+
+        return (-width..=width).map(|j| {
+            let expected: f64 = self.branches(j).iter().map(|(k, probability)| probability * next[(k + next_width) as usize]).sum();
+            (-1.0 * self.rate(step, j) * self.dt).exp() * expected
+        }).collect();
+    }
+
+    // Step nearest to a time
+    pub fn step_at(&self, time: f64) -> u32 {
+        return ((time / self.dt).round() as u32).min(self.steps);
+    }
+}
+
+
+
+// Price a bond with an optional embedded call (issuer redeems at the call price) and put (holder sells back at the put price)
+    // Each is given as (price, first date) and can be exercised on any coupon date from then on, before maturity
+pub fn tree_bond(tree: &HullWhiteTree, bond: &Bond, call: Option<(f64, f64)>, put: Option<(f64, f64)>) -> f64 {
+
+    // The bond is redeemed at maturity either way, so calls and puts only matter on the dates before it
+    let schedule = |first: f64| Exercise::Bermudan(bond.coupon_dates_from(first).into_iter().filter(|t| *t < bond.maturity - 1e-9).collect());
+    let call = call.map(|(price, first)| (price, schedule(first)));
+    let put = put.map(|(price, first)| (price, schedule(first)));
+
+    let values = roll_back_bond(tree, bond, 0, |step, value| {
+        let mut value: f64 = value;
+        if let Some((price, exercise)) = &call && exercise.allowed(step, tree.dt) {
+            value = value.min(*price);
+        }
+        if let Some((price, exercise)) = &put && exercise.allowed(step, tree.dt) {
+            value = value.max(*price);
+        }
+        value
+    });
+
+    return values[0];
+}
+
+
+
+// Price a European option on a bond's full price at expiry by rolling the bond back to expiry, then the option back to today
+pub fn tree_bond_option(tree: &HullWhiteTree, bond: &Bond, strike: f64, expiry: f64, call: bool) -> f64 {
+
+    let expiry_step: u32 = tree.step_at(expiry);
+    let bond_values = roll_back_bond(tree, bond, expiry_step, |_, value| value);
+
+    let mut values: Vec<f64> = bond_values.iter().map(|value| if call { (value - strike).max(0.0) } else { (strike - value).max(0.0) }).collect();
+
+    for step in (0..expiry_step).rev() {
+        values = tree.roll_back(step, &values);
+    }
+
+    return values[0];
+}
+
+
+
+// Roll a bond's cash flows back through the tree to a given step, returning its value at each node there without the cash flows paid at that step
+    // The adjust closure is handed (step, value excluding that step's cash flow) at every node and may cap or floor it for embedded options
+fn roll_back_bond<A: Fn(u32, f64) -> f64>(tree: &HullWhiteTree, bond: &Bond, until: u32, adjust: A) -> Vec<f64> {
+
+    // Cash flows paid at each step, snapped to the nearest step
+    let mut flows: Vec<f64> = vec![0.0; (tree.steps + 1) as usize];
+    for (time, amount) in bond.cash_flows() {
+        flows[tree.step_at(time) as usize] += amount;
+    }
+
+    let top: i64 = tree.width(tree.steps);
+    let mut values: Vec<f64> = vec![flows[tree.steps as usize]; (2 * top + 1) as usize];
+
+    for step in (until..tree.steps).rev() {
+        values = tree.roll_back(step, &values).into_iter().map(|value| adjust(step, value)).collect();
+        if step > until {
+            values.iter_mut().for_each(|value| *value += flows[step as usize]);
+        }
+    }
+
+    return values;
+}
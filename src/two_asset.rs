@@ -0,0 +1,201 @@
+/*
+
+JDA Options Pricing
+/src/two_asset.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use statrs::distribution::{Normal, ContinuousCDF};
+
+// Modules
+use crate::bivariate::bivariate_normal_cdf;
+use crate::monte_carlo::{MonteCarloEstimate, monte_carlo_terminal};
+
+
+
+// Struct to hold market data on two correlated underlyings
+    // Carry is the risk free rate for a stock paying no dividends and zero for a futures contract
+#[derive(Debug, Clone, Copy)]
+pub struct AssetPair {
+    pub spot1: f64,
+    pub spot2: f64,
+    pub volatility1: f64,
+    pub volatility2: f64,
+    pub carry1: f64,
+    pub carry2: f64,
+    pub correlation: f64,
+}
+
+impl AssetPair {
+
+    // Forward prices of the two underlyings at a given time
+    pub fn forwards(&self, time: f64) -> (f64, f64) {
+        return (self.spot1 * (self.carry1 * time).exp(), self.spot2 * (self.carry2 * time).exp());
+    }
+
+    // Volatility of the ratio of the two underlyings
+    pub fn spread_volatility(&self) -> f64 {
+        return (self.volatility1.powi(2) + self.volatility2.powi(2) - 2.0 * self.correlation * self.volatility1 * self.volatility2).sqrt();
+    }
+
+    // Correlation matrix of the pair, for the Monte Carlo
+    pub fn correlation_matrix(&self) -> Vec<Vec<f64>> {
+        return vec![vec![1.0, self.correlation], vec![self.correlation, 1.0]];
+    }
+}
+
+
+
+// The payoffs on the better or worse performer of two underlyings
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Rainbow {
+    BestOf,
+    WorstOf,
+}
+
+
+
+// Price the option to exchange the second underlying for the first (Margrabe)
+pub fn exchange_option(pair: &AssetPair, time: f64, rate: f64) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let volatility: f64 = pair.spread_volatility();
+
+    let d1: f64 = ((pair.spot1 / pair.spot2).ln() + (pair.carry1 - pair.carry2 + 0.5 * volatility.powi(2)) * time) / (volatility * time.sqrt());
+    let d2: f64 = d1 - volatility * time.sqrt();
+
+    return pair.spot1 * ((pair.carry1 - rate) * time).exp() * standard_normal.cdf(d1)
+        - pair.spot2 * ((pair.carry2 - rate) * time).exp() * standard_normal.cdf(d2);
+}
+
+
+
+// Price an option on the spread between two underlyings (first less second) using Kirk's approximation
+    // The second leg plus the strike is treated as a single lognormal asset
+pub fn kirk_spread(pair: &AssetPair, strike: f64, time: f64, rate: f64, call: bool) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let (forward1, forward2) = pair.forwards(time);
+
+    let weight: f64 = forward2 / (forward2 + strike);
+    let ratio: f64 = forward1 / (forward2 + strike);
+    let volatility: f64 = (pair.volatility1.powi(2) + (pair.volatility2 * weight).powi(2) - 2.0 * pair.correlation * pair.volatility1 * pair.volatility2 * weight).sqrt();
+
+    let d1: f64 = (ratio.ln() + 0.5 * volatility.powi(2) * time) / (volatility * time.sqrt());
+    let d2: f64 = d1 - volatility * time.sqrt();
+
+    // Black-76 on the ratio, scaled back up by the denominator
+    let scale: f64 = (forward2 + strike) * (-1.0 * rate * time).exp();
+
+    if call {
+        return scale * (ratio * standard_normal.cdf(d1) - standard_normal.cdf(d2));
+    } else {
+        return scale * (standard_normal.cdf(-1.0 * d2) - ratio * standard_normal.cdf(-1.0 * d1));
+    }
+}
+
+
+
+// Price an option on the spread between two underlyings using the Bjerksund-Stensland approximation
+    // A sharper version of Kirk's idea that stays accurate for strikes far from zero
+pub fn bjerksund_stensland_spread(pair: &AssetPair, strike: f64, time: f64, rate: f64, call: bool) -> f64 {
+
+    let standard_normal = Normal::standard();
+    let n = |x: f64| standard_normal.cdf(x);
+    let (forward1, forward2) = pair.forwards(time);
+
+    let (s1, s2, rho) = (pair.volatility1, pair.volatility2, pair.correlation);
+    let a: f64 = forward2 + strike;
+    let b: f64 = forward2 / a;
+    let volatility: f64 = (s1.powi(2) - 2.0 * b * rho * s1 * s2 + b.powi(2) * s2.powi(2)).sqrt();
+    let vol_sqrt_t: f64 = volatility * time.sqrt();
+
+    let d1: f64 = ((forward1 / a).ln() + (0.5 * s1.powi(2) - b * rho * s1 * s2 + 0.5 * b.powi(2) * s2.powi(2)) * time) / vol_sqrt_t;
+    let d2: f64 = ((forward1 / a).ln() + (-0.5 * s1.powi(2) + rho * s1 * s2 + 0.5 * b.powi(2) * s2.powi(2) - b * s2.powi(2)) * time) / vol_sqrt_t;
+    let d3: f64 = ((forward1 / a).ln() + (-0.5 * s1.powi(2) + 0.5 * b.powi(2) * s2.powi(2)) * time) / vol_sqrt_t;
+
+    let discount: f64 = (-1.0 * rate * time).exp();
+    let call_value: f64 = discount * (forward1 * n(d1) - forward2 * n(d2) - strike * n(d3));
+
+    // Puts follow from put-call parity on the spread
+    if call {
+        return call_value;
+    } else {
+        return call_value - discount * (forward1 - forward2 - strike);
+    }
+}
+
+
+
+// Price a call or put on the better or worse of two underlyings (Stulz)
+pub fn rainbow_option(pair: &AssetPair, strike: f64, time: f64, rate: f64, rainbow: Rainbow, call: bool) -> f64 {
+
+    let m = bivariate_normal_cdf;
+
+    let (s1, s2) = (pair.volatility1, pair.volatility2);
+    let volatility: f64 = pair.spread_volatility();
+    let rho1: f64 = (s1 - pair.correlation * s2) / volatility;
+    let rho2: f64 = (s2 - pair.correlation * s1) / volatility;
+
+    let asset1: f64 = pair.spot1 * ((pair.carry1 - rate) * time).exp();
+    let asset2: f64 = pair.spot2 * ((pair.carry2 - rate) * time).exp();
+    let cash: f64 = strike * (-1.0 * rate * time).exp();
+
+    let d: f64 = ((pair.spot1 / pair.spot2).ln() + (pair.carry1 - pair.carry2 + 0.5 * volatility.powi(2)) * time) / (volatility * time.sqrt());
+    let y1: f64 = ((pair.spot1 / strike).ln() + (pair.carry1 + 0.5 * s1.powi(2)) * time) / (s1 * time.sqrt());
+    let y2: f64 = ((pair.spot2 / strike).ln() + (pair.carry2 + 0.5 * s2.powi(2)) * time) / (s2 * time.sqrt());
+
+    let call_value: f64 = match rainbow {
+        Rainbow::WorstOf => asset1 * m(y1, -1.0 * d, -1.0 * rho1) + asset2 * m(y2, d - volatility * time.sqrt(), -1.0 * rho2)
+            - cash * m(y1 - s1 * time.sqrt(), y2 - s2 * time.sqrt(), pair.correlation),
+        Rainbow::BestOf => asset1 * m(y1, d, rho1) + asset2 * m(y2, -1.0 * d + volatility * time.sqrt(), rho2)
+            - cash * (1.0 - m(-1.0 * y1 + s1 * time.sqrt(), -1.0 * y2 + s2 * time.sqrt(), pair.correlation)),
+    };
+
+    if call {
+        return call_value;
+    }
+
+    // Puts follow from put-call parity against the value of receiving the best (or worst) of the two outright
+        // The best of two is the second plus the option to exchange it for the first, the worst is the first less that option
+    let exchange: f64 = exchange_option(pair, time, rate);
+    let underlying: f64 = match rainbow {
+        Rainbow::BestOf => asset2 + exchange,
+        Rainbow::WorstOf => asset1 - exchange,
+    };
+
+    return cash - underlying + call_value;
+}
+
+
+
+// Price a spread, exchange or rainbow payoff by correlated Monte Carlo
+    // A spread with a zero strike is the exchange option
+pub fn monte_carlo_two_asset(pair: &AssetPair, strike: f64, time: f64, rate: f64, rainbow: Option<Rainbow>, paths: u32, seed: Option<u64>, call: bool) -> Result<MonteCarloEstimate, String> {
+
+    let spots = [pair.spot1, pair.spot2];
+    let carries = [pair.carry1, pair.carry2];
+    let volatilities = [pair.volatility1, pair.volatility2];
+
+    return monte_carlo_terminal(&spots, &carries, &volatilities, &pair.correlation_matrix(), rate, time, paths, seed, |prices| {
+        match rainbow {
+            Some(Rainbow::BestOf) => {
+                let best: f64 = prices[0].max(prices[1]);
+                if call { (best - strike).max(0.0) } else { (strike - best).max(0.0) }
+            }
+            Some(Rainbow::WorstOf) => {
+                let worst: f64 = prices[0].min(prices[1]);
+                if call { (worst - strike).max(0.0) } else { (strike - worst).max(0.0) }
+            }
+            None => {
+                let spread: f64 = prices[0] - prices[1];
+                if call { (spread - strike).max(0.0) } else { (strike - spread).max(0.0) }
+            }
+        }
+    });
+}
@@ -0,0 +1,143 @@
+/*
+
+JDA Options Pricing
+/src/variance.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Modules
+use crate::provider::ChainQuote;
+
+
+
+// Model-free variance of one expiry (the VIX methodology), which is also the fair strike of a variance swap to that expiry
+#[derive(Debug, Clone)]
+pub struct ExpiryVariance {
+    pub time: f64,
+    pub rate: f64,
+    // Forward from put-call parity at the strike where calls and puts are closest in price
+    pub forward: f64,
+    // The first strike at or below the forward, where out-of-the-money puts give way to calls
+    pub atm_strike: f64,
+    // Annualised variance, the variance swap fair strike in variance units
+    pub variance: f64,
+    // (strike, out-of-the-money mid) of every strike that went in, the put and call average at the at-the-money strike
+    pub strikes: Vec<(f64, f64)>,
+}
+
+impl ExpiryVariance {
+
+    // Fair strike in volatility points, as the index is quoted
+    pub fn volatility(&self) -> f64 {
+        return self.variance.max(0.0).sqrt();
+    }
+}
+
+
+
+// Variance of one expiry from its calls and puts, time in calendar years to the expiry and rate from the curve at it
+    // sigma^2 = 2/T sum dK/K^2 e^(RT) Q(K) - 1/T (F/K0 - 1)^2
+pub fn model_free_variance(calls: &[ChainQuote], puts: &[ChainQuote], time: f64, rate: f64) -> Result<ExpiryVariance, String> {
+
+    if time <= 0.0 {
+        return Err("the time to expiry must be positive".to_string());
+    }
+
+    let growth: f64 = (rate * time).exp();
+
+    // Forward at the strike with the smallest call and put difference
+    let (parity_strike, difference) = calls.iter()
+        .filter_map(|call| {
+            let put = puts.iter().find(|put| put.strike == call.strike)?;
+            Some((call.strike, call.mid_price()? - put.mid_price()?))
+        })
+        .min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+        .ok_or("the forward needs a call and a put quoted at the same strike")?;
+
+    let forward: f64 = parity_strike + growth * difference;
+
+    let mut strikes: Vec<f64> = calls.iter().chain(puts.iter()).map(|quote| quote.strike).filter(|strike| *strike > 0.0).collect();
+    strikes.sort_by(|a, b| a.total_cmp(b));
+    strikes.dedup();
+
+    let atm_strike: f64 = strikes.iter().rev().find(|strike| **strike <= forward).copied().ok_or(format!("no strike is listed below the forward {:.4}", forward))?;
+
+    // Out-of-the-money quotes walking away from the at-the-money strike, skipping zero bids and stopping after two in a row
+    let walk = |quotes: &[ChainQuote], outward: &mut dyn Iterator<Item = &f64>| -> Vec<(f64, f64)> {
+        let mut selected: Vec<(f64, f64)> = Vec::new();
+        let mut zero_bids: usize = 0;
+        for strike in outward {
+            let quote = quotes.iter().find(|quote| quote.strike == *strike);
+            match quote.filter(|quote| quote.bid.unwrap_or(0.0) > 0.0).and_then(|quote| quote.mid_price()) {
+                Some(mid) => {
+                    zero_bids = 0;
+                    selected.push((*strike, mid));
+                }
+                None => {
+                    zero_bids += 1;
+                    if zero_bids == 2 { break; }
+                }
+            }
+        }
+        return selected;
+    };
+
+    let mut selected: Vec<(f64, f64)> = walk(puts, &mut strikes.iter().rev().filter(|strike| **strike < atm_strike));
+    selected.reverse();
+
+    // Both sides at the at-the-money strike
+    let at_the_money = |quotes: &[ChainQuote]| quotes.iter().find(|quote| quote.strike == atm_strike).and_then(|quote| quote.mid_price());
+    match (at_the_money(calls), at_the_money(puts)) {
+        (Some(call), Some(put)) => selected.push((atm_strike, 0.5 * (call + put))),
+        _ => return Err(format!("the at-the-money strike {} needs both a call and a put quote", atm_strike)),
+    }
+
+    selected.extend(walk(calls, &mut strikes.iter().filter(|strike| **strike > atm_strike)));
+
+    if selected.len() < 3 {
+        return Err(format!("only {} strikes have bids, too few to integrate over", selected.len()));
+    }
+
+    // Strike spacing is half the distance between neighbours, or the distance to the one neighbour at the ends
+    let last: usize = selected.len() - 1;
+    let mut total: f64 = 0.0;
+    for i in 0..=last {
+        let spacing: f64 = match i {
+            0 => selected[1].0 - selected[0].0,
+            _ if i == last => selected[last].0 - selected[last - 1].0,
+            _ => 0.5 * (selected[i + 1].0 - selected[i - 1].0),
+        };
+        total += spacing / selected[i].0.powi(2) * growth * selected[i].1;
+    }
+
+    let variance: f64 = 2.0 / time * total - (forward / atm_strike - 1.0).powi(2) / time;
+
+    return Ok(ExpiryVariance { time, rate, forward, atm_strike, variance, strikes: selected });
+}
+
+
+
+// Constant maturity variance at a horizon in years, linear in total variance between the expiries either side
+    // Terms are (time, variance) sorted by time, past either end the nearest term's variance is held flat
+    // Returns the variance with the near and far terms and the weight on the near one
+pub fn interpolate_variance(terms: &[(f64, f64)], horizon: f64) -> Option<(f64, usize, usize, f64)> {
+
+    if terms.is_empty() || horizon <= 0.0 {
+        return None;
+    }
+
+    let far: usize = match terms.iter().position(|(time, _)| *time >= horizon) {
+        Some(0) => return Some((terms[0].1, 0, 0, 1.0)),
+        Some(far) => far,
+        None => return Some((terms[terms.len() - 1].1, terms.len() - 1, terms.len() - 1, 1.0)),
+    };
+    let near: usize = far - 1;
+
+    let ((t1, v1), (t2, v2)) = (terms[near], terms[far]);
+    let weight: f64 = (t2 - horizon) / (t2 - t1);
+
+    return Some(((t1 * v1 * weight + t2 * v2 * (1.0 - weight)) / horizon, near, far, weight));
+}
@@ -0,0 +1,111 @@
+/*
+
+JDA Options Pricing
+/src/vol_cube.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use std::fs;
+
+
+
+// Struct to hold a volatility cube quoted by option expiry, underlying tenor and strike
+    // Vols are stored on the full grid of the three axes and interpolated linearly along each, held flat beyond the ends
+#[derive(Debug, Clone)]
+pub struct VolCube {
+    pub expiries: Vec<f64>,
+    pub tenors: Vec<f64>,
+    pub strikes: Vec<f64>,
+    vols: Vec<f64>,
+}
+
+impl VolCube {
+
+    // Build a cube from (expiry, tenor, strike, vol) points, which must cover every combination of the three axes
+    pub fn new(points: &[(f64, f64, f64, f64)]) -> Result<VolCube, String> {
+
+        if points.is_empty() {
+            return Err("the vol cube has no points".to_string());
+        }
+
+        let axis = |pick: fn(&(f64, f64, f64, f64)) -> f64| -> Vec<f64> {
+            let mut values: Vec<f64> = points.iter().map(pick).collect();
+            values.sort_by(|a, b| a.total_cmp(b));
+            values.dedup();
+            values
+        };
+
+        let expiries = axis(|p| p.0);
+        let tenors = axis(|p| p.1);
+        let strikes = axis(|p| p.2);
+
+        let mut vols: Vec<Option<f64>> = vec![None; expiries.len() * tenors.len() * strikes.len()];
+        for (expiry, tenor, strike, vol) in points {
+            let i = expiries.iter().position(|e| e == expiry).unwrap_or(0);
+            let j = tenors.iter().position(|t| t == tenor).unwrap_or(0);
+            let k = strikes.iter().position(|s| s == strike).unwrap_or(0);
+            vols[(i * tenors.len() + j) * strikes.len() + k] = Some(*vol);
+        }
+
+        if vols.iter().any(|vol| vol.is_none()) {
+            return Err("the vol cube is missing points, every expiry, tenor and strike combination needs a vol".to_string());
+        }
+
+        return Ok(VolCube { expiries, tenors, strikes, vols: vols.into_iter().flatten().collect() });
+    }
+
+    // Load a cube from a CSV file of expiry,tenor,strike,vol rows (a header row is skipped)
+    pub fn from_csv(path: &str) -> Result<VolCube, String> {
+
+        let text: String = fs::read_to_string(path).map_err(|e| format!("unable to read {}, {}", path, e))?;
+        let mut points: Vec<(f64, f64, f64, f64)> = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+
+            let line: &str = line.trim();
+            if line.is_empty() { continue; }
+
+            let fields: Result<Vec<f64>, _> = line.split(',').map(|field| field.trim().parse::<f64>()).collect();
+            match fields {
+                Ok(fields) if fields.len() == 4 => points.push((fields[0], fields[1], fields[2], fields[3])),
+                Err(_) if number == 0 => continue,
+                _ => return Err(format!("line {} of {} is not expiry,tenor,strike,vol", number + 1, path)),
+            }
+        }
+
+        return VolCube::new(&points);
+    }
+
+    // Interpolated vol for an option expiring at expiry on an underlying with the given tenor and strike
+    pub fn volatility(&self, expiry: f64, tenor: f64, strike: f64) -> f64 {
+
+        let (i0, i1, wi) = bracket(&self.expiries, expiry);
+        let (j0, j1, wj) = bracket(&self.tenors, tenor);
+        let (k0, k1, wk) = bracket(&self.strikes, strike);
+
+        let at = |i: usize, j: usize, k: usize| self.vols[(i * self.tenors.len() + j) * self.strikes.len() + k];
+        let along_strike = |i: usize, j: usize| (1.0 - wk) * at(i, j, k0) + wk * at(i, j, k1);
+        let along_tenor = |i: usize| (1.0 - wj) * along_strike(i, j0) + wj * along_strike(i, j1);
+
+        return (1.0 - wi) * along_tenor(i0) + wi * along_tenor(i1);
+    }
+}
+
+
+
+// Neighbouring points on an axis and the weight on the upper one, flat beyond the ends
+fn bracket(axis: &[f64], x: f64) -> (usize, usize, f64) {
+
+    let last: usize = axis.len() - 1;
+    if x <= axis[0] { return (0, 0, 0.0); }
+    if x >= axis[last] { return (last, last, 0.0); }
+
+    let upper: usize = axis.iter().position(|a| *a > x).unwrap_or(last);
+    let lower: usize = upper - 1;
+
+    return (lower, upper, (x - axis[lower]) / (axis[upper] - axis[lower]));
+}
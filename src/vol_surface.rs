@@ -0,0 +1,231 @@
+/*
+
+JDA Options Pricing
+/src/vol_surface.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::ValueEnum;
+use chrono::NaiveDate;
+
+// Modules
+use crate::solver::nelder_mead;
+use crate::svi::Svi;
+
+
+
+// How each expiry's smile is parameterised
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SurfaceModel {
+    // Independent raw SVI fit per expiry
+    Svi,
+    // Surface SVI, one rho and power-law curvature shared by every expiry, anchored to each expiry's ATM total variance
+    Ssvi,
+}
+
+
+
+// Quotes for one expiry in (log-moneyness, implied vol)
+#[derive(Debug, Clone)]
+pub struct SliceQuotes {
+    pub expiration: NaiveDate,
+    pub time: f64,
+    pub forward: f64,
+    pub quotes: Vec<(f64, f64)>,
+}
+
+// Fitted smile for one expiry, SSVI slices are stored in their equivalent raw SVI form
+#[derive(Debug, Clone)]
+pub struct Slice {
+    pub expiration: NaiveDate,
+    pub time: f64,
+    pub forward: f64,
+    pub svi: Svi,
+    // Root mean square error of the fitted vols
+    pub fit_error: f64,
+    pub quotes: Vec<(f64, f64)>,
+}
+
+impl Slice {
+
+    pub fn volatility(&self, k: f64) -> f64 {
+        return self.svi.volatility(k, self.time);
+    }
+}
+
+
+
+// Static arbitrage found in the fitted surface
+#[derive(Debug, Clone)]
+pub enum Violation {
+    // Negative density between two log-moneyness levels on an expiry, with the most negative g(k)
+    Butterfly { expiration: NaiveDate, from: f64, to: f64, worst: f64 },
+    // Total variance falling from one expiry to the next between two log-moneyness levels, with the largest drop
+    Calendar { first: NaiveDate, second: NaiveDate, from: f64, to: f64, worst: f64 },
+}
+
+
+
+// Implied vol surface in (log-moneyness, maturity)
+#[derive(Debug, Clone)]
+pub struct VolSurface {
+    pub model: SurfaceModel,
+    pub slices: Vec<Slice>,
+    // Shared SSVI parameters (rho, eta, gamma)
+    pub ssvi: Option<(f64, f64, f64)>,
+    // Expiries left out for having too few quotes to fit
+    pub skipped: Vec<NaiveDate>,
+}
+
+impl VolSurface {
+
+    // Fit every expiry, slices with fewer than five quotes are skipped
+    pub fn fit(mut slices: Vec<SliceQuotes>, model: SurfaceModel) -> Result<VolSurface, String> {
+
+        slices.sort_by(|a, b| a.time.total_cmp(&b.time));
+        let (usable, skipped): (Vec<SliceQuotes>, Vec<SliceQuotes>) = slices.into_iter().partition(|s| s.quotes.len() >= 5 && s.time > 0.0);
+
+        if usable.is_empty() {
+            return Err("no expiration has the five quotes needed to fit a smile".to_string());
+        }
+
+        let skipped: Vec<NaiveDate> = skipped.iter().map(|s| s.expiration).collect();
+        let total_variance = |slice: &SliceQuotes| -> Vec<(f64, f64)> { slice.quotes.iter().map(|(k, v)| (*k, v * v * slice.time)).collect() };
+
+        let (svis, ssvi): (Vec<Svi>, Option<(f64, f64, f64)>) = match model {
+            SurfaceModel::Svi => {
+                let mut svis: Vec<Svi> = Vec::new();
+                for slice in usable.iter() {
+                    let (svi, _) = Svi::fit(&total_variance(slice)).map_err(|e| format!("{} for {}", e, slice.expiration))?;
+                    svis.push(svi);
+                }
+                (svis, None)
+            }
+            SurfaceModel::Ssvi => {
+                let thetas: Vec<f64> = usable.iter().map(|slice| at_the_money(&total_variance(slice))).collect();
+                let points: Vec<Vec<(f64, f64)>> = usable.iter().map(total_variance).collect();
+                let (rho, eta, gamma) = fit_ssvi(&thetas, &points)?;
+                (thetas.iter().map(|theta| ssvi_slice(*theta, rho, eta, gamma)).collect(), Some((rho, eta, gamma)))
+            }
+        };
+
+        let slices: Vec<Slice> = usable.into_iter().zip(svis).map(|(quotes, svi)| {
+            let fit_error: f64 = (quotes.quotes.iter().map(|(k, v)| (svi.volatility(*k, quotes.time) - v).powi(2)).sum::<f64>() / (quotes.quotes.len() as f64)).sqrt();
+            Slice { expiration: quotes.expiration, time: quotes.time, forward: quotes.forward, svi, fit_error, quotes: quotes.quotes }
+        }).collect();
+
+        return Ok(VolSurface { model, slices, ssvi, skipped });
+    }
+
+    // Scan the surface for butterfly (negative density) and calendar (falling total variance) arbitrage on a grid of log-moneyness
+    pub fn arbitrage(&self, grid: &[f64]) -> Vec<Violation> {
+
+        let mut violations: Vec<Violation> = Vec::new();
+
+        for slice in self.slices.iter() {
+            for (from, to, worst) in runs(grid, |k| slice.svi.butterfly(k)) {
+                violations.push(Violation::Butterfly { expiration: slice.expiration, from, to, worst });
+            }
+        }
+
+        for pair in self.slices.windows(2) {
+            for (from, to, worst) in runs(grid, |k| pair[1].svi.total_variance(k) - pair[0].svi.total_variance(k)) {
+                violations.push(Violation::Calendar { first: pair[0].expiration, second: pair[1].expiration, from, to, worst });
+            }
+        }
+
+        return violations;
+    }
+}
+
+
+
+// Stretches of the grid where a function is negative, as (from, to, most negative value)
+fn runs<F: Fn(f64) -> f64>(grid: &[f64], f: F) -> Vec<(f64, f64, f64)> {
+
+    let mut found: Vec<(f64, f64, f64)> = Vec::new();
+    let mut current: Option<(f64, f64, f64)> = None;
+
+    for k in grid {
+        let value: f64 = f(*k);
+        if value < -1e-10 {
+            current = Some(match current {
+                Some((from, _, worst)) => (from, *k, worst.min(value)),
+                None => (*k, *k, value),
+            });
+        } else if let Some(run) = current.take() {
+            found.push(run);
+        }
+    }
+    found.extend(current);
+
+    return found;
+}
+
+
+
+// ATM total variance of a slice by linear interpolation of its quotes at k = 0
+fn at_the_money(points: &[(f64, f64)]) -> f64 {
+
+    let mut points: Vec<(f64, f64)> = points.to_vec();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let upper: usize = points.iter().position(|p| p.0 >= 0.0).unwrap_or(points.len() - 1);
+    if upper == 0 { return points[0].1; }
+
+    let (k0, w0) = points[upper - 1];
+    let (k1, w1) = points[upper];
+    if k1 <= 0.0 { return w1; }
+
+    return w0 + (w1 - w0) * (0.0 - k0) / (k1 - k0);
+}
+
+
+
+// Power-law SSVI curvature phi(theta) = eta / (theta^gamma * (1 + theta)^(1 - gamma))
+fn phi(theta: f64, eta: f64, gamma: f64) -> f64 {
+    return eta / (theta.powf(gamma) * (1.0 + theta).powf(1.0 - gamma));
+}
+
+// The raw SVI slice equal to an SSVI slice with ATM total variance theta
+fn ssvi_slice(theta: f64, rho: f64, eta: f64, gamma: f64) -> Svi {
+    let phi: f64 = phi(theta, eta, gamma);
+    return Svi {
+        a: 0.5 * theta * (1.0 - rho * rho),
+        b: 0.5 * theta * phi,
+        rho,
+        m: -1.0 * rho / phi,
+        sigma: (1.0 - rho * rho).sqrt() / phi,
+    };
+}
+
+// Fit the shared SSVI parameters to every slice's total variances
+    // eta * (1 + |rho|) <= 2 with gamma in (0, 1/2] keeps the power-law surface free of butterfly arbitrage (Gatheral-Jacquier), and increasing thetas keep it free of calendar arbitrage
+fn fit_ssvi(thetas: &[f64], points: &[Vec<(f64, f64)>]) -> Result<(f64, f64, f64), String> {
+
+    if thetas.iter().any(|theta| *theta <= 0.0) {
+        return Err("an expiration has no positive at-the-money variance".to_string());
+    }
+
+    let objective = |x: &[f64]| -> f64 {
+        let (rho, eta, gamma) = (x[0], x[1], x[2]);
+        if rho.abs() >= 1.0 || eta <= 0.0 || gamma <= 0.0 || gamma > 0.5 || eta * (1.0 + rho.abs()) > 2.0 {
+            return 1e10;
+        }
+        thetas.iter().zip(points).map(|(theta, slice)| {
+            let svi: Svi = ssvi_slice(*theta, rho, eta, gamma);
+            slice.iter().map(|(k, w)| ((svi.total_variance(*k) - w) / theta).powi(2)).sum::<f64>()
+        }).sum()
+    };
+
+    let (x, value) = nelder_mead(objective, &[-0.3, 0.8, 0.25], &[0.2, 0.3, 0.15], 1e-12, 4000);
+    if value >= 1e10 {
+        return Err("unable to fit an SSVI surface to the quotes".to_string());
+    }
+
+    return Ok((x[0], x[1], x[2]));
+}
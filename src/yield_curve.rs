@@ -0,0 +1,391 @@
+/*
+
+JDA Options Pricing
+/src/yield_curve.rs
+JohnDavid Abe
+
+*/
+
+
+
+// Packages
+use clap::{Args, ValueEnum};
+use std::fs;
+
+// Modules
+use crate::solver::{bisection, solve_linear};
+
+
+
+// How zero rates are filled in between the curve's pillars
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Interpolation {
+    // Straight lines between zero rates
+    LinearZero,
+    // Straight lines between log discount factors (piecewise flat forwards)
+    LogLinearDiscount,
+    // Cubic through the zero rates that never overshoots between pillars (Fritsch-Carlson)
+    MonotoneCubic,
+    // Nelson-Siegel-Svensson curve fitted to the bootstrapped zero rates
+    NelsonSiegelSvensson,
+}
+
+
+
+// Risk free rate for the pricers, flat or read off a curve at the option's maturity
+#[derive(Debug, Clone, Args)]
+pub struct RateArgs {
+
+    // Flat continuously compounded risk free rate
+    #[arg(short = 'r', long = "rate", required_unless_present = "curve", conflicts_with = "curve", allow_negative_numbers = true)]
+    pub rate: Option<f64>,
+
+    // CSV file of tenor,par yield rows to bootstrap the curve from instead
+    #[arg(long = "curve")]
+    pub curve: Option<String>,
+
+    #[arg(long = "interpolation", value_enum, default_value_t = Interpolation::MonotoneCubic)]
+    pub interpolation: Interpolation,
+}
+
+impl RateArgs {
+
+    // The flat rate, or the curve's zero rate to a maturity in years
+    pub fn zero_rate(&self, time: f64) -> Result<f64, String> {
+        let curve: YieldCurve = YieldCurve::load(self.rate, self.curve.clone(), self.interpolation)?;
+        return Ok(curve.zero_rate(time));
+    }
+}
+
+
+
+// Convert a rate compounded frequency times a year to a continuously compounded rate
+pub fn continuous_from_compounded(rate: f64, frequency: u32) -> f64 {
+    let frequency: f64 = frequency as f64;
+    return frequency * (1.0 + rate / frequency).ln();
+}
+
+
+
+// Struct to hold a zero curve, as continuously compounded zero rates at a set of pillar times
+    // Rates are held flat before the first pillar and after the last (except for Nelson-Siegel-Svensson, which is defined everywhere)
+#[derive(Debug, Clone)]
+pub struct YieldCurve {
+    pub times: Vec<f64>,
+    pub zero_rates: Vec<f64>,
+    pub interpolation: Interpolation,
+    slopes: Vec<f64>,
+    parameters: [f64; 6],
+}
+
+impl YieldCurve {
+
+    // A curve with the same rate at every maturity
+    pub fn flat(rate: f64) -> YieldCurve {
+        return YieldCurve { times: vec![1.0], zero_rates: vec![rate], interpolation: Interpolation::LinearZero, slopes: Vec::new(), parameters: [0.0; 6] };
+    }
+
+    // Build a curve straight from continuously compounded zero rates
+    pub fn from_zero_rates(times: Vec<f64>, zero_rates: Vec<f64>, interpolation: Interpolation) -> Result<YieldCurve, String> {
+
+        if times.is_empty() || times.len() != zero_rates.len() {
+            return Err("the curve needs a zero rate for every pillar".to_string());
+        }
+        if times.windows(2).any(|w| w[1] <= w[0]) || times[0] <= 0.0 {
+            return Err("the curve's pillars must be positive and increasing".to_string());
+        }
+
+        let mut curve = YieldCurve { times, zero_rates, interpolation, slopes: Vec::new(), parameters: [0.0; 6] };
+        curve.refresh()?;
+
+        return Ok(curve);
+    }
+
+    // Bootstrap zero rates from par yields of bonds paying coupons frequency times a year, such as the Treasury constant maturity series
+        // Each pillar's zero rate is solved so its par bond prices at 1, using the chosen interpolation for the coupons in between
+        // Non-local interpolation means later pillars move earlier coupons, so the solve is repeated over the whole curve until it settles
+    pub fn bootstrap(par_yields: &[(f64, f64)], frequency: u32, interpolation: Interpolation) -> Result<YieldCurve, String> {
+
+        let mut pillars: Vec<(f64, f64)> = par_yields.to_vec();
+        pillars.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let times: Vec<f64> = pillars.iter().map(|p| p.0).collect();
+        let guesses: Vec<f64> = pillars.iter().map(|p| continuous_from_compounded(p.1, frequency)).collect();
+
+        // Nelson-Siegel-Svensson is fitted afterwards, so its pillars are bootstrapped on straight zero lines
+        let knots: Interpolation = if interpolation == Interpolation::NelsonSiegelSvensson { Interpolation::LinearZero } else { interpolation };
+        let mut curve = YieldCurve::from_zero_rates(times, guesses, knots)?;
+
+        let period: f64 = 1.0 / (frequency as f64);
+
+        for _ in 0..50 {
+
+            let mut change: f64 = 0.0;
+
+            for (i, (tenor, par)) in pillars.iter().enumerate() {
+
+                // Price of the par bond maturing at this pillar when its zero rate is z
+                let mispricing = |z: f64| -> f64 {
+                    let mut trial = curve.clone();
+                    trial.zero_rates[i] = z;
+                    if trial.refresh().is_err() { return f64::NAN; }
+
+                    // Bills inside one coupon period pay once at maturity
+                    if *tenor <= period + 1e-9 {
+                        return (1.0 + par * tenor) * trial.discount(*tenor) - 1.0;
+                    }
+
+                    let mut price: f64 = trial.discount(*tenor);
+                    let mut time: f64 = *tenor;
+                    while time > 1e-9 {
+                        price += par * period * trial.discount(time);
+                        time -= period;
+                    }
+                    price - 1.0
+                };
+
+                let zero: f64 = bisection(mispricing, -0.5, 1.0, 1e-14).ok_or(format!("unable to bootstrap the {} year pillar", tenor))?;
+
+                change = change.max((zero - curve.zero_rates[i]).abs());
+                curve.zero_rates[i] = zero;
+                curve.refresh()?;
+            }
+
+            if change < 1e-12 { break; }
+        }
+
+        curve.interpolation = interpolation;
+        curve.refresh()?;
+
+        return Ok(curve);
+    }
+
+    // Bootstrap a curve from a CSV file of tenor,par yield rows, in years and decimals with semiannual coupons (a header row is skipped)
+    pub fn from_csv(path: &str, interpolation: Interpolation) -> Result<YieldCurve, String> {
+
+        let text: String = fs::read_to_string(path).map_err(|e| format!("unable to read {}, {}", path, e))?;
+        let mut pillars: Vec<(f64, f64)> = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+
+            let line: &str = line.trim();
+            if line.is_empty() { continue; }
+
+            let fields: Result<Vec<f64>, _> = line.split(',').map(|field| field.trim().parse::<f64>()).collect();
+            match fields {
+                Ok(fields) if fields.len() == 2 => pillars.push((fields[0], fields[1])),
+                Err(_) if number == 0 => continue,
+                _ => return Err(format!("line {} of {} is not tenor,yield", number + 1, path)),
+            }
+        }
+
+        return YieldCurve::bootstrap(&pillars, 2, interpolation);
+    }
+
+    // Pick the curve from a flat rate or a par yield file, exactly one of which should be given
+    pub fn load(rate: Option<f64>, path: Option<String>, interpolation: Interpolation) -> Result<YieldCurve, String> {
+        match (rate, path) {
+            (Some(rate), None) => Ok(YieldCurve::flat(rate)),
+            (None, Some(path)) => YieldCurve::from_csv(&path, interpolation),
+            _ => Err("give either a rate or a curve file".to_string()),
+        }
+    }
+
+    // Continuously compounded zero rate to a maturity
+    pub fn zero_rate(&self, time: f64) -> f64 {
+
+        if self.interpolation == Interpolation::NelsonSiegelSvensson {
+            return nelson_siegel_svensson(&self.parameters, time);
+        }
+
+        let last: usize = self.times.len() - 1;
+        if time <= self.times[0] { return self.zero_rates[0]; }
+        if time >= self.times[last] { return self.zero_rates[last]; }
+
+        let k: usize = self.times.iter().position(|t| *t > time).unwrap_or(last) - 1;
+        let (t0, t1) = (self.times[k], self.times[k + 1]);
+        let (z0, z1) = (self.zero_rates[k], self.zero_rates[k + 1]);
+        let w: f64 = (time - t0) / (t1 - t0);
+
+        match self.interpolation {
+            Interpolation::LinearZero => z0 + w * (z1 - z0),
+            Interpolation::LogLinearDiscount => (z0 * t0 + w * (z1 * t1 - z0 * t0)) / time,
+            Interpolation::MonotoneCubic => {
+                // Cubic Hermite between the pillars with the filtered slopes
+                let h: f64 = t1 - t0;
+                let (h00, h10, h01, h11) = (2.0 * w.powi(3) - 3.0 * w.powi(2) + 1.0, w.powi(3) - 2.0 * w.powi(2) + w, -2.0 * w.powi(3) + 3.0 * w.powi(2), w.powi(3) - w.powi(2));
+                h00 * z0 + h10 * h * self.slopes[k] + h01 * z1 + h11 * h * self.slopes[k + 1]
+            }
+            Interpolation::NelsonSiegelSvensson => unreachable!(),
+        }
+    }
+
+    // Price today of 1 paid at a maturity
+    pub fn discount(&self, time: f64) -> f64 {
+        return (-1.0 * self.zero_rate(time) * time).exp();
+    }
+
+    // Continuously compounded forward rate between two times
+    pub fn forward_rate(&self, start: f64, end: f64) -> f64 {
+        return (self.discount(start) / self.discount(end)).ln() / (end - start);
+    }
+
+    // Recompute whatever the interpolation needs from the pillars
+    fn refresh(&mut self) -> Result<(), String> {
+        match self.interpolation {
+            Interpolation::MonotoneCubic => self.slopes = monotone_slopes(&self.times, &self.zero_rates),
+            Interpolation::NelsonSiegelSvensson => self.parameters = fit_nelson_siegel_svensson(&self.times, &self.zero_rates)?,
+            _ => {}
+        }
+        return Ok(());
+    }
+}
+
+
+
+// Slopes at each pillar for a cubic that stays monotone between pillars (Fritsch-Carlson)
+fn monotone_slopes(times: &[f64], values: &[f64]) -> Vec<f64> {
+
+    let n: usize = times.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+
+    let secants: Vec<f64> = (0..n - 1).map(|k| (values[k + 1] - values[k]) / (times[k + 1] - times[k])).collect();
+
+    let mut slopes: Vec<f64> = vec![0.0; n];
+    slopes[0] = secants[0];
+    slopes[n - 1] = secants[n - 2];
+    for k in 1..n - 1 {
+        slopes[k] = if secants[k - 1] * secants[k] <= 0.0 { 0.0 } else { 0.5 * (secants[k - 1] + secants[k]) };
+    }
+
+    // Shrink slopes that would make the cubic overshoot
+    for k in 0..n - 1 {
+        if secants[k] == 0.0 {
+            slopes[k] = 0.0;
+            slopes[k + 1] = 0.0;
+            continue;
+        }
+        let alpha: f64 = slopes[k] / secants[k];
+        let beta: f64 = slopes[k + 1] / secants[k];
+        let size: f64 = alpha.powi(2) + beta.powi(2);
+        if size > 9.0 {
+            let scale: f64 = 3.0 / size.sqrt();
+            slopes[k] = scale * alpha * secants[k];
+            slopes[k + 1] = scale * beta * secants[k];
+        }
+    }
+
+    return slopes;
+}
+
+
+
+// Nelson-Siegel-Svensson zero rate with parameters [beta0, beta1, beta2, beta3, tau1, tau2]
+fn nelson_siegel_svensson(parameters: &[f64; 6], time: f64) -> f64 {
+    let basis = nelson_siegel_svensson_basis(time, parameters[4], parameters[5]);
+    return (0..4).map(|i| parameters[i] * basis[i]).sum();
+}
+
+// The level, slope and two curvature factors of the curve at a time
+fn nelson_siegel_svensson_basis(time: f64, tau1: f64, tau2: f64) -> [f64; 4] {
+
+    let loading = |tau: f64| if time <= 0.0 { 1.0 } else { (1.0 - (-1.0 * time / tau).exp()) / (time / tau) };
+    let decay = |tau: f64| if time <= 0.0 { 1.0 } else { (-1.0 * time / tau).exp() };
+
+    return [1.0, loading(tau1), loading(tau1) - decay(tau1), loading(tau2) - decay(tau2)];
+}
+
+
+
+// Fit Nelson-Siegel-Svensson to zero rates by least squares
+    // The betas enter linearly, so they are solved exactly for each pair of decay times on a grid and the best fit is kept
+fn fit_nelson_siegel_svensson(times: &[f64], zero_rates: &[f64]) -> Result<[f64; 6], String> {
+
+    if times.len() < 4 {
+        return Err("Nelson-Siegel-Svensson needs at least four pillars".to_string());
+    }
+
+    let grid: Vec<f64> = (0..30).map(|i| 0.1 * (300.0_f64).powf((i as f64) / 29.0)).collect();
+    let mut best: Option<(f64, [f64; 6])> = None;
+
+    for (i, tau1) in grid.iter().enumerate() {
+        for tau2 in grid.iter().skip(i + 1) {
+
+            // Normal equations of the linear least squares for the betas
+            let mut matrix = [[0.0; 4]; 4];
+            let mut vector = [0.0; 4];
+            for (time, zero) in times.iter().zip(zero_rates) {
+                let basis = nelson_siegel_svensson_basis(*time, *tau1, *tau2);
+                for r in 0..4 {
+                    vector[r] += basis[r] * zero;
+                    for c in 0..4 {
+                        matrix[r][c] += basis[r] * basis[c];
+                    }
+                }
+            }
+
+            let Some(betas) = solve_linear(matrix, vector) else { continue; };
+            let parameters: [f64; 6] = [betas[0], betas[1], betas[2], betas[3], *tau1, *tau2];
+            let error: f64 = times.iter().zip(zero_rates).map(|(t, z)| (nelson_siegel_svensson(&parameters, *t) - z).powi(2)).sum();
+
+            if best.is_none_or(|(best_error, _)| error < best_error) {
+                best = Some((error, parameters));
+            }
+        }
+    }
+
+    return best.map(|(_, parameters)| parameters).ok_or("unable to fit Nelson-Siegel-Svensson".to_string());
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // Price of a semiannual par bond off the curve, a bill inside six months pays once at maturity
+    fn par_bond_price(curve: &YieldCurve, tenor: f64, par: f64) -> f64 {
+        if tenor <= 0.5 + 1e-9 {
+            return (1.0 + par * tenor) * curve.discount(tenor);
+        }
+        let mut price: f64 = curve.discount(tenor);
+        let mut time: f64 = tenor;
+        while time > 1e-9 {
+            price += par * 0.5 * curve.discount(time);
+            time -= 0.5;
+        }
+        return price;
+    }
+
+    #[test]
+    fn treasury_curve_round_trips() {
+        let path: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/curves/treasury.csv");
+        let pillars: Vec<(f64, f64)> = fs::read_to_string(path).unwrap().lines().skip(1)
+            .map(|line| {
+                let (tenor, par) = line.split_once(',').unwrap();
+                (tenor.parse().unwrap(), par.parse().unwrap())
+            })
+            .collect();
+
+        for interpolation in Interpolation::value_variants() {
+            let curve: YieldCurve = YieldCurve::from_csv(path, *interpolation).unwrap();
+
+            // The fitted Nelson-Siegel-Svensson curve only passes near its pillars, so it is held to half a point
+            let tolerance: f64 = if *interpolation == Interpolation::NelsonSiegelSvensson { 0.005 } else { 1e-10 };
+            for (tenor, par) in pillars.iter() {
+                let price: f64 = par_bond_price(&curve, *tenor, *par);
+                assert!((price - 1.0).abs() < tolerance, "{:?} prices the {} year par bond at {}", interpolation, tenor, price);
+            }
+
+            let mut previous: f64 = 1.0;
+            for step in 1..=360 {
+                let discount: f64 = curve.discount((step as f64) / 12.0);
+                assert!(discount < previous, "{:?} discount rises at month {}", interpolation, step);
+                previous = discount;
+            }
+        }
+    }
+}